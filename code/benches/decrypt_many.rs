@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use abe_cubed::policy::{Policy, UserAttribute};
+use abe_cubed::scheme::{Abe, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme};
+use criterion::measurement::Measurement;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion};
+
+const USER_ID: &str = "GLOBAL_USER_ID";
+const NUM_CTS: [usize; 3] = [1, 10, 50];
+
+// Every ciphertext negates attributes of both labels, of which the key holds several
+const POLICIES: [&str; 3] = [
+    "!A.x:9 & !B.y:9 & A.x:0",
+    "!A.x:8 & !B.y:9 & B.y:1",
+    "(!A.x:9 & A.x:2) | (!B.y:8 & B.y:3)",
+];
+
+fn config_benchmarks<'a, M: Measurement>(group: &mut BenchmarkGroup<'a, M>) {
+    group.measurement_time(Duration::new(3, 0));
+    group.sample_size(40);
+    group.warm_up_time(Duration::new(1, 0));
+    group.sampling_mode(criterion::SamplingMode::Flat);
+}
+
+// `decrypt_many` against decrypting the same ciphertexts one by one
+pub fn bench_decrypt_many<T: Scheme>(scheme: T, c: &mut Criterion) {
+    let mut group = c.benchmark_group("decrypt_many");
+    config_benchmarks(&mut group);
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let name = abe.scheme.get_name();
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let user_attrs: Vec<UserAttribute> = (0..5)
+        .flat_map(|i| {
+            let attr = i.to_string();
+            [
+                UserAttribute::new("A", "x", &attr),
+                UserAttribute::new("B", "y", &attr),
+            ]
+        })
+        .collect();
+    let usk = abe.keygen(&mut rng, USER_ID, &msk, &user_attrs).unwrap();
+    for num_cts in NUM_CTS {
+        let cts: Vec<_> = (0..num_cts)
+            .map(|i| {
+                let policy = Policy::parse(POLICIES[i % POLICIES.len()]).unwrap();
+                abe.encrypt(&mut rng, &mpk, &policy).1
+            })
+            .collect();
+        let ct_refs: Vec<_> = cts.iter().collect();
+        group.bench_function(format!("{}/loop/{}", name, num_cts), |b| {
+            b.iter(|| {
+                ct_refs
+                    .iter()
+                    .map(|ct| abe.decrypt(&usk, black_box(ct)))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_function(format!("{}/decrypt_many/{}", name, num_cts), |b| {
+            b.iter(|| abe.decrypt_many(&usk, black_box(&ct_refs)))
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_decrypt_many_all(c: &mut Criterion) {
+    bench_decrypt_many(Opt0::new(), c);
+    bench_decrypt_many(Opt1::new(), c);
+    bench_decrypt_many(Opt2::new(), c);
+    bench_decrypt_many(Opt3::new(), c);
+    bench_decrypt_many(Opt4::new(), c);
+    bench_decrypt_many(Opt5::new(), c);
+    bench_decrypt_many(Opt6::new(), c);
+}
+
+criterion_group!(benches, bench_decrypt_many_all);
+criterion_main!(benches);
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use abe_cubed::policy::Policy;
+use abe_cubed::scheme::{Abe, EncryptionPool, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme};
+use criterion::measurement::Measurement;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkGroup, Criterion};
+
+const POLICY_SIZES: [usize; 3] = [10, 20, 40];
+
+fn config_benchmarks<'a, M: Measurement>(group: &mut BenchmarkGroup<'a, M>) {
+    group.measurement_time(Duration::new(3, 0));
+    group.sample_size(40);
+    group.warm_up_time(Duration::new(1, 0));
+    group.sampling_mode(criterion::SamplingMode::Flat);
+}
+
+// A conjunction over two authorities with a distinct label per row, every fourth of which
+// is negated
+fn get_policy(size: usize) -> Policy {
+    let rows: Vec<String> = (0..size)
+        .map(|j| {
+            let not = if j % 4 == 3 { "!" } else { "" };
+            format!("{}{}.l{}:{}", not, ["A", "B"][j % 2], j, j % 3)
+        })
+        .collect();
+    Policy::parse(&rows.join(" & ")).unwrap()
+}
+
+// Plain encryption against its offline and online phases. The online phase is measured
+// with the labels hashed offline and, as "online_cold", with the labels left to it.
+pub fn bench_online_offline<T: Scheme>(scheme: T, c: &mut Criterion) {
+    let mut group = c.benchmark_group("online_offline");
+    config_benchmarks(&mut group);
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let name = abe.scheme.get_name();
+    let (_, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    for size in POLICY_SIZES {
+        let policy = get_policy(size);
+        group.bench_function(format!("{}/encrypt/{}", name, size), |b| {
+            b.iter(|| abe.encrypt(&mut rng, &mpk, black_box(&policy)))
+        });
+        group.bench_function(format!("{}/offline/{}", name, size), |b| {
+            b.iter_with_large_drop(|| {
+                let mut pool = EncryptionPool::new();
+                abe.fill_pool(&mut rng, &mut pool, &mpk, 1, size);
+                pool.hash_labels(black_box(&policy));
+                pool
+            })
+        });
+        for (descr, hash_labels) in [("online", true), ("online_cold", false)] {
+            group.bench_function(format!("{}/{}/{}", name, descr, size), |b| {
+                b.iter_batched(
+                    || {
+                        let mut pool = EncryptionPool::new();
+                        abe.fill_pool(&mut rng, &mut pool, &mpk, 1, size);
+                        if hash_labels {
+                            pool.hash_labels(&policy);
+                        }
+                        pool
+                    },
+                    |mut pool| abe.encrypt_online(&mut pool, &mpk, black_box(&policy)),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+pub fn bench_online_offline_all(c: &mut Criterion) {
+    bench_online_offline(Opt0::new(), c);
+    bench_online_offline(Opt1::new(), c);
+    bench_online_offline(Opt2::new(), c);
+    bench_online_offline(Opt3::new(), c);
+    bench_online_offline(Opt4::new(), c);
+    bench_online_offline(Opt5::new(), c);
+    bench_online_offline(Opt6::new(), c);
+}
+
+criterion_group!(benches, bench_online_offline_all);
+criterion_main!(benches);
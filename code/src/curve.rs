@@ -20,6 +20,13 @@ pub fn pairing(
     Bls12_381::pairing(p, q)
 }
 
+pub fn multi_pairing(
+    p: impl IntoIterator<Item = impl Into<<Bls12_381 as Pairing>::G1Prepared>>,
+    q: impl IntoIterator<Item = impl Into<<Bls12_381 as Pairing>::G2Prepared>>,
+) -> PairingOutput<Bls12_381> {
+    Bls12_381::multi_pairing(p, q)
+}
+
 // Flip the groups, i.e. each G is actually H and each H is actually G
 // pub use ark_bls12_381::{
 //     g2::Config as G1Config, Fq12 as Gt, Fr as ScalarField, G2Affine as GAffine,
@@ -35,3 +42,10 @@ pub fn pairing(
 //     -> PairingOutput<Bls12_381> {
 //     Bls12_381::pairing(q, p)
 // }
+
+// pub fn multi_pairing(
+//         p: impl IntoIterator<Item = impl Into<<Bls12_381 as Pairing>::G2Prepared>>,
+//         q: impl IntoIterator<Item = impl Into<<Bls12_381 as Pairing>::G1Prepared>>)
+//     -> PairingOutput<Bls12_381> {
+//     Bls12_381::multi_pairing(q, p)
+// }
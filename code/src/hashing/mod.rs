@@ -1,4 +1,5 @@
 use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
 
 pub mod swift_ec;
 pub mod swift_hasher;
@@ -15,10 +16,11 @@ const DEFAULT_FIELD_HASHER_SEC_PARAM: usize = 128;
 const GID_DOMAIN: &str = "GID";
 const AUTH_ID_DOMAIN: &str = "AID";
 const XATTR_DOMAIN: &str = "XAT";
+const CHALLENGE_DOMAIN: &str = "NZK";
 const HASH_SIGN_POS: &str = "POS";
 const HASH_SIGN_NEG: &str = "NEG";
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum HashSign {
     POS,
     NEG,
@@ -44,9 +46,17 @@ pub fn hash_gid(gid: &str) -> GAffine {
 pub fn hash_attr(attr: &str) -> ScalarField {
     let domain = XATTR_DOMAIN.as_bytes();
     let hasher = <DefaultFieldHasher<Sha256> as HashToField<ScalarField>>::new(domain);
+    let attr: String = attr.nfc().collect();
     hasher.hash_to_field(attr.as_bytes(), 1)[0]
 }
 
+// Fiat-Shamir challenge of a non-interactive proof over its serialized transcript
+pub fn hash_challenge(transcript: &[u8]) -> ScalarField {
+    let domain = CHALLENGE_DOMAIN.as_bytes();
+    let hasher = <DefaultFieldHasher<Sha256> as HashToField<ScalarField>>::new(domain);
+    hasher.hash_to_field(transcript, 1)[0]
+}
+
 pub fn hash_lbl(auth_id: &str, lbl: &str, sign: HashSign, i: u64) -> GAffine {
     let domain = AUTH_ID_DOMAIN.as_bytes();
     let g_mapper = SwiftMapToCurveBasedHasher::<
@@ -0,0 +1,153 @@
+use super::{Expr, Policy, UserAttribute};
+
+#[cfg(test)]
+use super::ExprVisitor;
+
+pub type Literal = (bool, UserAttribute);
+
+pub fn lit(user_attr: UserAttribute) -> Expr<Literal> {
+    Expr::Lit((false, user_attr))
+}
+
+// Negations are pushed down to the literals (negation normal form), just like the parser does
+pub fn not(expr: Expr<Literal>) -> Expr<Literal> {
+    match expr {
+        Expr::Lit((is_neg, user_attr)) => Expr::Lit((!is_neg, user_attr)),
+        Expr::And(lhs, rhs) => Expr::Or(Box::new(not(*lhs)), Box::new(not(*rhs))),
+        Expr::Or(lhs, rhs) => Expr::And(Box::new(not(*lhs)), Box::new(not(*rhs))),
+    }
+}
+
+pub fn and(lhs: Expr<Literal>, rhs: Expr<Literal>) -> Expr<Literal> {
+    Expr::And(Box::new(lhs), Box::new(rhs))
+}
+
+pub fn or(lhs: Expr<Literal>, rhs: Expr<Literal>) -> Expr<Literal> {
+    Expr::Or(Box::new(lhs), Box::new(rhs))
+}
+
+// The parser produces left-associative trees, so we do the same here
+pub fn and_all(exprs: Vec<Expr<Literal>>) -> Expr<Literal> {
+    exprs
+        .into_iter()
+        .reduce(and)
+        .expect("Cannot build a conjunction of zero expressions")
+}
+
+pub fn or_all(exprs: Vec<Expr<Literal>>) -> Expr<Literal> {
+    exprs
+        .into_iter()
+        .reduce(or)
+        .expect("Cannot build a disjunction of zero expressions")
+}
+
+// There is no threshold gate in the LSSS, so a k-out-of-n threshold is expanded into
+// ANDs and ORs. Note that the size of the expansion grows with binomial(n, k).
+pub fn threshold(k: usize, exprs: Vec<Expr<Literal>>) -> Expr<Literal> {
+    if k == 0 || k > exprs.len() {
+        panic!(
+            "Cannot build a {}-out-of-{} threshold policy",
+            k,
+            exprs.len()
+        );
+    }
+    if k == exprs.len() {
+        return and_all(exprs);
+    }
+    if k == 1 {
+        return or_all(exprs);
+    }
+    let mut rest = exprs;
+    let first = rest.remove(0);
+    let with_first = and(first, threshold(k - 1, rest.clone()));
+    let without_first = threshold(k, rest);
+    or(with_first, without_first)
+}
+
+impl From<Expr<Literal>> for Policy {
+    fn from(expr: Expr<Literal>) -> Self {
+        Policy::from_expr(expr)
+    }
+}
+
+#[cfg(test)]
+fn ua(s: &str) -> UserAttribute {
+    UserAttribute::parse(s).unwrap()
+}
+
+#[test]
+fn test_builder_matches_parser() {
+    let expr = or(
+        and(
+            lit(ua("x.b:a")),
+            not(or(not(lit(ua("x.b:a2"))), lit(ua("orr.y:u")))),
+        ),
+        lit(ua("anda.z:z")),
+    );
+    let built = Policy::from_expr(expr);
+    let parsed = Policy::parse("x.b:a & !(!x.b:a2 | orr.y:u) | anda.z:z").unwrap();
+    assert!(built == parsed);
+
+    let expr = and_all(vec![
+        lit(ua("A.a:0")),
+        not(lit(ua("A.b:0"))),
+        lit(ua("A.c:0")),
+    ]);
+    let built = Policy::from_expr(expr);
+    let parsed = Policy::parse("A.a:0 & !A.b:0 & A.c:0").unwrap();
+    assert!(built == parsed);
+    assert_eq!(format!("{:?}", built), "A.a:0 & !A.b:0 & A.c:0");
+}
+
+#[test]
+fn test_threshold() {
+    let exprs = vec![lit(ua("A.a:0")), lit(ua("A.b:0")), lit(ua("A.c:0"))];
+    let policy = Policy::from_expr(threshold(2, exprs));
+    assert_eq!(
+        format!("{:?}", policy),
+        "A.a:0 & (A.b:0 | A.c:0) | A.b:0 & A.c:0"
+    );
+    let users = [
+        (vec!["A.a:0"], false),
+        (vec!["A.a:0", "A.c:0"], true),
+        (vec!["A.b:0", "A.c:0"], true),
+        (vec!["A.a:0", "A.b:0", "A.c:0"], true),
+    ];
+    for (user_attrs, expected) in users {
+        let user_attrs = user_attrs.into_iter().map(ua).collect();
+        assert_eq!(policy.evaluate(&user_attrs), expected);
+    }
+}
+
+#[test]
+fn test_visitor() {
+    struct Depth;
+
+    impl ExprVisitor<Literal> for Depth {
+        type Output = usize;
+
+        fn visit_lit(&mut self, _: &Literal) -> usize {
+            1
+        }
+
+        fn visit_and(&mut self, lhs: usize, rhs: usize) -> usize {
+            1 + std::cmp::max(lhs, rhs)
+        }
+
+        fn visit_or(&mut self, lhs: usize, rhs: usize) -> usize {
+            1 + std::cmp::max(lhs, rhs)
+        }
+    }
+
+    let policy = Policy::parse("A.a:0 & (A.b:0 | !A.c:0)").unwrap();
+    assert_eq!(policy.expr().accept(&mut Depth), 3);
+
+    let renamed = policy.expr().map(&mut |(is_neg, user_attr)| {
+        (
+            *is_neg,
+            UserAttribute::new("B", &user_attr.lbl, &user_attr.attr),
+        )
+    });
+    let renamed = Policy::from_expr(renamed);
+    assert_eq!(format!("{:?}", renamed), "B.a:0 & (B.b:0 | !B.c:0)");
+}
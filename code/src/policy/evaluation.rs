@@ -0,0 +1,230 @@
+use std::collections::BTreeSet;
+
+use crate::policy::{Expr, Policy};
+
+use super::secret_sharing::satisfies;
+use super::UserAttribute;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LiteralState {
+    pub user_attr: UserAttribute,
+    pub is_neg: bool,
+    pub is_satisfied: bool,
+}
+
+pub fn evaluate(user_attrs: &[UserAttribute], policy: &Policy) -> bool {
+    eval(user_attrs, &policy.expr)
+}
+
+fn eval(user_attrs: &[UserAttribute], expr: &Expr<(bool, UserAttribute)>) -> bool {
+    match expr {
+        Expr::Lit((is_neg, user_attr)) => satisfies(user_attrs, user_attr, *is_neg).is_some(),
+        Expr::And(lhs, rhs) => eval(user_attrs, lhs) && eval(user_attrs, rhs),
+        Expr::Or(lhs, rhs) => eval(user_attrs, lhs) || eval(user_attrs, rhs),
+    }
+}
+
+pub fn explain(user_attrs: &[UserAttribute], policy: &Policy) -> Vec<LiteralState> {
+    (0..policy.len())
+        .map(|i| {
+            let (user_attr, is_neg) = policy.get(i);
+            let is_satisfied = satisfies(user_attrs, &user_attr, is_neg).is_some();
+            LiteralState {
+                user_attr,
+                is_neg,
+                is_satisfied,
+            }
+        })
+        .collect()
+}
+
+pub fn minimal_satisfying_sets(policy: &Policy) -> Vec<Vec<(UserAttribute, bool)>> {
+    let canon = canonical_idcs(policy);
+    let mut idx = 0;
+    let sets = dnf(&mut idx, &canon, &policy.expr, false);
+    sets.into_iter()
+        .filter(|set| is_consistent(set, |i| policy.get(i)))
+        .map(|set| set.into_iter().map(|i| policy.get(i)).collect())
+        .collect()
+}
+
+pub fn is_satisfiable(policy: &Policy) -> bool {
+    !minimal_satisfying_sets(policy).is_empty()
+}
+
+// Whether every user who holds at least one attribute for every (auth, lbl) mentioned in
+// the policy satisfies it. This is weaker than being a tautology: a user who holds no
+// attribute under a label satisfies neither `A.a:x` nor `!A.a:x`, so `evaluate` rejects
+// the empty attribute set for every policy. For label holders a negated literal is the
+// exact complement of its positive counterpart, so the policy holds for all of them iff
+// its dual has no consistent satisfying set.
+pub fn holds_for_all_label_holders(policy: &Policy) -> bool {
+    let canon = canonical_idcs(policy);
+    let mut idx = 0;
+    let sets = dnf(&mut idx, &canon, &policy.expr, true);
+    !sets.into_iter().any(|set| {
+        is_consistent(&set, |i| {
+            let (user_attr, is_neg) = policy.get(i);
+            (user_attr, !is_neg)
+        })
+    })
+}
+
+// Maps each literal to the first literal with the same attribute and polarity, so that
+// a literal that occurs several times in the policy counts as a single requirement.
+fn canonical_idcs(policy: &Policy) -> Vec<usize> {
+    let mut canon = Vec::with_capacity(policy.len());
+    for i in 0..policy.len() {
+        let lit = policy.get(i);
+        let first = (0..i).find(|k| policy.get(*k) == lit).unwrap_or(i);
+        canon.push(first);
+    }
+    canon
+}
+
+// Literal indices follow the left-to-right order of the leaves, just like `Policy::get`.
+// If `dual` is set, ANDs and ORs are swapped, i.e. the DNF of the negated policy is built.
+fn dnf(
+    idx: &mut usize,
+    canon: &[usize],
+    expr: &Expr<(bool, UserAttribute)>,
+    dual: bool,
+) -> Vec<BTreeSet<usize>> {
+    match (expr, dual) {
+        (Expr::Lit(_), _) => {
+            let set = BTreeSet::from([canon[*idx]]);
+            *idx += 1;
+            vec![set]
+        }
+        (Expr::Or(lhs, rhs), false) | (Expr::And(lhs, rhs), true) => {
+            let mut l = dnf(idx, canon, lhs, dual);
+            let mut r = dnf(idx, canon, rhs, dual);
+            l.append(&mut r);
+            minimize(l)
+        }
+        (Expr::And(lhs, rhs), false) | (Expr::Or(lhs, rhs), true) => {
+            let l = dnf(idx, canon, lhs, dual);
+            let r = dnf(idx, canon, rhs, dual);
+            let mut sets = Vec::with_capacity(l.len() * r.len());
+            for set_l in l.iter() {
+                for set_r in r.iter() {
+                    sets.push(set_l.union(set_r).cloned().collect());
+                }
+            }
+            minimize(sets)
+        }
+    }
+}
+
+pub(super) fn minimize(mut sets: Vec<BTreeSet<usize>>) -> Vec<BTreeSet<usize>> {
+    sets.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+    sets.dedup();
+    let mut result: Vec<BTreeSet<usize>> = Vec::with_capacity(sets.len());
+    for set in sets {
+        if !result.iter().any(|smaller| smaller.is_subset(&set)) {
+            result.push(set);
+        }
+    }
+    result
+}
+
+// A set of literals is inconsistent iff it requires a user to both have and not have
+// the same attribute.
+fn is_consistent(set: &BTreeSet<usize>, get: impl Fn(usize) -> (UserAttribute, bool)) -> bool {
+    let lits: Vec<(UserAttribute, bool)> = set.iter().map(|i| get(*i)).collect();
+    !lits.iter().any(|(ua, is_neg)| {
+        lits.iter()
+            .any(|(other, other_is_neg)| ua == other && is_neg != other_is_neg)
+    })
+}
+
+#[test]
+fn test_evaluate() {
+    fn parse_attrs(attrs: Vec<&str>) -> Vec<UserAttribute> {
+        attrs
+            .iter()
+            .map(|ua| UserAttribute::parse(ua).unwrap())
+            .collect()
+    }
+
+    let policy = Policy::parse("x.b:a & !(x.b:a2 | !orr.y:u) | anda.z:z").unwrap();
+    let users = [
+        parse_attrs(vec!["anda.z:z"]),
+        parse_attrs(vec!["x.b:a"]),
+        parse_attrs(vec!["x.b:a", "orr.y:u"]),
+        parse_attrs(vec!["x.b:a", "x.b:a2", "orr.y:u", "anda.z:z"]),
+        parse_attrs(vec!["x.b:a", "x.b:a2", "orr.y:u", "anda.z:z2"]),
+        parse_attrs(vec!["x.b:a", "x.b:a3", "orr.y:u", "anda.z:z"]),
+    ];
+    let expected = vec![true, false, true, true, false, true];
+    for (user_attrs, expected) in users.iter().zip(expected) {
+        assert_eq!(evaluate(user_attrs, &policy), expected);
+        assert_eq!(policy.reconstruct_secret(user_attrs).is_some(), expected);
+    }
+
+    let states = explain(&users[4], &policy);
+    let satisfied: Vec<bool> = states.iter().map(|s| s.is_satisfied).collect();
+    assert_eq!(satisfied, vec![true, false, true, false]);
+    assert_eq!(states[1].user_attr, UserAttribute::new("x", "b", "a2"));
+    assert!(states[1].is_neg);
+}
+
+#[test]
+fn test_minimal_satisfying_sets() {
+    fn lits(lits: Vec<&str>) -> Vec<(UserAttribute, bool)> {
+        lits.iter()
+            .map(|s| match s.strip_prefix('!') {
+                Some(s) => (UserAttribute::parse(s).unwrap(), true),
+                None => (UserAttribute::parse(s).unwrap(), false),
+            })
+            .collect()
+    }
+
+    let policy = Policy::parse("x.b:a & !(x.b:a2 | !orr.y:u) | anda.z:z").unwrap();
+    assert_eq!(
+        minimal_satisfying_sets(&policy),
+        vec![
+            lits(vec!["anda.z:z"]),
+            lits(vec!["x.b:a", "!x.b:a2", "orr.y:u"])
+        ]
+    );
+
+    let policy = Policy::parse("(A.a:0 | A.b:0) & (A.a:0 | A.c:0)").unwrap();
+    assert_eq!(
+        minimal_satisfying_sets(&policy),
+        vec![lits(vec!["A.a:0"]), lits(vec!["A.b:0", "A.c:0"])]
+    );
+
+    let policy = Policy::parse("A.a:0 & (!A.a:0 | A.b:0)").unwrap();
+    assert_eq!(
+        minimal_satisfying_sets(&policy),
+        vec![lits(vec!["A.a:0", "A.b:0"])]
+    );
+}
+
+#[test]
+fn test_satisfiability() {
+    let policy = Policy::parse("A.a:0 & !A.a:0").unwrap();
+    assert!(!is_satisfiable(&policy));
+    assert!(!holds_for_all_label_holders(&policy));
+
+    let policy = Policy::parse("A.a:0 | !A.a:0").unwrap();
+    assert!(is_satisfiable(&policy));
+    assert!(holds_for_all_label_holders(&policy));
+
+    let policy = Policy::parse("A.a:0 | !A.a:1").unwrap();
+    assert!(is_satisfiable(&policy));
+    assert!(!holds_for_all_label_holders(&policy));
+
+    let policy = Policy::parse("(A.a:0 | !A.a:0) & (B.b:1 | !B.b:1)").unwrap();
+    assert!(holds_for_all_label_holders(&policy));
+
+    // Only label holders are quantified over, `evaluate` still rejects other users
+    let policy = Policy::parse("A.a:0 | !A.a:0").unwrap();
+    assert!(!evaluate(&[], &policy));
+    assert!(!evaluate(
+        &[UserAttribute::parse("A.b:0").unwrap()],
+        &policy
+    ));
+    assert!(evaluate(&[UserAttribute::parse("A.a:1").unwrap()], &policy));
+}
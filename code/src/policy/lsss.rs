@@ -0,0 +1,433 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use crate::curve::ScalarField;
+
+use super::secret_sharing::{satisfies, share_secret};
+use super::{builder, Policy, UserAttribute};
+
+// Exhaustive verification enumerates all subsets of the attributes a user could hold,
+// so it is restricted to small policies
+const MAX_VERIFY_DOMAIN: usize = 16;
+
+// A linear secret sharing scheme (M, rho): a secret s is shared as lambda = M * v for a
+// vector v = (s, r_2, ..., r_d) with random r_i, and row i belongs to the literal rho(i).
+// A set of rows can reconstruct s iff (1, 0, ..., 0) lies in the span of these rows.
+#[derive(PartialEq, Clone)]
+pub struct LsssMatrix {
+    rows: Vec<Vec<ScalarField>>,
+    rho: Vec<(UserAttribute, bool)>,
+}
+
+impl LsssMatrix {
+    // Rows follow the order of the literals in the policy, i.e. row j belongs to
+    // `policy.get(j)`. The entries are 0, 1 and -1 (Lewko-Waters construction).
+    pub fn from_policy(policy: &Policy) -> Self {
+        let splits = share_secret(policy);
+        let num_cols = splits
+            .iter()
+            .flat_map(|(_, idcs)| idcs.iter())
+            .map(|j| j.unsigned_abs() as usize + 1)
+            .max()
+            .unwrap_or(1);
+        let mut rows = Vec::with_capacity(splits.len());
+        let mut rho = Vec::with_capacity(splits.len());
+        for (i, (user_attr, idcs)) in splits.into_iter().enumerate() {
+            let mut row = vec![ScalarField::zero(); num_cols];
+            for j in idcs {
+                if j >= 0 {
+                    row[j as usize] += ScalarField::one();
+                } else {
+                    row[(-j) as usize] -= ScalarField::one();
+                }
+            }
+            rows.push(row);
+            rho.push((user_attr, policy.get(i).1));
+        }
+        LsssMatrix { rows, rho }
+    }
+
+    pub fn new(
+        rows: Vec<Vec<ScalarField>>,
+        rho: Vec<(UserAttribute, bool)>,
+    ) -> Result<Self, String> {
+        if rows.is_empty() || rows.len() != rho.len() {
+            return Err(String::from(
+                "An LSSS matrix needs at least one row and one label per row",
+            ));
+        }
+        if rows
+            .iter()
+            .any(|row| row.is_empty() || row.len() != rows[0].len())
+        {
+            return Err(String::from(
+                "All rows of an LSSS matrix must have the same, non-zero length",
+            ));
+        }
+        Ok(LsssMatrix { rows, rho })
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    pub fn row(&self, i: usize) -> &Vec<ScalarField> {
+        &self.rows[i]
+    }
+
+    pub fn rho(&self, i: usize) -> (UserAttribute, bool) {
+        self.rho[i].clone()
+    }
+
+    // The rows whose labels the attributes satisfy
+    pub fn satisfied_rows(&self, user_attrs: &Vec<UserAttribute>) -> Vec<usize> {
+        (0..self.num_rows())
+            .filter(|i| {
+                let (user_attr, is_neg) = &self.rho[*i];
+                satisfies(user_attrs, user_attr, *is_neg).is_some()
+            })
+            .collect()
+    }
+
+    // The conjunction of the row labels, i.e. row i belongs to `labels().get(i)`. It
+    // stands in for a policy wherever only the labels of the rows matter, e.g. for Tau.
+    pub fn labels(&self) -> Policy {
+        let lits = self.rho.iter().map(|(user_attr, is_neg)| {
+            let lit = builder::lit(user_attr.clone());
+            if *is_neg {
+                builder::not(lit)
+            } else {
+                lit
+            }
+        });
+        Policy::from_expr(builder::and_all(lits.collect()))
+    }
+
+    // Returns the shares lambda_i = <M_i, v> with v = (secret, random, ..., random)
+    pub fn share(&self, mut rng: impl Rng, secret: ScalarField) -> Vec<ScalarField> {
+        let mut v = Vec::with_capacity(self.num_cols());
+        v.push(secret);
+        for _ in 1..self.num_cols() {
+            v.push(ScalarField::rand(&mut rng));
+        }
+        self.rows
+            .iter()
+            .map(|row| row.iter().zip(v.iter()).map(|(m, v)| *m * v).sum())
+            .collect()
+    }
+
+    // Finds coefficients w with sum_i w_i * M_{rows[i]} = (1, 0, ..., 0) by Gaussian
+    // elimination, or None if the rows cannot reconstruct the secret.
+    pub fn reconstruction_coefficients(&self, rows: &[usize]) -> Option<Vec<ScalarField>> {
+        let n = rows.len();
+        let d = self.num_cols();
+        // the system M_rows^T * w = e_1 as an augmented d x (n + 1) matrix
+        let mut a: Vec<Vec<ScalarField>> = (0..d)
+            .map(|c| {
+                let mut eq: Vec<ScalarField> = rows.iter().map(|r| self.rows[*r][c]).collect();
+                eq.push(if c == 0 {
+                    ScalarField::one()
+                } else {
+                    ScalarField::zero()
+                });
+                eq
+            })
+            .collect();
+        let mut pivots = Vec::new();
+        let mut r = 0;
+        for c in 0..n {
+            let Some(p) = (r..d).find(|p| !a[*p][c].is_zero()) else {
+                continue;
+            };
+            a.swap(r, p);
+            let inv = a[r][c].inverse().unwrap();
+            a[r].iter_mut().skip(c).for_each(|x| *x *= inv);
+            let pivot_row = a[r].clone();
+            for (i, eq) in a.iter_mut().enumerate() {
+                if i != r && !eq[c].is_zero() {
+                    let f = eq[c];
+                    for (x, p) in eq.iter_mut().zip(pivot_row.iter()).skip(c) {
+                        *x -= f * p;
+                    }
+                }
+            }
+            pivots.push(c);
+            r += 1;
+        }
+        // the system is inconsistent iff a zero row has a non-zero right-hand side
+        if (r..d).any(|i| !a[i][n].is_zero()) {
+            return None;
+        }
+        let mut w = vec![ScalarField::zero(); n];
+        for (i, c) in pivots.into_iter().enumerate() {
+            w[c] = a[i][n];
+        }
+        Some(w)
+    }
+
+    // Checks that exactly the users satisfying the policy can reconstruct the secret. All
+    // subsets of the attributes mentioned in the policy are tried, together with one
+    // additional attribute per label for the negated literals.
+    pub fn verify(&self, policy: &Policy) -> Result<(), String> {
+        if self.num_rows() != policy.len() {
+            return Err(format!(
+                "The matrix has {} rows but the policy has {} literals",
+                self.num_rows(),
+                policy.len()
+            ));
+        }
+        for i in 0..self.num_rows() {
+            if self.rho[i] != policy.get(i) {
+                return Err(format!("Row {} is not labelled by literal {}", i, i));
+            }
+        }
+        let mut domain: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        for (user_attr, _) in self.rho.iter() {
+            let attrs = domain.entry(user_attr.auth_lbl()).or_default();
+            if !attrs.contains(&user_attr.attr) {
+                attrs.push(user_attr.attr.clone());
+            }
+        }
+        let mut candidates = Vec::new();
+        for ((auth, lbl), mut attrs) in domain {
+            let other = (0..)
+                .map(|i| format!("other{}", i))
+                .find(|a| !attrs.contains(a))
+                .unwrap();
+            attrs.push(other);
+            for attr in attrs {
+                candidates.push(UserAttribute::new(&auth, &lbl, &attr));
+            }
+        }
+        if candidates.len() > MAX_VERIFY_DOMAIN {
+            return Err(format!(
+                "Policy is too large to verify, {} candidate attributes exceed the limit of {}",
+                candidates.len(),
+                MAX_VERIFY_DOMAIN
+            ));
+        }
+        for mask in 0..(1u32 << candidates.len()) {
+            let user_attrs: Vec<UserAttribute> = candidates
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, ua)| ua.clone())
+                .collect();
+            let rows = self.satisfied_rows(&user_attrs);
+            let expected = policy.evaluate(&user_attrs);
+            if self.reconstruction_coefficients(&rows).is_some() != expected {
+                return Err(format!(
+                    "Matrix and policy disagree for the attributes {:?}",
+                    user_attrs
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Layout: number of rows and columns, then per row the label (auth, lbl and attr as
+    // length-prefixed UTF-8, a byte for the polarity) and the compressed coefficients.
+    // All lengths are little-endian u32.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_u32(&mut bytes, self.num_rows());
+        write_u32(&mut bytes, self.num_cols());
+        for (row, (user_attr, is_neg)) in self.rows.iter().zip(self.rho.iter()) {
+            for s in [&user_attr.auth, &user_attr.lbl, &user_attr.attr] {
+                write_u32(&mut bytes, s.len());
+                bytes.extend_from_slice(s.as_bytes());
+            }
+            bytes.push(*is_neg as u8);
+            for m in row {
+                m.serialize_compressed(&mut bytes).unwrap();
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = bytes;
+        let num_rows = read_u32(&mut reader)?;
+        let num_cols = read_u32(&mut reader)?;
+        // every row takes three lengths, the polarity and 32 bytes per coefficient, so the
+        // dimensions are checked against the input before anything is allocated for them
+        let min_len = num_cols
+            .checked_mul(32)
+            .and_then(|row_len| row_len.checked_add(13))
+            .and_then(|row_len| row_len.checked_mul(num_rows));
+        if min_len.is_none_or(|min_len| min_len > reader.len()) {
+            return Err(format!(
+                "An LSSS matrix of {} rows and {} columns does not fit into {} bytes",
+                num_rows,
+                num_cols,
+                reader.len()
+            ));
+        }
+        let mut rows = Vec::new();
+        let mut rho = Vec::new();
+        for _ in 0..num_rows {
+            let mut parts = Vec::with_capacity(3);
+            for _ in 0..3 {
+                let len = read_u32(&mut reader)?;
+                if reader.len() < len {
+                    return Err(String::from("Unexpected end of LSSS matrix"));
+                }
+                let (s, rest) = reader.split_at(len);
+                let s = std::str::from_utf8(s).map_err(|e| e.to_string())?;
+                parts.push(String::from(s));
+                reader = rest;
+            }
+            let is_neg = match reader.split_first() {
+                Some((0, rest)) => {
+                    reader = rest;
+                    false
+                }
+                Some((1, rest)) => {
+                    reader = rest;
+                    true
+                }
+                _ => return Err(String::from("Invalid polarity in LSSS matrix")),
+            };
+            let mut row = Vec::with_capacity(num_cols);
+            for _ in 0..num_cols {
+                let m =
+                    ScalarField::deserialize_compressed(&mut reader).map_err(|e| e.to_string())?;
+                row.push(m);
+            }
+            rows.push(row);
+            rho.push((UserAttribute::new(&parts[0], &parts[1], &parts[2]), is_neg));
+        }
+        if !reader.is_empty() {
+            return Err(String::from("Trailing bytes after LSSS matrix"));
+        }
+        LsssMatrix::new(rows, rho)
+    }
+}
+
+fn write_u32(bytes: &mut Vec<u8>, n: usize) {
+    bytes.extend_from_slice(&(n as u32).to_le_bytes());
+}
+
+fn read_u32(reader: &mut &[u8]) -> Result<usize, String> {
+    if reader.len() < 4 {
+        return Err(String::from("Unexpected end of LSSS matrix"));
+    }
+    let (n, rest) = reader.split_at(4);
+    *reader = rest;
+    Ok(u32::from_le_bytes(n.try_into().unwrap()) as usize)
+}
+
+// Small coefficients are printed as signed integers, e.g. -1 instead of p - 1
+fn fmt_coeff(m: &ScalarField) -> String {
+    if m.is_zero() {
+        return String::from("0");
+    }
+    let neg = -*m;
+    let (pos, neg) = (m.to_string(), neg.to_string());
+    if neg.len() < pos.len() {
+        format!("-{}", neg)
+    } else {
+        pos
+    }
+}
+
+impl fmt::Debug for LsssMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, (user_attr, is_neg)) in self.rows.iter().zip(self.rho.iter()) {
+            let coeffs: Vec<String> = row.iter().map(fmt_coeff).collect();
+            let lbl = format!("{}{:?}", if *is_neg { "!" } else { "" }, user_attr);
+            writeln!(f, "{}: [{}]", lbl, coeffs.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_lsss_from_policy() {
+    let policy = Policy::parse("x.b:a & !(!x.b:a2 | orr.y:u) | anda.z:z").unwrap();
+    let lsss = LsssMatrix::from_policy(&policy);
+    assert_eq!(lsss.num_rows(), 4);
+    assert_eq!(lsss.num_cols(), 3);
+    assert_eq!(
+        format!("{:?}", lsss),
+        "x.b:a: [1, 1, 0]\nx.b:a2: [0, -1, 1]\n!orr.y:u: [0, 0, -1]\nanda.z:z: [1, 0, 0]\n"
+    );
+    assert!(lsss.verify(&policy).is_ok());
+
+    let w = lsss.reconstruction_coefficients(&[0, 1, 2]).unwrap();
+    assert!(w.iter().all(|w| w.is_one()));
+    assert!(lsss.reconstruction_coefficients(&[0, 1]).is_none());
+
+    let mut rng = ark_std::test_rng();
+    let secret = ScalarField::rand(&mut rng);
+    let shares = lsss.share(&mut rng, secret);
+    assert_eq!(shares[0] + shares[1] + shares[2], secret);
+    assert_eq!(shares[3], secret);
+
+    let labels = lsss.labels();
+    assert_eq!(labels.len(), 4);
+    assert!((0..4).all(|i| labels.get(i) == lsss.rho(i)));
+    let user_attrs = vec![
+        UserAttribute::new("x", "b", "a"),
+        UserAttribute::new("x", "b", "a2"),
+        UserAttribute::new("orr", "y", "v"),
+    ];
+    assert_eq!(lsss.satisfied_rows(&user_attrs), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_lsss_verify() {
+    let policies = [
+        "A.a:0 | (!A.b:0 & A.a:2) & !(A.c:1 | A.c:2)",
+        "(A.a:0 & B.b:0) | (A.a:0 & C.c:0) | !A.a:0",
+        "A.a:0 & !A.a:0",
+    ];
+    for policy in policies {
+        let policy = Policy::parse(policy).unwrap();
+        assert!(LsssMatrix::from_policy(&policy).verify(&policy).is_ok());
+    }
+
+    // swapping the labels of two rows breaks the scheme
+    let policy = Policy::parse("A.a:0 & B.b:0 | C.c:0").unwrap();
+    let lsss = LsssMatrix::from_policy(&policy);
+    let rows = (0..3).map(|i| lsss.row(i).clone()).collect();
+    let rho = vec![lsss.rho(2), lsss.rho(1), lsss.rho(0)];
+    assert!(LsssMatrix::new(rows, rho).unwrap().verify(&policy).is_err());
+
+    // a matrix with wrong coefficients
+    let mut rows: Vec<Vec<ScalarField>> = (0..3).map(|i| lsss.row(i).clone()).collect();
+    rows[1] = vec![ScalarField::one(), ScalarField::zero()];
+    let rho = (0..3).map(|i| lsss.rho(i)).collect();
+    assert!(LsssMatrix::new(rows, rho).unwrap().verify(&policy).is_err());
+}
+
+#[test]
+fn test_lsss_serialization() {
+    let policy = Policy::parse("geo.city:\"S\u{e3}o Paulo\" & !(A.b:0 | A.b:1) | C.c:0").unwrap();
+    let lsss = LsssMatrix::from_policy(&policy);
+    let bytes = lsss.to_bytes();
+    assert!(LsssMatrix::from_bytes(&bytes).unwrap() == lsss);
+    assert!(LsssMatrix::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut bytes = bytes;
+    bytes.push(0);
+    assert!(LsssMatrix::from_bytes(&bytes).is_err());
+
+    // dimensions which do not fit into the input are rejected before allocating
+    let mut bytes = Vec::new();
+    write_u32(&mut bytes, 1);
+    write_u32(&mut bytes, u32::MAX as usize);
+    assert!(LsssMatrix::from_bytes(&bytes).is_err());
+    let mut bytes = Vec::new();
+    write_u32(&mut bytes, u32::MAX as usize);
+    write_u32(&mut bytes, u32::MAX as usize);
+    bytes.extend_from_slice(&[0; 64]);
+    assert!(LsssMatrix::from_bytes(&bytes).is_err());
+}
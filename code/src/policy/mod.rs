@@ -2,8 +2,9 @@ use core::fmt;
 use std::fmt::{Debug, Write};
 
 use ark_std::iterable::Iterable;
+use unicode_normalization::UnicodeNormalization;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub struct Policy {
     expr: Expr<(bool, UserAttribute)>,
     attrs: Vec<UserAttribute>,
@@ -20,10 +21,65 @@ impl Policy {
     }
 
     pub fn parse(s: &str) -> Result<Self, String> {
-        let (expr, attrs, negs) = parser::Parser::parse_policy(s)?;
+        Self::parse_with_widths(s, &BitWidths::new())
+    }
+
+    // Comparisons like `A.age < 18` need the bit width declared for (auth, lbl)
+    pub fn parse_with_widths(s: &str, widths: &BitWidths) -> Result<Self, String> {
+        let (expr, attrs, negs) = parser::Parser::parse_policy(s, widths)?;
+        Ok(Policy { expr, attrs, negs })
+    }
+
+    // Parses a policy file with let-bindings, see `parser::file`
+    pub fn parse_file(s: &str) -> Result<Self, String> {
+        Self::parse_file_with_widths(s, &BitWidths::new())
+    }
+
+    pub fn parse_file_with_widths(s: &str, widths: &BitWidths) -> Result<Self, String> {
+        let (expr, attrs, negs) = parser::Parser::parse_file(s, widths)?;
         Ok(Policy { expr, attrs, negs })
     }
 
+    pub fn from_expr(expr: Expr<(bool, UserAttribute)>) -> Self {
+        let mut attrs = Vec::new();
+        let mut negs = Vec::new();
+        for (is_neg, user_attr) in expr.literals() {
+            attrs.push(user_attr.clone());
+            negs.push(*is_neg);
+        }
+        Policy { expr, attrs, negs }
+    }
+
+    // Excludes users by their GID. For every GID, one of the authorities in the policy has
+    // to vouch (through its identity attribute, see `GID_LABEL`) that the user is someone
+    // else. Anyone satisfying the policy holds attributes of one of these authorities and
+    // thus its identity attribute, while a revoked user fails all of the negations.
+    // Only keys issued through `Abe` hold identity attributes. Keys from the low-level
+    // `Scheme::keygen` fail every negation of an identity attribute, so such users are
+    // excluded by any revoking policy, unless they were given `__gid` attributes by hand,
+    // in which case a revoked user can pass as someone else.
+    pub fn revoking(&self, gids: &[&str]) -> Policy {
+        let mut auths: Vec<String> = Vec::new();
+        for user_attr in self.attrs.iter() {
+            if !auths.contains(&user_attr.auth) {
+                auths.push(user_attr.auth.clone());
+            }
+        }
+        let mut expr = self.expr.clone();
+        for gid in gids {
+            let vouchers = auths
+                .iter()
+                .map(|auth| builder::not(builder::lit(UserAttribute::gid(auth, gid))))
+                .collect();
+            expr = builder::and(expr, builder::or_all(vouchers));
+        }
+        Policy::from_expr(expr)
+    }
+
+    pub fn expr(&self) -> &Expr<(bool, UserAttribute)> {
+        &self.expr
+    }
+
     pub fn conjunction_of(user_attrs: &Vec<UserAttribute>, num_negs: usize) -> Self {
         if num_negs > user_attrs.len() {
             panic!("Cannot have more negated attributes than total length of policy");
@@ -56,15 +112,55 @@ impl Policy {
         secret_sharing::share_secret(self)
     }
 
+    pub fn lsss(&self) -> LsssMatrix {
+        LsssMatrix::from_policy(self)
+    }
+
     pub fn reconstruct_secret(&self, user_attrs: &Vec<UserAttribute>) -> Option<Vec<usize>> {
         secret_sharing::reconstruct_secret(user_attrs, self)
     }
+
+    pub fn cheapest_row_set(
+        &self,
+        user_attrs: &Vec<UserAttribute>,
+        weight: impl Fn(&[usize]) -> u64,
+    ) -> Option<Vec<usize>> {
+        secret_sharing::cheapest_row_set(user_attrs, self, &weight)
+    }
+
+    pub fn evaluate(&self, user_attrs: &Vec<UserAttribute>) -> bool {
+        evaluation::evaluate(user_attrs, self)
+    }
+
+    pub fn explain(&self, user_attrs: &Vec<UserAttribute>) -> Vec<LiteralState> {
+        evaluation::explain(user_attrs, self)
+    }
+
+    pub fn minimal_satisfying_sets(&self) -> Vec<Vec<(UserAttribute, bool)>> {
+        evaluation::minimal_satisfying_sets(self)
+    }
+
+    pub fn is_satisfiable(&self) -> bool {
+        evaluation::is_satisfiable(self)
+    }
+
+    pub fn holds_for_all_label_holders(&self) -> bool {
+        evaluation::holds_for_all_label_holders(self)
+    }
+
+    // Returns None if the policy folds into a contradiction, i.e. nobody can satisfy it
+    pub fn normalize(&self) -> Option<Policy> {
+        normalize::normalize(self)
+    }
 }
 
 fn fmt_expr(
     expr: &Expr<(bool, UserAttribute)>,
     f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
+    if let Some((is_neg, lits)) = as_set(expr) {
+        return fmt_set(is_neg, &lits, f);
+    }
     match expr {
         Expr::Lit((is_neg, t)) => {
             if *is_neg {
@@ -73,14 +169,88 @@ fn fmt_expr(
             write!(f, "{:?}", t)
         }
         Expr::And(lhs, rhs) => {
-            fmt_expr(&lhs, f)?;
+            fmt_operand(lhs, f)?;
             write!(f, " & ")?;
-            fmt_expr(&rhs, f)
+            fmt_operand(rhs, f)
+        }
+        Expr::Or(lhs, rhs) => {
+            fmt_expr(lhs, f)?;
+            write!(f, " | ")?;
+            fmt_expr(rhs, f)
+        }
+    }
+}
+
+// AND binds stronger than OR, so disjunctions below a conjunction need parentheses
+fn fmt_operand(
+    expr: &Expr<(bool, UserAttribute)>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    match expr {
+        Expr::Or(_, _) if as_set(expr).is_none() => {
+            write!(f, "(")?;
+            fmt_expr(expr, f)?;
+            write!(f, ")")
         }
-        Expr::Or(_, _) => {
-            panic!("Not implemented")
+        _ => fmt_expr(expr, f),
+    }
+}
+
+// A chain of ORs over positive literals (or ANDs over negated literals) which all share
+// the same label is printed as a set membership
+fn as_set(expr: &Expr<(bool, UserAttribute)>) -> Option<(bool, Vec<&UserAttribute>)> {
+    fn collect<'a>(
+        expr: &'a Expr<(bool, UserAttribute)>,
+        is_neg: bool,
+        lits: &mut Vec<&'a UserAttribute>,
+    ) -> Option<()> {
+        match expr {
+            Expr::Lit((neg, user_attr)) if *neg == is_neg => {
+                lits.push(user_attr);
+                Some(())
+            }
+            Expr::Or(lhs, rhs) if !is_neg => {
+                collect(lhs, is_neg, lits)?;
+                collect(rhs, is_neg, lits)
+            }
+            Expr::And(lhs, rhs) if is_neg => {
+                collect(lhs, is_neg, lits)?;
+                collect(rhs, is_neg, lits)
+            }
+            _ => None,
         }
     }
+
+    let is_neg = match expr {
+        Expr::Or(_, _) => false,
+        Expr::And(_, _) => true,
+        Expr::Lit(_) => return None,
+    };
+    let mut lits = Vec::new();
+    collect(expr, is_neg, &mut lits)?;
+    if lits.iter().all(|ua| ua.auth_lbl() == lits[0].auth_lbl()) {
+        Some((is_neg, lits))
+    } else {
+        None
+    }
+}
+
+fn fmt_set(
+    is_neg: bool,
+    lits: &[&UserAttribute],
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    fmt_ident(&lits[0].auth, f)?;
+    f.write_char('.')?;
+    fmt_ident(&lits[0].lbl, f)?;
+    f.write_str(if is_neg { " not in {" } else { " in {" })?;
+    for (i, user_attr) in lits.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        fmt_ident(&user_attr.attr, f)?;
+    }
+    f.write_char('}')
 }
 
 impl fmt::Debug for Policy {
@@ -89,6 +259,10 @@ impl fmt::Debug for Policy {
     }
 }
 
+// Label of the identity attribute auth.__gid:<gid>, which the high-level API issues to
+// every user under each of their authorities
+pub const GID_LABEL: &str = "__gid";
+
 #[derive(PartialEq, Clone)]
 pub struct UserAttribute {
     pub auth: String,
@@ -97,11 +271,13 @@ pub struct UserAttribute {
 }
 
 impl UserAttribute {
+    // All components are brought into Unicode NFC, so that canonically equivalent
+    // spellings of the same value are the same attribute
     pub fn new(auth: &str, lbl: &str, attr: &str) -> Self {
         UserAttribute {
-            auth: String::from(auth),
-            lbl: String::from(lbl),
-            attr: String::from(attr),
+            auth: auth.nfc().collect(),
+            lbl: lbl.nfc().collect(),
+            attr: attr.nfc().collect(),
         }
     }
 
@@ -110,10 +286,27 @@ impl UserAttribute {
         Ok(UserAttribute { auth, lbl, attr })
     }
 
+    pub fn gid(auth: &str, gid: &str) -> Self {
+        UserAttribute::new(auth, GID_LABEL, gid)
+    }
+
     pub fn auth_lbl_attr(&self) -> (String, String, String) {
         (self.auth.clone(), self.lbl.clone(), self.attr.clone())
     }
 
+    // Issues the bit attributes for a numeric value, see `BitWidths`
+    pub fn numeric(
+        auth: &str,
+        lbl: &str,
+        value: u64,
+        widths: &BitWidths,
+    ) -> Result<Vec<UserAttribute>, String> {
+        let bits = widths
+            .get(auth, lbl)
+            .ok_or(format!("No bit width declared for {}.{}", auth, lbl))?;
+        numeric::bit_attrs(auth, lbl, value, bits)
+    }
+
     pub fn auth_attr(&self) -> (String, String) {
         (self.auth.clone(), self.attr.clone())
     }
@@ -125,20 +318,101 @@ impl UserAttribute {
 
 impl Debug for UserAttribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.auth)?;
+        fmt_ident(&self.auth, f)?;
         f.write_char('.')?;
-        f.write_str(&self.lbl)?;
+        fmt_ident(&self.lbl, f)?;
         f.write_char(':')?;
-        f.write_str(&self.attr)
+        fmt_ident(&self.attr, f)
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Expr<T> {
+// Identifiers which the lexer cannot read as is are quoted, so that the output parses
+// back into the same attribute
+fn fmt_ident(s: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if !s.is_empty() && s.chars().all(parser::is_ident_char) && !parser::is_reserved(s) {
+        return f.write_str(s);
+    }
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            _ => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr<T> {
     Lit(T),
     And(Box<Expr<T>>, Box<Expr<T>>),
     Or(Box<Expr<T>>, Box<Expr<T>>),
 }
 
+pub trait ExprVisitor<T> {
+    type Output;
+
+    fn visit_lit(&mut self, lit: &T) -> Self::Output;
+
+    fn visit_and(&mut self, lhs: Self::Output, rhs: Self::Output) -> Self::Output;
+
+    fn visit_or(&mut self, lhs: Self::Output, rhs: Self::Output) -> Self::Output;
+}
+
+impl<T> Expr<T> {
+    // Visits the tree bottom-up, literals are visited in left-to-right order
+    pub fn accept<V: ExprVisitor<T>>(&self, visitor: &mut V) -> V::Output {
+        match self {
+            Expr::Lit(lit) => visitor.visit_lit(lit),
+            Expr::And(lhs, rhs) => {
+                let l = lhs.accept(visitor);
+                let r = rhs.accept(visitor);
+                visitor.visit_and(l, r)
+            }
+            Expr::Or(lhs, rhs) => {
+                let l = lhs.accept(visitor);
+                let r = rhs.accept(visitor);
+                visitor.visit_or(l, r)
+            }
+        }
+    }
+
+    pub fn literals(&self) -> Vec<&T> {
+        let mut lits = Vec::new();
+        self.collect_literals(&mut lits);
+        lits
+    }
+
+    fn collect_literals<'a>(&'a self, lits: &mut Vec<&'a T>) {
+        match self {
+            Expr::Lit(lit) => lits.push(lit),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.collect_literals(lits);
+                rhs.collect_literals(lits);
+            }
+        }
+    }
+
+    pub fn map<U>(&self, f: &mut impl FnMut(&T) -> U) -> Expr<U> {
+        match self {
+            Expr::Lit(lit) => Expr::Lit(f(lit)),
+            Expr::And(lhs, rhs) => Expr::And(Box::new(lhs.map(f)), Box::new(rhs.map(f))),
+            Expr::Or(lhs, rhs) => Expr::Or(Box::new(lhs.map(f)), Box::new(rhs.map(f))),
+        }
+    }
+}
+
+pub mod builder;
+mod evaluation;
+mod lsss;
+mod normalize;
+mod numeric;
 mod parser;
 mod secret_sharing;
+
+pub use evaluation::LiteralState;
+pub use lsss::LsssMatrix;
+pub use numeric::BitWidths;
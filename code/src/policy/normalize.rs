@@ -0,0 +1,332 @@
+use crate::policy::{Expr, Policy};
+
+use super::UserAttribute;
+
+// n-ary version of `Expr` which is used while simplifying a policy
+#[derive(Clone)]
+enum Node {
+    False,
+    Lit(bool, UserAttribute),
+    // `x | !x`, i.e. the user holds any attribute under the label of x
+    Presence(UserAttribute),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+}
+
+pub fn normalize(policy: &Policy) -> Option<Policy> {
+    match simplify(from_expr(&policy.expr)) {
+        Node::False => None,
+        node => Some(Policy::from_expr(to_expr(node))),
+    }
+}
+
+fn from_expr(expr: &Expr<(bool, UserAttribute)>) -> Node {
+    match expr {
+        Expr::Lit((is_neg, user_attr)) => Node::Lit(*is_neg, user_attr.clone()),
+        Expr::And(lhs, rhs) => Node::And(vec![from_expr(lhs), from_expr(rhs)]),
+        Expr::Or(lhs, rhs) => Node::Or(vec![from_expr(lhs), from_expr(rhs)]),
+    }
+}
+
+fn to_expr(node: Node) -> Expr<(bool, UserAttribute)> {
+    match node {
+        Node::False => panic!("Contradictions cannot be expressed as a policy"),
+        Node::Lit(is_neg, user_attr) => Expr::Lit((is_neg, user_attr)),
+        Node::Presence(user_attr) => Expr::Or(
+            Box::new(Expr::Lit((false, user_attr.clone()))),
+            Box::new(Expr::Lit((true, user_attr))),
+        ),
+        // the parser produces left-associative Expr trees, so we do the same here
+        Node::And(nodes) => nodes
+            .into_iter()
+            .map(to_expr)
+            .reduce(|lhs, rhs| Expr::And(Box::new(lhs), Box::new(rhs)))
+            .unwrap(),
+        Node::Or(nodes) => nodes
+            .into_iter()
+            .map(to_expr)
+            .reduce(|lhs, rhs| Expr::Or(Box::new(lhs), Box::new(rhs)))
+            .unwrap(),
+    }
+}
+
+// Canonical representation of a node, used for deduplication and ordering
+fn key(node: &Node) -> String {
+    match node {
+        Node::False => String::from("0"),
+        Node::Lit(is_neg, user_attr) => format!("{:?}/{}", user_attr, *is_neg as u8),
+        Node::Presence(user_attr) => format!("{}.{}:*", user_attr.auth, user_attr.lbl),
+        Node::And(nodes) => {
+            let keys: Vec<String> = nodes.iter().map(key).collect();
+            format!("&({})", keys.join(","))
+        }
+        Node::Or(nodes) => {
+            let keys: Vec<String> = nodes.iter().map(key).collect();
+            format!("|({})", keys.join(","))
+        }
+    }
+}
+
+fn is_compound(node: &Node) -> bool {
+    matches!(node, Node::And(_) | Node::Or(_) | Node::Presence(_))
+}
+
+fn same_lbl(lhs: &UserAttribute, rhs: &UserAttribute) -> bool {
+    lhs.auth == rhs.auth && lhs.lbl == rhs.lbl
+}
+
+fn conjuncts(node: &Node) -> Vec<&Node> {
+    match node {
+        Node::And(nodes) => nodes.iter().collect(),
+        _ => vec![node],
+    }
+}
+
+fn disjuncts(node: &Node) -> Vec<&Node> {
+    match node {
+        Node::Or(nodes) => nodes.iter().collect(),
+        _ => vec![node],
+    }
+}
+
+// Every literal (positive or negative) requires the user to hold some attribute
+// under its label, hence it implies the presence check for that label.
+fn covers(nodes: &[&Node], node: &Node) -> bool {
+    let k = key(node);
+    nodes.iter().any(|other| key(other) == k)
+        || match node {
+            Node::Presence(ua) => nodes
+                .iter()
+                .any(|other| matches!(other, Node::Lit(_, ub) if same_lbl(ua, ub))),
+            _ => false,
+        }
+}
+
+fn implies(nodes: &[&Node], node: &Node) -> bool {
+    conjuncts(node).iter().all(|x| covers(nodes, x))
+}
+
+fn dedup(nodes: Vec<Node>) -> Vec<Node> {
+    let mut result: Vec<Node> = Vec::with_capacity(nodes.len());
+    let mut keys = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let k = key(&node);
+        if !keys.contains(&k) {
+            keys.push(k);
+            result.push(node);
+        }
+    }
+    result
+}
+
+fn sort(mut nodes: Vec<Node>) -> Vec<Node> {
+    nodes.sort_by_cached_key(|node| (is_compound(node), key(node)));
+    nodes
+}
+
+fn simplify(node: Node) -> Node {
+    match node {
+        Node::And(nodes) => simplify_and(nodes),
+        Node::Or(nodes) => simplify_or(nodes),
+        node => node,
+    }
+}
+
+fn simplify_and(nodes: Vec<Node>) -> Node {
+    let mut flat = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match simplify(node) {
+            Node::False => return Node::False,
+            Node::And(inner) => flat.extend(inner),
+            node => flat.push(node),
+        }
+    }
+    let nodes = dedup(flat);
+
+    // a & !a
+    for node in nodes.iter() {
+        if let Node::Lit(is_neg, ua) = node {
+            let contradiction = nodes
+                .iter()
+                .any(|other| matches!(other, Node::Lit(n, ub) if n != is_neg && ua == ub));
+            if contradiction {
+                return Node::False;
+            }
+        }
+    }
+
+    // a & (a | b) = a
+    let mut kept = vec![true; nodes.len()];
+    for i in 0..nodes.len() {
+        let rest: Vec<&Node> = (0..nodes.len())
+            .filter(|j| *j != i && kept[*j])
+            .flat_map(|j| conjuncts(&nodes[j]))
+            .collect();
+        if disjuncts(&nodes[i]).iter().any(|d| implies(&rest, d)) {
+            kept[i] = false;
+        }
+    }
+    let nodes: Vec<Node> = nodes
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(node, keep)| keep.then_some(node))
+        .collect();
+
+    if nodes.len() == 1 {
+        nodes.into_iter().next().unwrap()
+    } else {
+        Node::And(sort(nodes))
+    }
+}
+
+fn simplify_or(nodes: Vec<Node>) -> Node {
+    let mut flat = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match simplify(node) {
+            Node::False => {}
+            Node::Or(inner) => flat.extend(inner),
+            node => flat.push(node),
+        }
+    }
+    let nodes = dedup(flat);
+
+    // a | !a only checks that the user holds any attribute under the label of a,
+    // so it cannot be folded into a constant, but it absorbs other literals of that label
+    let mut folded = Vec::with_capacity(nodes.len());
+    for node in nodes.iter() {
+        match node {
+            Node::Lit(is_neg, ua) => {
+                let tautology = nodes
+                    .iter()
+                    .any(|other| matches!(other, Node::Lit(n, ub) if n != is_neg && ua == ub));
+                if tautology {
+                    folded.push(Node::Presence(ua.clone()));
+                } else {
+                    folded.push(node.clone());
+                }
+            }
+            _ => folded.push(node.clone()),
+        }
+    }
+    let nodes = dedup(folded);
+
+    // a | (a & b) = a
+    let mut kept = vec![true; nodes.len()];
+    for i in 0..nodes.len() {
+        let lhs = conjuncts(&nodes[i]);
+        let absorbed = (0..nodes.len()).any(|j| {
+            j != i && kept[j] && implies(&lhs, &nodes[j]) && {
+                // keep exactly one of two equivalent nodes
+                let rhs = conjuncts(&nodes[j]);
+                !implies(&rhs, &nodes[i]) || j < i
+            }
+        });
+        if absorbed {
+            kept[i] = false;
+        }
+    }
+    let nodes: Vec<Node> = nodes
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(node, keep)| keep.then_some(node))
+        .collect();
+
+    match nodes.len() {
+        0 => Node::False,
+        1 => nodes.into_iter().next().unwrap(),
+        _ => Node::Or(sort(nodes)),
+    }
+}
+
+#[cfg(test)]
+fn normalized(s: &str) -> Option<String> {
+    let policy = Policy::parse(s).unwrap();
+    normalize(&policy).map(|p| format!("{:?}", p))
+}
+
+#[test]
+fn test_flatten_and_order() {
+    assert_eq!(
+        normalized("C.c:0 & (B.b:0 & A.a:0)"),
+        Some(String::from("A.a:0 & B.b:0 & C.c:0"))
+    );
+    assert_eq!(normalized("B.b:0 | A.a:0"), normalized("A.a:0 | B.b:0"));
+    assert_eq!(
+        normalized("(B.b:0 | A.a:0) & C.c:0"),
+        Some(String::from("C.c:0 & (A.a:0 | B.b:0)"))
+    );
+}
+
+#[test]
+fn test_dedup_and_absorption() {
+    assert_eq!(normalized("A.a:0 & A.a:0"), Some(String::from("A.a:0")));
+    assert_eq!(normalized("A.a:0 | A.a:0"), Some(String::from("A.a:0")));
+    assert_eq!(
+        normalized("(A.b:0 & A.a:0) | A.a:0"),
+        Some(String::from("A.a:0"))
+    );
+    assert_eq!(
+        normalized("A.a:0 & (A.a:0 | A.b:0)"),
+        Some(String::from("A.a:0"))
+    );
+    assert_eq!(
+        normalized("(A.a:0 & B.b:0) | (B.b:0 & A.a:0 & C.c:0)"),
+        Some(String::from("A.a:0 & B.b:0"))
+    );
+}
+
+#[test]
+fn test_constant_folding() {
+    assert_eq!(normalized("A.a:0 & !A.a:0"), None);
+    assert_eq!(normalized("!(A.a:0 | !A.a:0)"), None);
+    assert_eq!(
+        normalized("(A.a:0 & !A.a:0) | B.b:0"),
+        Some(String::from("B.b:0"))
+    );
+    assert_eq!(
+        normalized("(A.a:0 | !A.a:0) & A.a:1"),
+        Some(String::from("A.a:1"))
+    );
+    assert_eq!(
+        normalized("A.a:0 | !A.a:0 | !A.a:1"),
+        Some(String::from("A.a:0 | !A.a:0"))
+    );
+    assert_eq!(
+        normalized("(A.a:0 | !A.a:0) & B.b:0"),
+        Some(String::from("B.b:0 & (A.a:0 | !A.a:0)"))
+    );
+}
+
+#[test]
+fn test_normalization_preserves_semantics() {
+    let policies = vec![
+        "A.a:0 | (!A.b:0 & A.a:2) & !(A.c:1 | A.c:2)",
+        "(A.a:0 | !A.a:0) & (A.a:1 | B.b:0) & A.a:1",
+        "(A.a:0 & !B.b:0) | (!B.b:0 & A.a:0 & C.c:0) | (A.a:0 & !A.a:0)",
+    ];
+    let users: Vec<Vec<UserAttribute>> = vec![
+        vec![],
+        vec!["A.a:0"],
+        vec!["A.a:1"],
+        vec!["A.a:0", "A.a:1"],
+        vec!["A.a:2", "A.b:1", "A.c:0"],
+        vec!["A.a:0", "B.b:1", "C.c:0"],
+        vec!["A.a:0", "B.b:0"],
+        vec!["A.a:3", "B.b:0"],
+    ]
+    .into_iter()
+    .map(|uas| {
+        uas.iter()
+            .map(|ua| UserAttribute::parse(ua).unwrap())
+            .collect()
+    })
+    .collect();
+    for policy in policies {
+        let policy = Policy::parse(policy).unwrap();
+        let normalized = normalize(&policy).unwrap();
+        assert!(normalized.len() <= policy.len());
+        for user_attrs in users.iter() {
+            assert_eq!(policy.evaluate(user_attrs), normalized.evaluate(user_attrs));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use super::{Expr, UserAttribute};
+
+// Numeric attributes are encoded as a "bag of bits": a value of n bits under (auth, lbl)
+// is issued as the n attributes auth.lbl:b{i}_{bit} (bit i counted from the least
+// significant bit), and comparisons in policies are compiled into formulas over these.
+#[derive(Debug, Clone, Default)]
+pub struct BitWidths {
+    widths: HashMap<(String, String), usize>,
+}
+
+impl BitWidths {
+    pub fn new() -> Self {
+        BitWidths {
+            widths: HashMap::new(),
+        }
+    }
+
+    pub fn declare(&mut self, auth: &str, lbl: &str, bits: usize) -> Result<&mut Self, String> {
+        if bits == 0 || bits > 64 {
+            return Err(format!(
+                "Bit width of {}.{} must be between 1 and 64, but {} was given",
+                auth, lbl, bits
+            ));
+        }
+        if self.get(auth, lbl).is_some() {
+            return Err(format!("Bit width of {}.{} is already declared", auth, lbl));
+        }
+        self.widths
+            .insert((String::from(auth), String::from(lbl)), bits);
+        Ok(self)
+    }
+
+    pub fn get(&self, auth: &str, lbl: &str) -> Option<usize> {
+        self.widths
+            .get(&(String::from(auth), String::from(lbl)))
+            .copied()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CmpOp {
+    pub(crate) fn complement(self) -> Self {
+        match self {
+            CmpOp::Lt => CmpOp::Ge,
+            CmpOp::Le => CmpOp::Gt,
+            CmpOp::Gt => CmpOp::Le,
+            CmpOp::Ge => CmpOp::Lt,
+            CmpOp::Eq => CmpOp::Ne,
+            CmpOp::Ne => CmpOp::Eq,
+        }
+    }
+}
+
+fn check_value(auth: &str, lbl: &str, value: u64, bits: usize) -> Result<(), String> {
+    if bits < 64 && value >> bits != 0 {
+        return Err(format!(
+            "Value {} does not fit into the {} bits declared for {}.{}",
+            value, bits, auth, lbl
+        ));
+    }
+    Ok(())
+}
+
+fn bit_attr(auth: &str, lbl: &str, i: usize, bit: u64) -> UserAttribute {
+    UserAttribute::new(auth, lbl, &format!("b{}_{}", i, bit))
+}
+
+pub(crate) fn bit_attrs(
+    auth: &str,
+    lbl: &str,
+    value: u64,
+    bits: usize,
+) -> Result<Vec<UserAttribute>, String> {
+    check_value(auth, lbl, value, bits)?;
+    Ok((0..bits)
+        .map(|i| bit_attr(auth, lbl, i, (value >> i) & 1))
+        .collect())
+}
+
+// Compiles `auth.lbl op value` into a formula over positive bit literals, the most
+// significant bit coming first.
+pub(crate) fn compile(
+    auth: &str,
+    lbl: &str,
+    op: CmpOp,
+    value: u64,
+    bits: usize,
+) -> Result<Expr<(bool, UserAttribute)>, String> {
+    check_value(auth, lbl, value, bits)?;
+    let max = if bits == 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    };
+    let lit = |i: usize, bit: u64| Expr::Lit((false, bit_attr(auth, lbl, i, bit)));
+    // every user with an issued value holds exactly one of the two most significant bits
+    let any = || {
+        Some(Expr::Or(
+            Box::new(lit(bits - 1, 0)),
+            Box::new(lit(bits - 1, 1)),
+        ))
+    };
+    let res = match op {
+        CmpOp::Gt => greater(bits, value, &lit),
+        CmpOp::Ge if value == 0 => any(),
+        CmpOp::Ge => greater(bits, value - 1, &lit),
+        CmpOp::Lt => less(bits, value, &lit),
+        CmpOp::Le if value == max => any(),
+        CmpOp::Le => less(bits, value + 1, &lit),
+        CmpOp::Eq => (0..bits)
+            .rev()
+            .map(|i| lit(i, (value >> i) & 1))
+            .reduce(|lhs, rhs| Expr::And(Box::new(lhs), Box::new(rhs))),
+        CmpOp::Ne => (0..bits)
+            .rev()
+            .map(|i| lit(i, 1 - ((value >> i) & 1)))
+            .reduce(|lhs, rhs| Expr::Or(Box::new(lhs), Box::new(rhs))),
+    };
+    res.ok_or(format!(
+        "Comparison {:?} {} on {}.{} can never be satisfied",
+        op, value, auth, lbl
+    ))
+}
+
+// x > c iff at the most significant bit where they differ, x has a one and c a zero.
+// The formula is built from the least significant bit upwards, None stands for false.
+fn greater(
+    bits: usize,
+    value: u64,
+    lit: &impl Fn(usize, u64) -> Expr<(bool, UserAttribute)>,
+) -> Option<Expr<(bool, UserAttribute)>> {
+    let mut acc = None;
+    for i in 0..bits {
+        let x = lit(i, 1);
+        acc = if (value >> i) & 1 == 1 {
+            acc.map(|rest| Expr::And(Box::new(x), Box::new(rest)))
+        } else {
+            match acc {
+                None => Some(x),
+                Some(rest) => Some(Expr::Or(Box::new(x), Box::new(rest))),
+            }
+        };
+    }
+    acc
+}
+
+fn less(
+    bits: usize,
+    value: u64,
+    lit: &impl Fn(usize, u64) -> Expr<(bool, UserAttribute)>,
+) -> Option<Expr<(bool, UserAttribute)>> {
+    let mut acc = None;
+    for i in 0..bits {
+        let x = lit(i, 0);
+        acc = if (value >> i) & 1 == 0 {
+            acc.map(|rest| Expr::And(Box::new(x), Box::new(rest)))
+        } else {
+            match acc {
+                None => Some(x),
+                Some(rest) => Some(Expr::Or(Box::new(x), Box::new(rest))),
+            }
+        };
+    }
+    acc
+}
+
+#[test]
+fn test_numeric_comparisons() {
+    use super::Policy;
+
+    let mut widths = BitWidths::new();
+    widths.declare("A", "age", 4).unwrap();
+    let ops = ["<", "<=", ">", ">=", "==", "!="];
+    let cmp = |op: &str, x: u64, c: u64| match op {
+        "<" => x < c,
+        "<=" => x <= c,
+        ">" => x > c,
+        ">=" => x >= c,
+        "==" => x == c,
+        _ => x != c,
+    };
+    for op in ops {
+        for c in 0..16 {
+            let policy = Policy::parse_with_widths(&format!("A.age {} {}", op, c), &widths);
+            let neg_policy = Policy::parse_with_widths(&format!("!(A.age {} {})", op, c), &widths);
+            for x in 0..16 {
+                let user_attrs = UserAttribute::numeric("A", "age", x, &widths).unwrap();
+                match &policy {
+                    Ok(policy) => assert_eq!(policy.evaluate(&user_attrs), cmp(op, x, c)),
+                    Err(_) => assert!(!cmp(op, x, c)),
+                }
+                match &neg_policy {
+                    Ok(policy) => assert_eq!(policy.evaluate(&user_attrs), !cmp(op, x, c)),
+                    Err(_) => assert!(cmp(op, x, c)),
+                }
+            }
+        }
+    }
+
+    assert!(Policy::parse_with_widths("A.age >= 16", &widths).is_err());
+    assert!(Policy::parse("A.age >= 3").is_err());
+    assert!(UserAttribute::numeric("A", "age", 16, &widths).is_err());
+    assert!(UserAttribute::numeric("A", "height", 3, &widths).is_err());
+}
+
+#[test]
+fn test_declare_bit_widths() {
+    let mut widths = BitWidths::new();
+    assert!(widths.declare("A", "age", 0).is_err());
+    assert!(widths.declare("A", "age", 65).is_err());
+    assert_eq!(widths.get("A", "age"), None);
+    widths
+        .declare("A", "age", 64)
+        .unwrap()
+        .declare("B", "age", 1)
+        .unwrap();
+    assert!(widths.declare("A", "age", 7).is_err());
+    assert_eq!(widths.get("A", "age"), Some(64));
+    assert_eq!(widths.get("B", "age"), Some(1));
+}
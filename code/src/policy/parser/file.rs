@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use super::lexer::{lex_with_locs, Token};
+use super::{BitWidths, Expr, ParseResult, Parser, UserAttribute};
+
+// A policy file consists of `let name = <expr>;` definitions and exactly one policy
+// expression, which may refer to the definitions by name. Definitions may be given in
+// any order and refer to each other, as long as there are no cycles. Everything after
+// a `#` up to the end of the line is a comment. `let` is a reserved word, so an authority,
+// label or value named `let` has to be quoted as `"let"`.
+//
+//     # fragments
+//     let is_employee = hr.role in {staff, manager};
+//     let in_eu = geo.region:eu;
+//
+//     is_employee & in_eu
+//
+// References are expanded by parsing the tokens of the definition again at the place
+// of the reference, so negations are pushed down into the definition like everywhere
+// else and literals are numbered in the order of the expanded policy.
+impl<'a> Parser<'a> {
+    pub fn parse_file(input: &str, widths: &BitWidths) -> ParseResult {
+        let (tokens, locs) = lex_with_locs(input)?;
+        let mut parser = Parser::new(tokens, widths);
+        let res = parser.file();
+        if parser.had_error {
+            let msg = parser.err_msg.as_ref().unwrap();
+            return Err(match locs.get(parser.err_idx) {
+                Some((line, col)) => format!("line {}, column {}: {}", line, col, msg),
+                None => format!("end of input: {}", msg),
+            });
+        }
+        match res {
+            None => panic!("Unreachable"),
+            Some(exp) => Ok((exp, parser.attrs, parser.negs)),
+        }
+    }
+
+    fn file(&mut self) -> Option<Expr<(bool, UserAttribute)>> {
+        let mut defs = HashMap::new();
+        let mut order = Vec::new();
+        let mut main = None;
+        while self.peek().is_some() {
+            let start = self.curr;
+            let end = (start..self.tokens.len())
+                .find(|i| self.tokens[*i] == Token::Semicolon)
+                .unwrap_or(self.tokens.len());
+            if self.try_next(Token::Let).is_some() {
+                let name = match self.next() {
+                    Some(Token::Ident(name)) => name,
+                    t => {
+                        self.set_err_msg(&format!("Found token '{:?}' but a name was expected", t));
+                        return None;
+                    }
+                };
+                self.require(Token::Assign)?;
+                if end == self.tokens.len() {
+                    self.set_err_msg(&format!("Missing ';' after the definition of '{}'", name));
+                    return None;
+                }
+                if defs.insert(name.clone(), (self.curr, end)).is_some() {
+                    self.curr = start + 2;
+                    self.set_err_msg(&format!("'{}' is defined more than once", name));
+                    return None;
+                }
+                order.push(name);
+            } else if main.is_some() {
+                self.curr = start + 1;
+                self.set_err_msg("A policy file must contain exactly one policy expression");
+                return None;
+            } else {
+                main = Some((start, end));
+            }
+            self.curr = end + 1;
+        }
+        let Some((start, end)) = main else {
+            self.set_err_msg("A policy file must contain exactly one policy expression");
+            return None;
+        };
+        self.defs = Some(defs);
+
+        // every definition is checked once, even if it is never referenced
+        for name in order {
+            let (num_attrs, num_negs) = (self.attrs.len(), self.negs.len());
+            self.expand(&name)?;
+            self.attrs.truncate(num_attrs);
+            self.negs.truncate(num_negs);
+        }
+        self.curr = start;
+        let exp = self.or()?;
+        self.expect_end(end)?;
+        Some(exp)
+    }
+
+    // A name is an identifier which does not start a literal
+    pub(super) fn peek_ref(&mut self) -> Option<String> {
+        self.defs.as_ref()?;
+        match (self.tokens.get(self.curr), self.tokens.get(self.curr + 1)) {
+            (Some(Token::Ident(name)), next) if next != Some(&Token::Dot) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    pub(super) fn reference(&mut self, name: String) -> Option<Expr<(bool, UserAttribute)>> {
+        self.advance();
+        self.expand(&name)
+    }
+
+    fn expand(&mut self, name: &str) -> Option<Expr<(bool, UserAttribute)>> {
+        let Some(&(start, end)) = self.defs.as_ref().unwrap().get(name) else {
+            self.set_err_msg(&format!("'{}' is not defined", name));
+            return None;
+        };
+        if self.expanding.iter().any(|n| n == name) {
+            let mut cycle = self.expanding.clone();
+            cycle.push(String::from(name));
+            self.set_err_msg(&format!("Cyclic definition: {}", cycle.join(" -> ")));
+            return None;
+        }
+        self.expanding.push(String::from(name));
+        let ret = self.curr;
+        self.curr = start;
+        let exp = self.or()?;
+        self.expect_end(end)?;
+        self.curr = ret;
+        self.expanding.pop();
+        Some(exp)
+    }
+
+    fn expect_end(&mut self, end: usize) -> Option<()> {
+        if self.curr != end {
+            let t = self.next();
+            self.set_err_msg(&format!("Unexpected token '{:?}'", t.unwrap()));
+            return None;
+        }
+        Some(())
+    }
+}
+
+#[test]
+fn test_parse_file() {
+    use super::super::Policy;
+
+    let src = "
+        # fragments, in any order
+        let staff = is_employee & !on_leave;
+        let is_employee = hr.role in {staff, manager};
+        let on_leave = hr.status:leave;
+        let in_eu = geo.region:eu;   # unused definitions are allowed
+
+        staff & (geo.region:eu | !in_eu)
+    ";
+    let policy = Policy::parse_file(src).unwrap();
+    let expected = Policy::parse(
+        "(hr.role:staff | hr.role:manager) & !hr.status:leave & (geo.region:eu | !geo.region:eu)",
+    )
+    .unwrap();
+    assert!(policy == expected);
+
+    let err = Policy::parse_file("let a = b;\nlet b = A.a:0 | c;\nlet c = a;\na").unwrap_err();
+    assert_eq!(err, "line 3, column 9: Cyclic definition: a -> b -> c -> a");
+
+    let err = Policy::parse_file("let a = A.a:0;\n\na & b").unwrap_err();
+    assert_eq!(err, "line 3, column 5: 'b' is not defined");
+
+    let err = Policy::parse_file("let a = A.a:0;\nlet a = A.a:1;\na").unwrap_err();
+    assert_eq!(err, "line 2, column 5: 'a' is defined more than once");
+
+    let err = Policy::parse_file("let a = A.a:0 B.b:0;\na").unwrap_err();
+    assert_eq!(err, "line 1, column 15: Unexpected token 'Ident(\"B\")'");
+
+    let err = Policy::parse_file("let a = A.a:0;\na;\na").unwrap_err();
+    assert_eq!(
+        err,
+        "line 3, column 1: A policy file must contain exactly one policy expression"
+    );
+
+    let err = Policy::parse_file("let a = A.a:0").unwrap_err();
+    assert_eq!(
+        err,
+        "line 1, column 7: Missing ';' after the definition of 'a'"
+    );
+
+    let err = Policy::parse_file("let a = ;\na").unwrap_err();
+    assert_eq!(err, "line 1, column 9: Incomplete or missing attribute");
+
+    let err = Policy::parse_file("let a = let.b:c;\na").unwrap_err();
+    assert_eq!(
+        err,
+        "Reserved word 'let' used in an attribute at index 8, quote it to use it as a name"
+    );
+    let err = Policy::parse_file("let a = A.let:c;\na").unwrap_err();
+    assert!(err.starts_with("Reserved word 'let'"));
+    let err = Policy::parse_file("let let = A.a:0;\nA.a:1").unwrap_err();
+    assert_eq!(
+        err,
+        "line 1, column 5: Found token 'Some(Let)' but a name was expected"
+    );
+    let policy = Policy::parse_file("let a = A.\"let\":c;\na").unwrap();
+    assert_eq!(format!("{:?}", policy), "A.\"let\":c");
+    assert!(Policy::parse(&format!("{:?}", policy)).unwrap() == policy);
+
+    let err = Policy::parse_file("# only a comment").unwrap_err();
+    assert_eq!(
+        err,
+        "end of input: A policy file must contain exactly one policy expression"
+    );
+}
@@ -1,5 +1,3 @@
-use std::str::from_utf8;
-
 #[derive(PartialEq, Debug, Clone)]
 pub enum Token {
     LParen,
@@ -9,77 +7,251 @@ pub enum Token {
     Not,
     Colon,
     Dot,
+    LBrace,
+    RBrace,
+    Comma,
+    Assign,
+    Semicolon,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Let,
     Ident(String),
 }
 
-pub fn lex(input: &str) -> Vec<Token> {
-    if !input.is_ascii() {
-        panic!("Lexing error: policy must only contain ASCII character");
-    }
+pub fn lex(input: &str) -> Result<Vec<Token>, String> {
+    Ok(lex_with_locs(input)?.0)
+}
+
+type Locs = Vec<(usize, usize)>;
+
+// Additionally returns the (line, column) at which each token starts, both counted from 1
+pub fn lex_with_locs(input: &str) -> Result<(Vec<Token>, Locs), String> {
     let mut tokens = Vec::new();
+    let mut starts = Vec::new();
     let mut idx = 0;
-    let input = input.as_bytes();
+    let input: Vec<char> = input.chars().collect();
     while idx < input.len() {
+        let start = idx;
+        let num_tokens = tokens.len();
         match input[idx] {
-            b' ' | b'\r' | b'\t' | b'\n' => idx = idx + 1,
-            b'(' => {
+            ' ' | '\r' | '\t' | '\n' => idx += 1,
+            '#' => {
+                while idx < input.len() && input[idx] != '\n' {
+                    idx += 1
+                }
+            }
+            '(' => {
                 tokens.push(Token::LParen);
-                idx = idx + 1
+                idx += 1
             }
-            b')' => {
+            ')' => {
                 tokens.push(Token::RParen);
-                idx = idx + 1
+                idx += 1
             }
-            b':' => {
+            ':' => {
                 tokens.push(Token::Colon);
-                idx = idx + 1
+                idx += 1
             }
-            b'.' => {
+            '.' => {
                 tokens.push(Token::Dot);
-                idx = idx + 1
+                idx += 1
+            }
+            '{' => {
+                tokens.push(Token::LBrace);
+                idx += 1
+            }
+            '}' => {
+                tokens.push(Token::RBrace);
+                idx += 1
             }
-            b'!' => {
+            ',' => {
+                tokens.push(Token::Comma);
+                idx += 1
+            }
+            '!' if input.get(idx + 1) == Some(&'=') => {
+                tokens.push(Token::Ne);
+                idx += 2
+            }
+            '!' => {
                 tokens.push(Token::Not);
-                idx = idx + 1
+                idx += 1
+            }
+            '=' if input.get(idx + 1) == Some(&'=') => {
+                tokens.push(Token::Eq);
+                idx += 2
+            }
+            '=' => {
+                tokens.push(Token::Assign);
+                idx += 1
+            }
+            ';' => {
+                tokens.push(Token::Semicolon);
+                idx += 1
+            }
+            '<' if input.get(idx + 1) == Some(&'=') => {
+                tokens.push(Token::Le);
+                idx += 2
+            }
+            '<' => {
+                tokens.push(Token::Lt);
+                idx += 1
+            }
+            '>' if input.get(idx + 1) == Some(&'=') => {
+                tokens.push(Token::Ge);
+                idx += 2
             }
-            b'&' => {
+            '>' => {
+                tokens.push(Token::Gt);
+                idx += 1
+            }
+            '&' => {
                 tokens.push(Token::And);
-                idx = idx + 1
+                idx += 1
             }
-            b'|' => {
+            '|' => {
                 tokens.push(Token::Or);
-                idx = idx + 1
+                idx += 1
+            }
+            '"' => {
+                let (token, i) = quoted(&input, idx)?;
+                tokens.push(token);
+                idx = i
             }
             _ => {
-                let (token, i) = ident(input, idx);
+                let (token, i) = ident(&input, idx)?;
                 tokens.push(token);
                 idx = i
             }
         }
+        if tokens.len() > num_tokens {
+            starts.push(start);
+            check_reserved(&tokens, &starts)?;
+        }
+    }
+    let mut locs = Vec::with_capacity(starts.len());
+    let (mut line, mut col, mut idx) = (1, 1, 0);
+    for start in starts {
+        while idx < start {
+            if input[idx] == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+            idx += 1;
+        }
+        locs.push((line, col));
     }
-    return tokens;
+    Ok((tokens, locs))
 }
 
-fn ident(input: &[u8], start: usize) -> (Token, usize) {
+// `let` starts a definition in policy files, so it may only appear unquoted there and
+// never as the authority, label or value of an attribute
+fn check_reserved(tokens: &[Token], starts: &[usize]) -> Result<(), String> {
+    let n = tokens.len();
+    let at = match tokens {
+        [.., Token::Dot | Token::Colon, Token::Let] => starts[n - 1],
+        [.., Token::Let, Token::Dot] => starts[n - 2],
+        _ => return Ok(()),
+    };
+    Err(format!(
+        "Reserved word 'let' used in an attribute at index {}, quote it to use it as a name",
+        at
+    ))
+}
+
+pub(crate) fn is_reserved(s: &str) -> bool {
+    s == "let"
+}
+
+pub(crate) fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn ident(input: &[char], start: usize) -> Result<(Token, usize), String> {
     let mut end = start;
-    while end < input.len() && (input[end].is_ascii_alphanumeric() || input[end] == b'_') {
-        end = end + 1;
+    while end < input.len() && is_ident_char(input[end]) {
+        end += 1;
     }
-    let str = from_utf8(&input[start..end]).unwrap();
-    if str.is_empty() {
-        panic!(
+    if start == end {
+        return Err(format!(
             "Illegal character '{}' found at index {}",
-            from_utf8(&input[start..start + 1]).unwrap(),
-            end
-        );
+            input[start], end
+        ));
+    }
+    let ident: String = input[start..end].iter().collect();
+    if is_reserved(&ident) {
+        return Ok((Token::Let, end));
+    }
+    Ok((Token::Ident(ident), end))
+}
+
+// Quoted identifiers may contain any character. Quotes and backslashes are escaped
+// with a backslash, and \n, \t and \u{..} are supported as well.
+fn quoted(input: &[char], start: usize) -> Result<(Token, usize), String> {
+    let mut str = String::new();
+    let mut end = start + 1;
+    loop {
+        match input.get(end) {
+            None => {
+                return Err(format!(
+                    "Unterminated quoted identifier starting at index {}",
+                    start
+                ))
+            }
+            Some('"') => break,
+            Some('\\') => {
+                let (c, i) = escape(input, end + 1)?;
+                str.push(c);
+                end = i
+            }
+            Some(c) => {
+                str.push(*c);
+                end += 1
+            }
+        }
+    }
+    if str.is_empty() {
+        return Err(format!("Empty quoted identifier found at index {}", start));
+    }
+    Ok((Token::Ident(str), end + 1))
+}
+
+fn escape(input: &[char], start: usize) -> Result<(char, usize), String> {
+    match input.get(start) {
+        Some('"') => Ok(('"', start + 1)),
+        Some('\\') => Ok(('\\', start + 1)),
+        Some('n') => Ok(('\n', start + 1)),
+        Some('t') => Ok(('\t', start + 1)),
+        Some('u') if input.get(start + 1) == Some(&'{') => {
+            let Some(close) = (start + 2..input.len()).find(|i| input[*i] == '}') else {
+                return Err(format!(
+                    "Unterminated unicode escape found at index {}",
+                    start
+                ));
+            };
+            let hex: String = input[start + 2..close].iter().collect();
+            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                Some(c) => Ok((c, close + 1)),
+                None => Err(format!(
+                    "Invalid unicode escape '{}' found at index {}",
+                    hex, start
+                )),
+            }
+        }
+        Some(c) => Err(format!("Illegal escape '\\{}' found at index {}", c, start)),
+        None => Err(format!("Unterminated escape found at index {}", start)),
     }
-    (Token::Ident(String::from(str)), end)
 }
 
 #[test]
 fn test_lexer() {
     let input = "x.b:a & (!x.b:a2 | orr.y:u) | anda.z:z";
-    let tokens = lex(input);
+    let tokens = lex(input).unwrap();
     assert_eq!(tokens.len(), 26);
     assert_eq!(tokens[0], Token::Ident(String::from("x")));
     assert_eq!(tokens[1], Token::Dot);
@@ -108,3 +280,87 @@ fn test_lexer() {
     assert_eq!(tokens[24], Token::Colon);
     assert_eq!(tokens[25], Token::Ident(String::from("z")));
 }
+
+#[test]
+fn test_lexer_comparisons() {
+    let tokens = lex("A.a<1 A.a<=1 A.a>1 A.a>=1 A.a==1 A.a!=1 !A.a:1").unwrap();
+    let ops: Vec<Token> = tokens
+        .iter()
+        .filter(|t| !matches!(t, Token::Ident(_) | Token::Dot))
+        .cloned()
+        .collect();
+    assert_eq!(
+        ops,
+        vec![
+            Token::Lt,
+            Token::Le,
+            Token::Gt,
+            Token::Ge,
+            Token::Eq,
+            Token::Ne,
+            Token::Not,
+            Token::Colon
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_quoted() {
+    let tokens = lex(r#"geo.city:"S\u{e3}o \"Paulo\"" | hr.email:"alice@corp.example""#).unwrap();
+    assert_eq!(tokens[4], Token::Ident(String::from("São \"Paulo\"")));
+    assert_eq!(tokens[5], Token::Or);
+    assert_eq!(tokens[10], Token::Ident(String::from("alice@corp.example")));
+
+    let tokens = lex("geo.stadt:München").unwrap();
+    assert_eq!(tokens[4], Token::Ident(String::from("München")));
+}
+
+#[test]
+fn test_lexer_locs() {
+    let (tokens, locs) = lex_with_locs("# comment\nlet x = A.a:0;\n  x | \"B\".b:1").unwrap();
+    assert_eq!(tokens.len(), 16);
+    assert_eq!(tokens[0], Token::Let);
+    assert_eq!(tokens[2], Token::Assign);
+    assert_eq!(tokens[8], Token::Semicolon);
+    assert_eq!(locs[0], (2, 1));
+    assert_eq!(locs[3], (2, 9));
+    assert_eq!(locs[9], (3, 3));
+    assert_eq!(locs[11], (3, 7));
+}
+
+#[test]
+fn test_lexer_errors() {
+    let err = lex("A.a:0 & A.b:1 $ A.c:2").unwrap_err();
+    assert_eq!(err, "Illegal character '$' found at index 14");
+    let err = lex("A.a:\"unterminated").unwrap_err();
+    assert_eq!(err, "Unterminated quoted identifier starting at index 4");
+    let err = lex("A.a:\"\"").unwrap_err();
+    assert_eq!(err, "Empty quoted identifier found at index 4");
+    let err = lex(r#"A.a:"x\q""#).unwrap_err();
+    assert_eq!(err, "Illegal escape '\\q' found at index 7");
+    let err = lex(r#"A.a:"x\"#).unwrap_err();
+    assert_eq!(err, "Unterminated escape found at index 7");
+    let err = lex(r#"A.a:"\u{110000}""#).unwrap_err();
+    assert_eq!(err, "Invalid unicode escape '110000' found at index 6");
+    let err = lex(r#"A.a:"\u{e3""#).unwrap_err();
+    assert_eq!(err, "Unterminated unicode escape found at index 6");
+}
+
+#[test]
+fn test_lexer_reserved() {
+    let tokens = lex("let a = A.b:c;").unwrap();
+    assert_eq!(tokens[0], Token::Let);
+    assert_eq!(
+        lex("\"let\".\"let\":\"let\"").unwrap()[0],
+        Token::Ident(String::from("let"))
+    );
+
+    let err = lex("A.b:c | let.b:c").unwrap_err();
+    assert_eq!(
+        err,
+        "Reserved word 'let' used in an attribute at index 8, quote it to use it as a name"
+    );
+    assert!(lex("A.let:c").is_err());
+    assert!(lex("A.b:let").is_err());
+    assert!(lex("A.b:lets & A.lets:b").is_ok());
+}
@@ -1,35 +1,54 @@
+use std::collections::HashMap;
+
+use super::numeric::{self, BitWidths, CmpOp};
 use super::{Expr, UserAttribute};
 
+mod file;
 mod lexer;
 
 use lexer::{lex, Token};
 
-pub struct Parser {
+pub(super) use lexer::{is_ident_char, is_reserved};
+
+type ParseResult = Result<(Expr<(bool, UserAttribute)>, Vec<UserAttribute>, Vec<bool>), String>;
+
+pub struct Parser<'a> {
     tokens: Vec<Token>,
+    widths: &'a BitWidths,
     attrs: Vec<UserAttribute>,
     negs: Vec<bool>,
     curr: usize,
     is_neg: bool,
     err_msg: Option<String>,
+    err_idx: usize,
     had_error: bool,
+    // token ranges of the let-bindings of a policy file, see `parse_file`
+    defs: Option<HashMap<String, (usize, usize)>>,
+    expanding: Vec<String>,
 }
 
-impl Parser {
-    fn new(tokens: Vec<Token>) -> Self {
+impl<'a> Parser<'a> {
+    fn new(tokens: Vec<Token>, widths: &'a BitWidths) -> Self {
         Parser {
             tokens,
+            widths,
             attrs: Vec::new(),
             negs: Vec::new(),
             curr: 0,
             is_neg: false,
             err_msg: None,
+            err_idx: 0,
             had_error: false,
+            defs: None,
+            expanding: Vec::new(),
         }
     }
 
     fn set_err_msg(&mut self, msg: &str) {
         if !self.had_error {
             self.err_msg = Some(String::from(msg));
+            // the token which caused the error has usually been consumed already
+            self.err_idx = self.curr.saturating_sub(1);
         }
         self.had_error = true;
     }
@@ -76,11 +95,9 @@ impl Parser {
         }
     }
 
-    pub fn parse_policy(
-        input: &str,
-    ) -> Result<(Expr<(bool, UserAttribute)>, Vec<UserAttribute>, Vec<bool>), String> {
-        let tokens = lex(input);
-        let mut parser = Parser::new(tokens);
+    pub fn parse_policy(input: &str, widths: &BitWidths) -> ParseResult {
+        let tokens = lex(input)?;
+        let mut parser = Parser::new(tokens, widths);
 
         let res = parser.or();
         if parser.had_error {
@@ -93,15 +110,19 @@ impl Parser {
     }
 
     pub fn parse_user_attr(attr: &str) -> Result<(String, String, String), String> {
-        let tokens = lex(attr);
-        let mut parser = Parser::new(tokens);
+        let tokens = lex(attr)?;
+        let widths = BitWidths::new();
+        let mut parser = Parser::new(tokens, &widths);
         let res = parser.lit();
         if parser.had_error {
             return Err(parser.err_msg.as_ref().unwrap().clone());
         }
         match res {
             Some(Expr::Lit((_, user_attr))) => Ok((user_attr.auth, user_attr.lbl, user_attr.attr)),
-            _ => panic!("Unreachable"),
+            Some(_) => Err(String::from(
+                "Expected a single user attribute but found a compound expression",
+            )),
+            None => panic!("Unreachable"),
         }
     }
 
@@ -147,6 +168,9 @@ impl Parser {
             self.require(Token::RParen);
             return Some(exp);
         }
+        if let Some(name) = self.peek_ref() {
+            return self.reference(name);
+        }
         self.lit()
     }
 
@@ -154,6 +178,14 @@ impl Parser {
         if let Some(Token::Ident(auth)) = self.next() {
             self.require(Token::Dot);
             if let Some(Token::Ident(lbl)) = self.next() {
+                if let Some(op) = self.cmp_op() {
+                    return self.comparison(&auth, &lbl, op);
+                }
+                if let Some(Token::Ident(kw)) = self.peek() {
+                    if kw == "in" || kw == "not" {
+                        return self.membership(&auth, &lbl);
+                    }
+                }
                 self.require(Token::Colon);
                 if let Some(Token::Ident(attr)) = self.next() {
                     let user_attr = UserAttribute::new(&auth, &lbl, &attr);
@@ -163,8 +195,104 @@ impl Parser {
                 }
             }
         }
+        self.set_err_msg("Incomplete or missing attribute");
         None
     }
+
+    // `auth.lbl in {a, b}` is lowered to `auth.lbl:a | auth.lbl:b` and
+    // `auth.lbl not in {a, b}` to `!auth.lbl:a & !auth.lbl:b`
+    fn membership(&mut self, auth: &str, lbl: &str) -> Option<Expr<(bool, UserAttribute)>> {
+        let mut is_neg = self.is_neg;
+        if self.try_next(Token::Ident(String::from("not"))).is_some() {
+            is_neg = !is_neg;
+        }
+        self.require(Token::Ident(String::from("in")))?;
+        self.require(Token::LBrace)?;
+        let mut expr: Option<Expr<(bool, UserAttribute)>> = None;
+        loop {
+            let attr = match self.next() {
+                Some(Token::Ident(attr)) => attr,
+                Some(Token::RBrace) if expr.is_none() => {
+                    self.set_err_msg(&format!("Empty set given for {}.{}", auth, lbl));
+                    return None;
+                }
+                t => {
+                    self.set_err_msg(&format!(
+                        "Found token '{:?}' but an attribute was expected",
+                        t
+                    ));
+                    return None;
+                }
+            };
+            let user_attr = UserAttribute::new(auth, lbl, &attr);
+            self.attrs.push(user_attr.clone());
+            self.negs.push(is_neg);
+            let lit = Expr::Lit((is_neg, user_attr));
+            expr = Some(match expr {
+                None => lit,
+                Some(lhs) if is_neg => Expr::And(Box::new(lhs), Box::new(lit)),
+                Some(lhs) => Expr::Or(Box::new(lhs), Box::new(lit)),
+            });
+            if self.try_next(Token::Comma).is_none() {
+                break;
+            }
+        }
+        self.require(Token::RBrace)?;
+        expr
+    }
+
+    fn cmp_op(&mut self) -> Option<CmpOp> {
+        let op = match self.peek()? {
+            Token::Lt => CmpOp::Lt,
+            Token::Le => CmpOp::Le,
+            Token::Gt => CmpOp::Gt,
+            Token::Ge => CmpOp::Ge,
+            Token::Eq => CmpOp::Eq,
+            Token::Ne => CmpOp::Ne,
+            _ => return None,
+        };
+        self.advance();
+        Some(op)
+    }
+
+    // A comparison is compiled into bit literals right away. Negations are applied to
+    // the operator, so that the resulting bit literals are all positive.
+    fn comparison(
+        &mut self,
+        auth: &str,
+        lbl: &str,
+        op: CmpOp,
+    ) -> Option<Expr<(bool, UserAttribute)>> {
+        let value = match self.next() {
+            Some(Token::Ident(value)) => value,
+            t => {
+                self.set_err_msg(&format!("Found token '{:?}' but a number was expected", t));
+                return None;
+            }
+        };
+        let Ok(value) = value.parse::<u64>() else {
+            self.set_err_msg(&format!("'{}' is not a valid number", value));
+            return None;
+        };
+        let Some(bits) = self.widths.get(auth, lbl) else {
+            self.set_err_msg(&format!("No bit width declared for {}.{}", auth, lbl));
+            return None;
+        };
+        let op = if self.is_neg { op.complement() } else { op };
+        match numeric::compile(auth, lbl, op, value, bits) {
+            Ok(expr) => {
+                for (is_neg, user_attr) in expr.literals() {
+                    self.attrs.push(user_attr.clone());
+                    self.negs.push(*is_neg);
+                }
+                Some(expr)
+            }
+            Err(msg) => {
+                self.set_err_msg(&msg);
+                None
+            }
+        }
+    }
 }
 
 #[test]
@@ -188,7 +316,7 @@ fn test_parser() {
     }
 
     let policy = "x.b:a & !(!x.b:a2 | orr.y:u) | anda.z:z";
-    let (expr, _, _) = Parser::parse_policy(&policy).unwrap();
+    let (expr, _, _) = Parser::parse_policy(&policy, &BitWidths::new()).unwrap();
 
     assert_eq!(
         expr,
@@ -198,3 +326,87 @@ fn test_parser() {
         )
     );
 }
+
+#[test]
+fn test_parser_comparison() {
+    let mut widths = BitWidths::new();
+    widths.declare("A", "age", 3).unwrap();
+    let (expr, attrs, negs) = Parser::parse_policy("B.x:y & !(A.age < 5)", &widths).unwrap();
+
+    // !(age < 5) == (age >= 5) == b2_1 & (b1_1 | b0_1)
+    let bit = |s: &str| Box::new(Expr::Lit((false, UserAttribute::new("A", "age", s))));
+    assert_eq!(
+        expr,
+        Expr::And(
+            Box::new(Expr::Lit((false, UserAttribute::new("B", "x", "y")))),
+            Box::new(Expr::And(
+                bit("b2_1"),
+                Box::new(Expr::Or(bit("b1_1"), bit("b0_1")))
+            )),
+        )
+    );
+    assert_eq!(attrs.len(), 4);
+    assert!(negs.iter().all(|is_neg| !is_neg));
+
+    let err = Parser::parse_policy("A.age < x", &widths).unwrap_err();
+    assert_eq!(err, "'x' is not a valid number");
+    let err = Parser::parse_policy("A.height < 3", &widths).unwrap_err();
+    assert_eq!(err, "No bit width declared for A.height");
+}
+
+#[test]
+fn test_parser_unicode() {
+    use super::Policy;
+
+    // "São Paulo" once precomposed and once with a combining tilde
+    let composed = "geo.city:\"S\u{e3}o Paulo\"";
+    let decomposed = "geo.city:\"Sa\u{303}o Paulo\"";
+    let user_attr = UserAttribute::parse(decomposed).unwrap();
+    assert_eq!(user_attr, UserAttribute::parse(composed).unwrap());
+    assert_eq!(user_attr.attr, "S\u{e3}o Paulo");
+
+    let policy = Policy::parse(&format!("{} & dept.name:\"R&D\"", decomposed)).unwrap();
+    assert_eq!(policy.get(0).0, user_attr);
+    assert_eq!(policy.get(1).0, UserAttribute::new("dept", "name", "R&D"));
+
+    // the debug output quotes and escapes where necessary and parses back
+    let user_attr = UserAttribute::new("hr", "email", "\"alice\"@corp.example\\");
+    assert_eq!(
+        format!("{:?}", user_attr),
+        r#"hr.email:"\"alice\"@corp.example\\""#
+    );
+    let policy = Policy::parse(&format!("{:?} | !geo.city:München", user_attr)).unwrap();
+    assert_eq!(policy, Policy::parse(&format!("{:?}", policy)).unwrap());
+
+    // malformed input is rejected rather than aborting the parse
+    assert!(Policy::parse(r#"geo.city:"S\u{zz}o""#).is_err());
+    assert!(Policy::parse("geo.city:\"Paulo").is_err());
+    assert!(Policy::parse("geo.city:\"\" | A.a:0").is_err());
+    assert!(Policy::parse("A.a:0 @ A.b:0").is_err());
+    assert!(UserAttribute::parse("A.a:\"x\\").is_err());
+}
+
+#[test]
+fn test_parser_membership() {
+    use super::Policy;
+
+    let policy = Policy::parse("hr.dept in {eng, ops, sec} & !(hr.lvl not in {a})").unwrap();
+    let expected = Policy::parse("(hr.dept:eng | hr.dept:ops | hr.dept:sec) & hr.lvl:a").unwrap();
+    assert!(policy == expected);
+
+    let policy = Policy::parse("!(hr.dept in {eng, ops}) | hr.dept not in {\"R&D\"}").unwrap();
+    let expected = Policy::parse("!hr.dept:eng & !hr.dept:ops | !hr.dept:\"R&D\"").unwrap();
+    assert!(policy == expected);
+
+    // sets are printed back in compact form
+    let policy = Policy::parse("A.a:0 & (hr.dept:eng | hr.dept:ops) | !B.b:0 & !B.b:1").unwrap();
+    assert_eq!(
+        format!("{:?}", policy),
+        "A.a:0 & hr.dept in {eng, ops} | B.b not in {0, 1}"
+    );
+    assert!(policy == Policy::parse(&format!("{:?}", policy)).unwrap());
+
+    assert!(Policy::parse("hr.dept in {}").is_err());
+    assert!(Policy::parse("hr.dept not {eng}").is_err());
+    assert!(Policy::parse("hr.dept in {eng ops}").is_err());
+}
@@ -1,5 +1,11 @@
+#[cfg(test)]
+use std::collections::BTreeSet;
+
 use crate::policy::{Expr, Policy};
 
+#[cfg(test)]
+use super::evaluation::minimize;
+
 use super::UserAttribute;
 
 pub fn share_secret(policy: &Policy) -> Vec<(UserAttribute, Vec<i64>)> {
@@ -32,7 +38,11 @@ fn helper(
     }
 }
 
-fn satisfies(user_attrs: &Vec<UserAttribute>, curr: &UserAttribute, is_neg: bool) -> Option<usize> {
+pub fn satisfies(
+    user_attrs: &[UserAttribute],
+    curr: &UserAttribute,
+    is_neg: bool,
+) -> Option<usize> {
     let mut matches = 0;
     let mut others = 0;
     for user_attr in user_attrs {
@@ -106,6 +116,183 @@ fn aux(
     }
 }
 
+// All inclusion-minimal sets of rows the user satisfies and which suffice to reconstruct
+// the secret. Their number can grow exponentially with the number of AND-ed disjunctions,
+// so this only serves as a reference for `cheapest_row_set` in tests.
+#[cfg(test)]
+fn satisfying_row_sets(user_attrs: &[UserAttribute], policy: &Policy) -> Vec<Vec<usize>> {
+    let mut idx = 0;
+    row_sets(&mut idx, user_attrs, &policy.expr)
+        .into_iter()
+        .map(|set| set.into_iter().collect())
+        .collect()
+}
+
+#[cfg(test)]
+fn row_sets(
+    idx: &mut usize,
+    user_attrs: &[UserAttribute],
+    expr: &Expr<(bool, UserAttribute)>,
+) -> Vec<BTreeSet<usize>> {
+    match expr {
+        Expr::Lit((is_neg, user_attr)) => {
+            let sets = match satisfies(user_attrs, user_attr, *is_neg) {
+                None => vec![],
+                Some(_) => vec![BTreeSet::from([*idx])],
+            };
+            *idx += 1;
+            sets
+        }
+        Expr::Or(lhs, rhs) => {
+            let mut l = row_sets(idx, user_attrs, lhs);
+            let mut r = row_sets(idx, user_attrs, rhs);
+            l.append(&mut r);
+            minimize(l)
+        }
+        Expr::And(lhs, rhs) => {
+            let l = row_sets(idx, user_attrs, lhs);
+            let r = row_sets(idx, user_attrs, rhs);
+            let mut sets = Vec::with_capacity(l.len() * r.len());
+            for set_l in l.iter() {
+                for set_r in r.iter() {
+                    sets.push(set_l.union(set_r).cloned().collect());
+                }
+            }
+            minimize(sets)
+        }
+    }
+}
+
+// Upper bound on the number of weight evaluations of the exact search in
+// `cheapest_row_set`, after which the best set found so far is returned
+const SEARCH_BUDGET: usize = 1 << 16;
+
+// The lightest satisfying set of rows. Weights need not be additive, but adding rows must
+// never make a set lighter. A dynamic program over the policy tree, which keeps the
+// lighter subtree at every OR, gives a first candidate in time polynomial in the size of
+// the policy. A branch-and-bound search over the choices at the ORs then looks for a
+// lighter set, cutting off every partial choice which already weighs as much as the best
+// set so far. If the search exceeds `SEARCH_BUDGET`, the best set found until then is
+// returned, which is never heavier than the candidate of the dynamic program.
+pub fn cheapest_row_set(
+    user_attrs: &[UserAttribute],
+    policy: &Policy,
+    weight: &dyn Fn(&[usize]) -> u64,
+) -> Option<Vec<usize>> {
+    let mut idx = 0;
+    let tree = satisfied_tree(&mut idx, user_attrs, &policy.expr)?;
+    let rows = greedy(&tree, weight);
+    let mut best = (weight(&rows), rows);
+    let mut budget = SEARCH_BUDGET;
+    search(
+        &mut vec![&tree],
+        &mut Vec::new(),
+        weight,
+        &mut best,
+        &mut budget,
+    );
+    let (_, mut rows) = best;
+    rows.sort();
+    Some(rows)
+}
+
+// The policy tree restricted to the rows the user satisfies. Subtrees which cannot be
+// satisfied are dropped, so every remaining choice leads to a satisfying set.
+enum Node {
+    Row(usize),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+}
+
+fn satisfied_tree(
+    idx: &mut usize,
+    user_attrs: &[UserAttribute],
+    expr: &Expr<(bool, UserAttribute)>,
+) -> Option<Node> {
+    match expr {
+        Expr::Lit((is_neg, user_attr)) => {
+            let node = satisfies(user_attrs, user_attr, *is_neg).map(|_| Node::Row(*idx));
+            *idx += 1;
+            node
+        }
+        Expr::Or(lhs, rhs) => {
+            let l = satisfied_tree(idx, user_attrs, lhs);
+            let r = satisfied_tree(idx, user_attrs, rhs);
+            match (l, r) {
+                (Some(l), Some(r)) => Some(Node::Or(Box::new(l), Box::new(r))),
+                (l, r) => l.or(r),
+            }
+        }
+        Expr::And(lhs, rhs) => {
+            let l = satisfied_tree(idx, user_attrs, lhs);
+            let r = satisfied_tree(idx, user_attrs, rhs);
+            Some(Node::And(Box::new(l?), Box::new(r?)))
+        }
+    }
+}
+
+// The lightest set per subtree, which is not necessarily the lightest overall
+fn greedy(node: &Node, weight: &dyn Fn(&[usize]) -> u64) -> Vec<usize> {
+    match node {
+        Node::Row(j) => vec![*j],
+        Node::Or(lhs, rhs) => {
+            let l = greedy(lhs, weight);
+            let r = greedy(rhs, weight);
+            if weight(&l) <= weight(&r) {
+                l
+            } else {
+                r
+            }
+        }
+        Node::And(lhs, rhs) => {
+            let mut rows = greedy(lhs, weight);
+            rows.append(&mut greedy(rhs, weight));
+            rows
+        }
+    }
+}
+
+// `pending` holds the subtrees which still have to be satisfied in addition to `rows`
+fn search<'a>(
+    pending: &mut Vec<&'a Node>,
+    rows: &mut Vec<usize>,
+    weight: &dyn Fn(&[usize]) -> u64,
+    best: &mut (u64, Vec<usize>),
+    budget: &mut usize,
+) {
+    let Some(node) = pending.pop() else {
+        *best = (weight(rows), rows.clone());
+        return;
+    };
+    match node {
+        Node::Row(j) => {
+            rows.push(*j);
+            if *budget > 0 {
+                *budget -= 1;
+                if weight(rows) < best.0 {
+                    search(pending, rows, weight, best, budget);
+                }
+            }
+            rows.pop();
+        }
+        Node::And(lhs, rhs) => {
+            pending.push(rhs);
+            pending.push(lhs);
+            search(pending, rows, weight, best, budget);
+            pending.pop();
+            pending.pop();
+        }
+        Node::Or(lhs, rhs) => {
+            for child in [lhs, rhs] {
+                pending.push(child);
+                search(pending, rows, weight, best, budget);
+                pending.pop();
+            }
+        }
+    }
+    pending.push(node);
+}
+
 #[test]
 fn test_secret_reconstruction() {
     let user_1 = vec!["anda.z:z"]
@@ -160,3 +347,98 @@ fn test_secret_sharing() {
     assert_eq!(sharing[2].1, vec![-2]);
     assert_eq!(sharing[3].1, vec![0]);
 }
+
+#[test]
+fn test_satisfying_row_sets() {
+    let user_attrs: Vec<UserAttribute> = ["A.a:0", "A.b:0", "B.b:1"]
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let policy = Policy::parse("(A.a:0 | A.b:0) & (!B.b:0 | A.a:0) | A.c:0").unwrap();
+    let sets = satisfying_row_sets(&user_attrs, &policy);
+    assert_eq!(sets, vec![vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3]]);
+}
+
+#[test]
+fn test_cheapest_row_set() {
+    let user_attrs: Vec<UserAttribute> = ["A.a:0", "A.b:0", "B.b:1"]
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let policy = Policy::parse("(A.a:0 | A.b:0) & (!B.b:0 | A.a:0) | A.c:0").unwrap();
+    let rows = cheapest_row_set(&user_attrs, &policy, &|rows| rows.len() as u64);
+    assert_eq!(rows, Some(vec![0, 2]));
+    let rows = cheapest_row_set(&user_attrs, &policy, &|rows| {
+        rows.iter().map(|j| 5 - *j as u64).sum()
+    });
+    assert_eq!(rows, Some(vec![1, 3]));
+
+    // 2^40 satisfying sets, of which the lightest is found in a single pass
+    let clauses: Vec<String> = (0..40)
+        .map(|i| format!("(A.a{}:0 | A.b{}:0)", i, i))
+        .collect();
+    let policy = Policy::parse(&clauses.join(" & ")).unwrap();
+    let user_attrs: Vec<UserAttribute> = (0..40)
+        .flat_map(|i| [format!("A.a{}:0", i), format!("A.b{}:0", i)])
+        .map(|ua| UserAttribute::parse(&ua).unwrap())
+        .collect();
+    let rows = cheapest_row_set(&user_attrs, &policy, &|rows| {
+        rows.iter().filter(|j| *j % 2 == 0).count() as u64
+    })
+    .unwrap();
+    assert_eq!(rows, (0..40).map(|i| 2 * i + 1).collect::<Vec<usize>>());
+    assert!(cheapest_row_set(&user_attrs[1..], &policy, &|rows| rows.len() as u64).is_some());
+    assert!(cheapest_row_set(&user_attrs[2..], &policy, &|rows| rows.len() as u64).is_none());
+}
+
+#[test]
+fn test_cheapest_row_set_beats_greedy() {
+    let user_attrs: Vec<UserAttribute> = ["A.a:0", "A.b:0", "A.c:0"]
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let policy = Policy::parse("(A.b:0 | A.a:0) & (A.a:0 | A.c:0)").unwrap();
+    // the number of distinct attributes, so that rows 1 and 2 share their cost
+    let distinct = |rows: &[usize]| {
+        let attrs: BTreeSet<_> = rows
+            .iter()
+            .map(|j| policy.get(*j).0.auth_lbl_attr())
+            .collect();
+        attrs.len() as u64
+    };
+    let mut idx = 0;
+    let tree = satisfied_tree(&mut idx, &user_attrs, &policy.expr).unwrap();
+    assert_eq!(greedy(&tree, &distinct), vec![0, 2]);
+    assert_eq!(
+        cheapest_row_set(&user_attrs, &policy, &distinct),
+        Some(vec![1, 2])
+    );
+
+    // compare against all satisfying sets
+    let clauses: Vec<String> = (0..6)
+        .map(|i| format!("(A.a{}:0 | A.a{}:0 | !A.b{}:0)", i, (i + 1) % 6, i % 2))
+        .collect();
+    let policy = Policy::parse(&clauses.join(" & ")).unwrap();
+    let user_attrs: Vec<UserAttribute> = ["A.a0:0", "A.a2:0", "A.a3:0", "A.a5:0", "A.b1:1"]
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let check = |weight: &dyn Fn(&[usize]) -> u64| {
+        let rows = cheapest_row_set(&user_attrs, &policy, weight).unwrap();
+        let min = satisfying_row_sets(&user_attrs, &policy)
+            .iter()
+            .map(|set| weight(set))
+            .min()
+            .unwrap();
+        assert_eq!(weight(&rows), min);
+    };
+    check(&|rows| rows.len() as u64);
+    check(&|rows| {
+        let attrs: BTreeSet<_> = rows
+            .iter()
+            .map(|j| policy.get(*j).0.auth_lbl_attr())
+            .collect();
+        attrs.len() as u64 * 3 + rows.len() as u64
+    });
+    check(&|rows| rows.iter().map(|j| (*j as u64 * 7) % 5).sum::<u64>() + rows.len() as u64);
+}
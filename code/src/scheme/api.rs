@@ -0,0 +1,318 @@
+use rand::Rng;
+
+use crate::curve::Gt;
+use crate::policy::{LsssMatrix, Policy, UserAttribute, GID_LABEL};
+
+use super::{
+    EncryptionPool, EpochUpdate, Iota, RerandomizeMode, RetrievalKey, Scheme, Tau,
+    TransformationKey, TransformedCiphertext, UpdateKey,
+};
+
+// High-level interface on top of a scheme variant. The user key keeps the Iota it was
+// generated with and the ciphertext keeps its policy, from which Tau is rebuilt, so that
+// callers never handle the index maps themselves. Every key also holds the identity
+// attribute `auth.__gid:<gid>` of each of its authorities, which allows revoking GIDs.
+pub struct Abe<S: Scheme> {
+    pub scheme: S,
+}
+
+pub struct UserKey<S: Scheme> {
+    pub gid: String,
+    pub iota: Iota,
+    pub usk: S::USK,
+}
+
+// The part of a user key which is handed to a server for outsourced decryption
+pub struct ServerKey<S: Scheme> {
+    pub iota: Iota,
+    pub tk: TransformationKey<S::USK>,
+}
+
+pub struct PolicyCiphertext<S: Scheme> {
+    pub policy: Policy,
+    pub ct: S::Ciphertext,
+}
+
+// A ciphertext under an explicit LSSS matrix, see `Scheme::encrypt_lsss`
+pub struct LsssCiphertext<S: Scheme> {
+    pub lsss: LsssMatrix,
+    pub ct: S::Ciphertext,
+}
+
+impl<S: Scheme> Abe<S> {
+    pub fn new(scheme: S) -> Self {
+        Abe { scheme }
+    }
+
+    pub fn setup(&self, rng: impl Rng, auths: &Vec<&str>) -> (S::MSK, S::MPK) {
+        self.scheme.setup(rng, auths)
+    }
+
+    pub fn keygen(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        msk: &S::MSK,
+        user_attrs: &[UserAttribute],
+    ) -> Result<UserKey<S>, String> {
+        let user_attrs = issued_attrs(gid, user_attrs)?;
+        let iota = Iota::new(&user_attrs);
+        let usk = self.scheme.keygen(rng, gid, msk, &user_attrs, &iota);
+        Ok(UserKey {
+            gid: String::from(gid),
+            iota,
+            usk,
+        })
+    }
+
+    // Like `keygen` for many users at once, see `Scheme::keygen_batch`
+    pub fn keygen_batch(
+        &self,
+        rng: impl Rng,
+        msk: &S::MSK,
+        users: &[(&str, &[UserAttribute])],
+    ) -> Result<Vec<UserKey<S>>, String> {
+        let user_attrs: Vec<Vec<UserAttribute>> = users
+            .iter()
+            .map(|(gid, user_attrs)| issued_attrs(gid, user_attrs))
+            .collect::<Result<_, _>>()?;
+        let iotas: Vec<Iota> = user_attrs.iter().map(Iota::new).collect();
+        let batch: Vec<_> = users
+            .iter()
+            .zip(user_attrs.iter())
+            .zip(iotas.iter())
+            .map(|(((gid, _), user_attrs), iota)| (*gid, user_attrs, iota))
+            .collect();
+        let usks = self.scheme.keygen_batch(rng, msk, &batch)?;
+        Ok(users
+            .iter()
+            .zip(iotas)
+            .zip(usks)
+            .map(|(((gid, _), iota), usk)| UserKey {
+                gid: String::from(*gid),
+                iota,
+                usk,
+            })
+            .collect())
+    }
+
+    // Issues additional attributes to an existing key, see `Scheme::keygen_extend`. Some
+    // variants cannot extend every key and return an error instead, after which the key
+    // has to be reissued with `keygen`:
+    // - Opt0 and Opt1 share the randomness of an attribute value among all labels of an
+    //   authority, so a value which the key already holds under another label cannot be
+    //   added, e.g. `A.team:eng` to a key with `A.dept:eng`.
+    // - Opt5 and Opt6 cannot add attributes under a label which is new to the key.
+    pub fn keygen_extend(
+        &self,
+        rng: impl Rng,
+        msk: &S::MSK,
+        usk: &mut UserKey<S>,
+        user_attrs: &[UserAttribute],
+    ) -> Result<(), String> {
+        check_reserved(user_attrs)?;
+        let mut user_attrs = user_attrs.to_vec();
+        for auth in auths_of(&user_attrs) {
+            if !usk.iota.contains(&auth, GID_LABEL, &usk.gid) {
+                user_attrs.push(UserAttribute::gid(&auth, &usk.gid));
+            }
+        }
+        self.scheme
+            .keygen_extend(rng, &usk.gid, msk, &mut usk.usk, &user_attrs, &mut usk.iota)
+    }
+
+    pub fn rotate_epoch(
+        &self,
+        rng: impl Rng,
+        auth: &str,
+        msk: &mut S::MSK,
+        mpk: &mut S::MPK,
+    ) -> Result<EpochUpdate, String> {
+        self.scheme.rotate_epoch(rng, auth, msk, mpk)
+    }
+
+    pub fn update_usk(&self, usk: &mut UserKey<S>, uk: &UpdateKey) -> Result<(), String> {
+        self.scheme.update_usk(&mut usk.usk, uk)
+    }
+
+    pub fn rerandomize_key(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        usk: &mut UserKey<S>,
+    ) -> Result<(), String> {
+        self.scheme
+            .rerandomize_usk(rng, mpk, &mut usk.usk, &usk.iota)
+    }
+
+    // The retrieval key stays with the user, see `TransformationKey`
+    pub fn transformation_key(
+        &self,
+        rng: impl Rng,
+        usk: &UserKey<S>,
+    ) -> (ServerKey<S>, RetrievalKey) {
+        let (tk, rk) = self.scheme.transformation_key(rng, &usk.usk, &usk.gid);
+        let sk = ServerKey {
+            iota: usk.iota.clone(),
+            tk,
+        };
+        (sk, rk)
+    }
+
+    pub fn encrypt(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        policy: &Policy,
+    ) -> (Gt, PolicyCiphertext<S>) {
+        let tau = Tau::new(policy);
+        let (k, ct) = self.scheme.encrypt(rng, mpk, policy, &tau);
+        let ct = PolicyCiphertext {
+            policy: policy.clone(),
+            ct,
+        };
+        (k, ct)
+    }
+
+    pub fn encrypt_lsss(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        lsss: &LsssMatrix,
+    ) -> (Gt, LsssCiphertext<S>) {
+        let tau = Tau::new(&lsss.labels());
+        let (k, ct) = self.scheme.encrypt_lsss(rng, mpk, lsss, &tau);
+        let ct = LsssCiphertext {
+            lsss: lsss.clone(),
+            ct,
+        };
+        (k, ct)
+    }
+
+    // Precomputes `num_cts` encryptions under policies of up to `max_rows` rows
+    pub fn fill_pool(
+        &self,
+        rng: impl Rng,
+        pool: &mut EncryptionPool<S::Powers>,
+        mpk: &S::MPK,
+        num_cts: usize,
+        max_rows: usize,
+    ) {
+        self.scheme.fill_pool(rng, pool, mpk, num_cts, max_rows)
+    }
+
+    pub fn encrypt_online(
+        &self,
+        pool: &mut EncryptionPool<S::Powers>,
+        mpk: &S::MPK,
+        policy: &Policy,
+    ) -> Result<(Gt, PolicyCiphertext<S>), String> {
+        let tau = Tau::new(policy);
+        let (k, ct) = self.scheme.encrypt_online(pool, mpk, policy, &tau)?;
+        let ct = PolicyCiphertext {
+            policy: policy.clone(),
+            ct,
+        };
+        Ok((k, ct))
+    }
+
+    // Encrypts such that the given GIDs cannot decrypt, even if they satisfy the policy
+    pub fn encrypt_revoking(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        policy: &Policy,
+        revoked_gids: &[&str],
+    ) -> (Gt, PolicyCiphertext<S>) {
+        self.encrypt(rng, mpk, &policy.revoking(revoked_gids))
+    }
+
+    // Restricts the ciphertext to users which also satisfy `extra`, see `Scheme::update_policy`
+    pub fn update_policy(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        ct: &PolicyCiphertext<S>,
+        extra: &Policy,
+    ) -> Result<PolicyCiphertext<S>, String> {
+        let (new_ct, policy) = self
+            .scheme
+            .update_policy(rng, mpk, &ct.policy, &ct.ct, extra)?;
+        Ok(PolicyCiphertext { policy, ct: new_ct })
+    }
+
+    // Returns the factor by which the encapsulated key was multiplied
+    pub fn rerandomize(
+        &self,
+        rng: impl Rng,
+        mpk: &S::MPK,
+        ct: &mut PolicyCiphertext<S>,
+        mode: RerandomizeMode,
+    ) -> Result<Gt, String> {
+        let tau = Tau::new(&ct.policy);
+        self.scheme
+            .rerandomize_ciphertext(rng, mpk, &ct.policy, &tau, &mut ct.ct, mode)
+    }
+
+    pub fn transform(
+        &self,
+        sk: &ServerKey<S>,
+        ct: &PolicyCiphertext<S>,
+    ) -> Option<TransformedCiphertext> {
+        let tau = Tau::new(&ct.policy);
+        self.scheme
+            .transform(&sk.tk, &sk.iota, &tau, &ct.policy, &ct.ct)
+    }
+
+    pub fn decrypt(&self, usk: &UserKey<S>, ct: &PolicyCiphertext<S>) -> Option<Gt> {
+        let tau = Tau::new(&ct.policy);
+        self.scheme
+            .decrypt(&usk.usk, &usk.gid, &usk.iota, &tau, &ct.policy, &ct.ct)
+    }
+
+    pub fn decrypt_lsss(&self, usk: &UserKey<S>, ct: &LsssCiphertext<S>) -> Option<Gt> {
+        let tau = Tau::new(&ct.lsss.labels());
+        self.scheme
+            .decrypt_lsss(&usk.usk, &usk.gid, &usk.iota, &tau, &ct.lsss, &ct.ct)
+    }
+
+    pub fn decrypt_many(&self, usk: &UserKey<S>, cts: &[&PolicyCiphertext<S>]) -> Vec<Option<Gt>> {
+        let taus: Vec<Tau> = cts.iter().map(|ct| Tau::new(&ct.policy)).collect();
+        let cts: Vec<_> = cts
+            .iter()
+            .zip(taus.iter())
+            .map(|(ct, tau)| (&ct.policy, tau, &ct.ct))
+            .collect();
+        self.scheme
+            .decrypt_many(&usk.usk, &usk.gid, &usk.iota, &cts)
+    }
+}
+
+// The attributes of a new key: the given ones and the identity attribute of the GID at
+// every authority among them
+fn issued_attrs(gid: &str, user_attrs: &[UserAttribute]) -> Result<Vec<UserAttribute>, String> {
+    check_reserved(user_attrs)?;
+    let mut user_attrs = user_attrs.to_vec();
+    for auth in auths_of(&user_attrs) {
+        user_attrs.push(UserAttribute::gid(&auth, gid));
+    }
+    Ok(user_attrs)
+}
+
+// The identity attributes are issued by the API only
+pub(super) fn check_reserved(user_attrs: &[UserAttribute]) -> Result<(), String> {
+    match user_attrs.iter().find(|ua| ua.lbl == GID_LABEL) {
+        Some(ua) => Err(format!("Attribute {:?} uses the reserved label", ua)),
+        None => Ok(()),
+    }
+}
+
+fn auths_of(user_attrs: &[UserAttribute]) -> Vec<String> {
+    let mut auths: Vec<String> = Vec::new();
+    for ua in user_attrs.iter() {
+        if !auths.contains(&ua.auth) {
+            auths.push(ua.auth.clone());
+        }
+    }
+    auths
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::policy::UserAttribute;
+
+use super::api::check_reserved;
+use super::common::{prove_key, verify_key, KeyProof, PartialKey};
+use super::{Abe, Iota, Scheme, UserKey};
+
+// A single authority, which sets up and keeps its own master key. Authorities do not
+// depend on each other: each one publishes its public key, from which encryptors assemble
+// the MPK (see `MpkDirectory`), and issues partial keys for the attributes it manages.
+pub struct Authority<S: Scheme> {
+    pub abe: Abe<S>,
+    name: String,
+    msk: S::PartialMSK,
+    mpk: S::PartialMPK,
+}
+
+// A partial key issued by a single authority, together with the attributes it holds.
+// Users assemble their key from the partial keys of all their authorities.
+pub struct IssuedKey<S: Scheme> {
+    pub gid: String,
+    pub user_attrs: Vec<UserAttribute>,
+    pub usk: S::PartialUSK,
+}
+
+impl<S: Scheme> Authority<S> {
+    pub fn setup(rng: impl Rng, scheme: S, name: &str) -> Self {
+        let abe = Abe::new(scheme);
+        let (msk, mpk) = abe.scheme.setup_authority(rng, name);
+        Authority {
+            abe,
+            name: String::from(name),
+            msk,
+            mpk,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn public_key(&self) -> S::PartialMPK {
+        self.mpk.clone()
+    }
+
+    // Proves knowledge of the secrets behind the public key, see `MpkDirectory::publish`
+    pub fn prove_public_key(&self, rng: impl Rng) -> KeyProof<S::PartialMPK> {
+        prove_key(rng, &self.abe.scheme, &self.msk, &self.mpk)
+    }
+
+    // Issues the given attributes, which all have to be managed by this authority, and the
+    // identity attribute of the GID
+    pub fn keygen(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        user_attrs: &[UserAttribute],
+    ) -> Result<IssuedKey<S>, String> {
+        let user_attrs = authority_attrs(&self.name, gid, user_attrs)?;
+        let iota = Iota::new(&user_attrs);
+        let usk = self
+            .abe
+            .scheme
+            .keygen_authority(rng, gid, &self.msk, &user_attrs, &iota);
+        Ok(IssuedKey {
+            gid: String::from(gid),
+            user_attrs,
+            usk,
+        })
+    }
+}
+
+// The attributes a single authority issues for the given ones, i.e. including the
+// identity attribute of the GID
+pub(super) fn authority_attrs(
+    auth: &str,
+    gid: &str,
+    user_attrs: &[UserAttribute],
+) -> Result<Vec<UserAttribute>, String> {
+    check_reserved(user_attrs)?;
+    if let Some(ua) = user_attrs.iter().find(|ua| ua.auth != auth) {
+        return Err(format!(
+            "Attribute {:?} is not managed by authority '{}'",
+            ua, auth
+        ));
+    }
+    let mut user_attrs = user_attrs.to_vec();
+    user_attrs.push(UserAttribute::gid(auth, gid));
+    Ok(user_attrs)
+}
+
+// Collects the public keys which the authorities publish separately and assembles the MPK
+// from them. Every authority name can be published only once, and only together with a
+// valid proof that the authority knows the secrets behind its public key.
+pub struct MpkDirectory<S: Scheme> {
+    partial_mpks: HashMap<String, S::PartialMPK>,
+}
+
+impl<S: Scheme> MpkDirectory<S> {
+    pub fn new() -> Self {
+        MpkDirectory {
+            partial_mpks: HashMap::new(),
+        }
+    }
+
+    pub fn publish(
+        &mut self,
+        partial_mpk: S::PartialMPK,
+        proof: &KeyProof<S::PartialMPK>,
+    ) -> Result<(), String> {
+        let auth = partial_mpk.get_auth();
+        if self.partial_mpks.contains_key(&auth) {
+            return Err(format!(
+                "A public key for authority '{}' was already published",
+                auth
+            ));
+        }
+        verify_key(&S::new(), &partial_mpk, proof)?;
+        self.partial_mpks.insert(auth, partial_mpk);
+        Ok(())
+    }
+
+    pub fn get(&self, auth: &str) -> Option<&S::PartialMPK> {
+        self.partial_mpks.get(auth)
+    }
+
+    pub fn mpk(&self) -> S::MPK {
+        self.partial_mpks.values().cloned().collect()
+    }
+}
+
+impl<S: Scheme> Default for MpkDirectory<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Scheme> Abe<S> {
+    // Checks a partial key an authority issued against its public key, see
+    // `Scheme::verify_partial_usk`
+    pub fn verify_issued_key(
+        &self,
+        partial_mpk: &S::PartialMPK,
+        issued: &IssuedKey<S>,
+    ) -> Result<(), String> {
+        let iota = Iota::new(&issued.user_attrs);
+        self.scheme.verify_partial_usk(
+            partial_mpk,
+            &issued.gid,
+            &issued.user_attrs,
+            &iota,
+            &issued.usk,
+        )
+    }
+
+    // Assembles the key of a user from the partial keys its authorities issued
+    pub fn assemble_key(&self, issued: Vec<IssuedKey<S>>) -> Result<UserKey<S>, String> {
+        let Some(gid) = issued.first().map(|key| key.gid.clone()) else {
+            return Err(String::from("No partial keys given"));
+        };
+        let mut auths = Vec::new();
+        let mut user_attrs = Vec::new();
+        let mut partial_usks = Vec::new();
+        for key in issued {
+            if key.gid != gid {
+                return Err(format!(
+                    "The partial keys were issued to different GIDs '{}' and '{}'",
+                    gid, key.gid
+                ));
+            }
+            let auth = key.usk.get_auth();
+            if auths.contains(&auth) {
+                return Err(format!(
+                    "Authority '{}' issued more than one of the partial keys",
+                    auth
+                ));
+            }
+            if !key.user_attrs.contains(&UserAttribute::gid(&auth, &gid)) {
+                return Err(format!(
+                    "The partial key of authority '{}' does not hold the identity attribute",
+                    auth
+                ));
+            }
+            auths.push(auth);
+            user_attrs.extend(key.user_attrs);
+            partial_usks.push(key.usk);
+        }
+        Ok(UserKey {
+            gid,
+            iota: Iota::new(&user_attrs),
+            usk: partial_usks.into_iter().collect(),
+        })
+    }
+}
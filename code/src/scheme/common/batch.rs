@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use ark_ec::{CurveGroup, VariableBaseMSM};
+
+use crate::curve::{multi_pairing, Gt, ScalarField, G, H};
+use crate::hashing::hash_attr;
+use crate::policy::Policy;
+
+use super::Tau;
+
+// The product of the pairings of a decryption. All of them share a single final
+// exponentiation, which dominates the cost of a pairing.
+pub struct PairingProduct {
+    g_vec: Vec<G>,
+    h_vec: Vec<H>,
+}
+
+impl PairingProduct {
+    pub fn new() -> Self {
+        PairingProduct {
+            g_vec: Vec::new(),
+            h_vec: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, g: G, h: H) {
+        self.g_vec.push(g);
+        self.h_vec.push(h);
+    }
+
+    pub fn finish(self) -> Gt {
+        multi_pairing(self.g_vec, self.h_vec).0
+    }
+}
+
+impl Default for PairingProduct {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The rows (positive and negated) with which a key decrypts, solved once for every
+// distinct policy and Tau of a batch
+pub struct SolutionCache<'a> {
+    solutions: Vec<(&'a Policy, &'a Tau, Solution)>,
+}
+
+type Solution = Option<(Vec<usize>, Vec<usize>)>;
+
+impl<'a> SolutionCache<'a> {
+    pub fn new() -> Self {
+        SolutionCache {
+            solutions: Vec::new(),
+        }
+    }
+
+    pub fn get_or_solve(
+        &mut self,
+        policy: &'a Policy,
+        tau: &'a Tau,
+        solve: impl FnOnce() -> Solution,
+    ) -> Solution {
+        if let Some((_, _, eps)) = self
+            .solutions
+            .iter()
+            .find(|(p, t, _)| *p == policy && *t == tau)
+        {
+            return eps.clone();
+        }
+        let eps = solve();
+        self.solutions.push((policy, tau, eps.clone()));
+        eps
+    }
+}
+
+impl Default for SolutionCache<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What decryption derives from the key alone. Besides the generator and the hashed GID,
+// which are paired with the ciphertext, these are the MSMs of k_3 and k_5 for a negated
+// attribute over the attributes which the key holds under its label, with the exponents
+// 1 / (x_attr_not - x_attr). They only depend on the authority, the label and the negated
+// attribute, so decrypting many ciphertexts with the same key computes each of them once.
+// A cache must only ever be used with a single key.
+pub struct KeyCache {
+    pub bases: (G, G),
+    x_attrs: HashMap<String, ScalarField>,
+    k_3: HashMap<(String, String, String), G>,
+    k_5: HashMap<(String, String, String), H>,
+}
+
+impl KeyCache {
+    pub fn new(bases: (G, G)) -> Self {
+        KeyCache {
+            bases,
+            x_attrs: HashMap::new(),
+            k_3: HashMap::new(),
+            k_5: HashMap::new(),
+        }
+    }
+
+    pub fn hash_attr(&mut self, attr: &str) -> ScalarField {
+        *self
+            .x_attrs
+            .entry(String::from(attr))
+            .or_insert_with(|| hash_attr(attr))
+    }
+
+    // The k_3 of the negated attribute, from the k_3 of the attributes under its label
+    pub fn k_3(
+        &mut self,
+        (auth, lbl, attr_not): (&str, &str, &str),
+        k_3_map: &HashMap<(String, String), G>,
+    ) -> G {
+        let key = (auth.to_owned(), lbl.to_owned(), attr_not.to_owned());
+        if let Some(k_3) = self.k_3.get(&key) {
+            return *k_3;
+        }
+        let bases = k_3_map
+            .iter()
+            .filter(|((l, _), _)| l == lbl)
+            .map(|((_, attr), k_3)| (attr, *k_3));
+        let k_3 = self.msm(attr_not, bases, -ScalarField::from(1));
+        self.k_3.insert(key, k_3);
+        k_3
+    }
+
+    // The k_5 of the negated attribute. The attributes under its label are those of
+    // k_3_map, and `k_5` gives the k_5 of each of them.
+    pub fn k_5(
+        &mut self,
+        (auth, lbl, attr_not): (&str, &str, &str),
+        k_3_map: &HashMap<(String, String), G>,
+        k_5: impl Fn(&str) -> H,
+    ) -> H {
+        let key = (auth.to_owned(), lbl.to_owned(), attr_not.to_owned());
+        if let Some(k_5) = self.k_5.get(&key) {
+            return *k_5;
+        }
+        let bases = k_3_map
+            .keys()
+            .filter(|(l, _)| l == lbl)
+            .map(|(_, attr)| (attr, k_5(attr)));
+        let k_5 = self.msm(attr_not, bases, ScalarField::from(1));
+        self.k_5.insert(key, k_5);
+        k_5
+    }
+
+    fn msm<'a, C: CurveGroup<ScalarField = ScalarField> + VariableBaseMSM<MulBase = C::Affine>>(
+        &mut self,
+        attr_not: &str,
+        bases: impl Iterator<Item = (&'a String, C)>,
+        numerator: ScalarField,
+    ) -> C {
+        let x_attr_not = self.hash_attr(attr_not);
+        let mut affine_bases = Vec::new();
+        let mut exps = Vec::new();
+        for (attr, base) in bases {
+            affine_bases.push(base.into_affine());
+            exps.push(numerator / (x_attr_not - self.hash_attr(attr)));
+        }
+        C::msm(&affine_bases, &exps).unwrap()
+    }
+}
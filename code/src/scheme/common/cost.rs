@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::policy::{Policy, UserAttribute};
+
+use super::{Iota, Tau};
+
+// Rough costs of the operations in decryption relative to one base of a multi-scalar
+// multiplication in G (BLS12-381). Group additions and hashing are neglected.
+const PAIRING_WEIGHT: u64 = 8;
+const G_MSM_BASE_WEIGHT: u64 = 1;
+const H_MSM_BASE_WEIGHT: u64 = 2;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct DecryptionCost {
+    pub pairings: usize,
+    pub g_msm_bases: usize,
+    pub h_msm_bases: usize,
+}
+
+impl DecryptionCost {
+    pub fn weight(&self) -> u64 {
+        self.pairings as u64 * PAIRING_WEIGHT
+            + self.g_msm_bases as u64 * G_MSM_BASE_WEIGHT
+            + self.h_msm_bases as u64 * H_MSM_BASE_WEIGHT
+    }
+}
+
+// Everything a cost model may look at besides the rows used for decryption
+pub struct CostContext<'a> {
+    pub policy: &'a Policy,
+    pub user_attrs: &'a Vec<UserAttribute>,
+    pub iota: &'a Iota,
+    pub tau: &'a Tau,
+}
+
+impl<'a> CostContext<'a> {
+    // Splits the rows into positive and negated ones, like `solve_lse` does
+    pub fn split(&self, rows: &[usize]) -> (Vec<usize>, Vec<usize>) {
+        rows.iter().partition(|j| !self.policy.get(**j).1)
+    }
+
+    // The number of groups decryption forms when grouping the rows by `f`
+    pub fn groups<T: Eq + Hash>(&self, rows: &[usize], f: impl Fn(usize) -> T) -> usize {
+        rows.iter().map(|j| f(*j)).collect::<HashSet<T>>().len()
+    }
+
+    // The number of attributes the user holds under the label of row j. For a satisfied
+    // negated row, this is the size of the MSMs needed to handle it.
+    pub fn others(&self, j: usize) -> usize {
+        let user_attr = self.policy.get(j).0;
+        self.user_attrs
+            .iter()
+            .filter(|ua| ua.auth == user_attr.auth && ua.lbl == user_attr.lbl)
+            .count()
+    }
+
+    // Like `others`, but counted once per (auth, lbl, attr) among the rows
+    pub fn others_per_attr(&self, rows: &[usize]) -> usize {
+        let mut seen = HashSet::new();
+        rows.iter()
+            .filter(|j| seen.insert(self.policy.get(**j).0.auth_lbl_attr()))
+            .map(|j| self.others(*j))
+            .sum()
+    }
+
+    pub fn auth_iota(&self, j: usize) -> (String, usize) {
+        let (auth, lbl, attr) = self.policy.get(j).0.auth_lbl_attr();
+        let iota = self.iota.get(&auth, &lbl, &attr);
+        (auth, iota)
+    }
+
+    // The number of distinct entries of c_4_vec addressed by tau and tau tilde
+    pub fn tau_union(&self, rows: &[usize]) -> usize {
+        let mut idcs = HashSet::new();
+        for j in rows {
+            idcs.insert(self.tau.get(*j));
+            idcs.insert(self.tau.get_tilde(*j));
+        }
+        idcs.len()
+    }
+}
+
+// The cost of decrypting with a given set of rows under one scheme variant
+pub trait ReconstructionCost {
+    fn cost(&self, ctx: &CostContext, rows: &[usize]) -> DecryptionCost;
+}
+
+// Finds the cheapest set of rows which reconstructs the secret under the cost model, see
+// `Policy::cheapest_row_set`. All cost models only grow when rows are added.
+pub fn reconstruct_secret_with(
+    ctx: &CostContext,
+    cost: &impl ReconstructionCost,
+) -> Option<Vec<usize>> {
+    ctx.policy
+        .cheapest_row_set(ctx.user_attrs, |rows| cost.cost(ctx, rows).weight())
+}
+
+#[test]
+fn test_reconstruct_secret_with() {
+    use crate::scheme::{Opt0, Opt3, Scheme};
+
+    let policy = Policy::parse("(A.a:x & A.b:y) | !A.c:z").unwrap();
+    let tau = Tau::new(&policy);
+    let choose = |user_attrs: &Vec<UserAttribute>,
+                  cost: &dyn Fn(&CostContext) -> Option<Vec<usize>>| {
+        let iota = Iota::new(user_attrs);
+        cost(&CostContext {
+            policy: &policy,
+            user_attrs,
+            iota: &iota,
+            tau: &tau,
+        })
+    };
+    let opt0 = |ctx: &CostContext| reconstruct_secret_with(ctx, &Opt0::new());
+    let opt3 = |ctx: &CostContext| reconstruct_secret_with(ctx, &Opt3::new());
+
+    let mut user_attrs = vec![
+        UserAttribute::new("A", "a", "x"),
+        UserAttribute::new("A", "b", "y"),
+        UserAttribute::new("A", "c", "w0"),
+    ];
+    // a negated literal is cheap as long as the user holds few attributes under its label
+    assert_eq!(choose(&user_attrs, &opt0), Some(vec![2]));
+    assert_eq!(choose(&user_attrs, &opt3), Some(vec![2]));
+    // Opt3 shares pairings among the positive literals and switches earlier
+    for i in 1..3 {
+        user_attrs.push(UserAttribute::new("A", "c", &format!("w{}", i)));
+    }
+    assert_eq!(choose(&user_attrs, &opt0), Some(vec![2]));
+    assert_eq!(choose(&user_attrs, &opt3), Some(vec![0, 1]));
+    for i in 3..10 {
+        user_attrs.push(UserAttribute::new("A", "c", &format!("w{}", i)));
+    }
+    assert_eq!(choose(&user_attrs, &opt0), Some(vec![0, 1]));
+    assert_eq!(choose(&user_attrs, &opt3), Some(vec![0, 1]));
+
+    user_attrs.truncate(1);
+    assert_eq!(choose(&user_attrs, &opt0), None);
+}
+
+#[test]
+fn test_reconstruct_secret_with_large_policy() {
+    use crate::scheme::{Opt3, Scheme};
+
+    // 2^30 satisfying sets; a negated literal needs an MSM over all 8 attributes under its
+    // label, so the positive literals are cheaper
+    let clauses: Vec<String> = (0..30)
+        .map(|i| format!("(!A.n{}:0 | A.p{}:0)", i, i))
+        .collect();
+    let policy = Policy::parse(&clauses.join(" & ")).unwrap();
+    let tau = Tau::new(&policy);
+    let mut user_attrs = Vec::new();
+    for i in 0..30 {
+        user_attrs.push(UserAttribute::new("A", &format!("p{}", i), "0"));
+        for k in 1..9 {
+            user_attrs.push(UserAttribute::new("A", &format!("n{}", i), &k.to_string()));
+        }
+    }
+    let iota = Iota::new(&user_attrs);
+    let ctx = CostContext {
+        policy: &policy,
+        user_attrs: &user_attrs,
+        iota: &iota,
+        tau: &tau,
+    };
+    let rows = reconstruct_secret_with(&ctx, &Opt3::new()).unwrap();
+    assert_eq!(rows, (0..30).map(|i| 2 * i + 1).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_reconstruct_secret_with_shared_costs() {
+    use crate::scheme::{Opt3, Scheme};
+
+    // Both disjunctions are tied on their own, so picking per subtree keeps the left-most
+    // rows {0, 2}, which share neither a tau tilde nor an (auth, iota) group
+    let policy = Policy::parse("(B.b:0 | A.a:0) & (A.a:0 | C.c:0)").unwrap();
+    let tau = Tau::new(&policy);
+    let user_attrs = vec![
+        UserAttribute::new("A", "a", "0"),
+        UserAttribute::new("B", "b", "0"),
+        UserAttribute::new("C", "c", "0"),
+    ];
+    let iota = Iota::new(&user_attrs);
+    let ctx = CostContext {
+        policy: &policy,
+        user_attrs: &user_attrs,
+        iota: &iota,
+        tau: &tau,
+    };
+    let opt3 = Opt3::new();
+    let rows = reconstruct_secret_with(&ctx, &opt3).unwrap();
+    assert_eq!(rows, vec![0, 3]);
+    assert_eq!(opt3.cost(&ctx, &rows).pairings, 5);
+    assert_eq!(opt3.cost(&ctx, &[0, 2]).pairings, 6);
+}
@@ -0,0 +1,127 @@
+use ark_ec::Group;
+
+use crate::curve::{Gt, G};
+use crate::hashing::hash_gid;
+use crate::policy::{LsssMatrix, Policy, UserAttribute};
+
+use super::{
+    reconstruct_secret_with, solve_lsss, CostContext, Iota, KeyCache, ReconstructionCost, Scheme,
+    SolutionCache, Tau, TransformationKey, TransformedCiphertext, WeightedRows, Weights,
+};
+
+// The part of decryption which depends on the layout of a variant, i.e. combining the given
+// rows of the ciphertext with the key. Finding the rows is up to the cost model of the
+// variant, everything else about decryption is shared.
+pub trait SolvedDecryption: Scheme + ReconstructionCost {
+    fn user_attributes(usk: &Self::USK) -> Vec<UserAttribute>;
+
+    // The generator and the hashed GID in the cache are the only parts of decryption
+    // besides the key which are paired with the ciphertext, see `TransformationKey`
+    fn decrypt_solved(
+        usk: &Self::USK,
+        iota: &Iota,
+        tau: &Tau,
+        policy: &Policy,
+        ct: &Self::Ciphertext,
+        rows: WeightedRows,
+        cache: &mut KeyCache,
+    ) -> Option<Gt>;
+}
+
+// Splits the rows chosen by the cost model into positive and negated ones
+fn solve_lse(
+    scheme: &impl ReconstructionCost,
+    user_attrs: &Vec<UserAttribute>,
+    iota: &Iota,
+    tau: &Tau,
+    policy: &Policy,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let ctx = CostContext {
+        policy,
+        user_attrs,
+        iota,
+        tau,
+    };
+    let eps_all = reconstruct_secret_with(&ctx, scheme)?;
+    let (eps_not_vec, eps_vec) = eps_all.into_iter().partition(|i| policy.get(*i).1);
+    Some((eps_vec, eps_not_vec))
+}
+
+fn decrypt_with<S: SolvedDecryption>(
+    scheme: &S,
+    usk: &S::USK,
+    bases: (G, G),
+    iota: &Iota,
+    tau: &Tau,
+    policy: &Policy,
+    ct: &S::Ciphertext,
+) -> Option<Gt> {
+    let eps = solve_lse(scheme, &S::user_attributes(usk), iota, tau, policy)?;
+    let rows = (eps, Weights::Unit);
+    S::decrypt_solved(usk, iota, tau, policy, ct, rows, &mut KeyCache::new(bases))
+}
+
+pub fn decrypt<S: SolvedDecryption>(
+    scheme: &S,
+    usk: &S::USK,
+    gid: &str,
+    iota: &Iota,
+    tau: &Tau,
+    policy: &Policy,
+    ct: &S::Ciphertext,
+) -> Option<Gt> {
+    let bases = (G::generator(), hash_gid(gid).into());
+    decrypt_with(scheme, usk, bases, iota, tau, policy, ct)
+}
+
+pub fn decrypt_lsss<S: SolvedDecryption>(
+    usk: &S::USK,
+    gid: &str,
+    iota: &Iota,
+    tau: &Tau,
+    lsss: &LsssMatrix,
+    ct: &S::Ciphertext,
+) -> Option<Gt> {
+    let bases = (G::generator(), hash_gid(gid).into());
+    let solution = solve_lsss(lsss, &S::user_attributes(usk))?;
+    let mut cache = KeyCache::new(bases);
+    S::decrypt_solved(usk, iota, tau, &lsss.labels(), ct, solution, &mut cache)
+}
+
+// Decryption with a transformation key, which gives the key to the power of 1/z
+pub fn transform<S: SolvedDecryption>(
+    scheme: &S,
+    tk: &TransformationKey<S::USK>,
+    iota: &Iota,
+    tau: &Tau,
+    policy: &Policy,
+    ct: &S::Ciphertext,
+) -> Option<TransformedCiphertext> {
+    let k = decrypt_with(scheme, &tk.usk, (tk.g, tk.gid), iota, tau, policy, ct)?;
+    Some(TransformedCiphertext { k })
+}
+
+// Decrypts many ciphertexts with the same key. The GID is hashed once, the rows to decrypt
+// with are solved for once for every distinct policy and the values derived from the key
+// for a negated attribute once for every attribute, see `KeyCache`.
+pub fn decrypt_many<S: SolvedDecryption>(
+    scheme: &S,
+    usk: &S::USK,
+    gid: &str,
+    iota: &Iota,
+    cts: &[(&Policy, &Tau, &S::Ciphertext)],
+) -> Vec<Option<Gt>> {
+    let bases = (G::generator(), hash_gid(gid).into());
+    let user_attrs = S::user_attributes(usk);
+    let mut solutions = SolutionCache::new();
+    let mut cache = KeyCache::new(bases);
+    cts.iter()
+        .map(|(policy, tau, ct)| {
+            let eps = solutions.get_or_solve(policy, tau, || {
+                solve_lse(scheme, &user_attrs, iota, tau, policy)
+            })?;
+            let rows = (eps, Weights::Unit);
+            S::decrypt_solved(usk, iota, tau, policy, ct, rows, &mut cache)
+        })
+        .collect()
+}
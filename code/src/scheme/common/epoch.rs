@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use ark_ec::Group;
+use ark_ff::UniformRand;
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+
+use crate::curve::{ScalarField, G};
+use crate::hashing::hash_gid;
+use crate::policy::Policy;
+
+use super::{FullKey, PartialKey};
+
+// Moving an authority to its next epoch adds random offsets to beta, b and b_not. Users
+// which keep their attributes receive an update key for their GID which shifts their
+// partial key by the same offsets; everyone else is left with a key for the old epoch,
+// which does not work for ciphertexts encrypted under the new one.
+//
+// The b_prime of opt4 and opt6 and the b_not_prime of opt5 and opt6 are not rotated. They
+// only enter a key raised to the user's own randomness, which the authority does not know,
+// so no update key for the GID could shift them. Nor do they have to move: that part of
+// the key cancels against the randomness in its other components in every epoch, while
+// the beta, b and b_not in the rest of the key are what a revoked user lacks.
+pub struct EpochUpdate {
+    pub auth: String,
+    pub epoch: u64,
+    pub beta: ScalarField,
+    pub b: ScalarField,
+    pub b_not: ScalarField,
+}
+
+// k is added to everything that contains g^beta * H(gid)^b and k_not to everything that
+// contains g^beta * H(gid)^b_not
+pub struct UpdateKey {
+    pub auth: String,
+    pub epoch: u64,
+    pub k: G,
+    pub k_not: G,
+}
+
+impl EpochUpdate {
+    pub fn new(mut rng: impl Rng, auth: &str, epoch: u64) -> Self {
+        EpochUpdate {
+            auth: String::from(auth),
+            epoch,
+            beta: ScalarField::rand(&mut rng),
+            b: ScalarField::rand(&mut rng),
+            b_not: ScalarField::rand(&mut rng),
+        }
+    }
+
+    pub fn update_key(&self, gid: &str) -> UpdateKey {
+        let g = G::generator().mul(self.beta);
+        let gid_hashed = hash_gid(gid);
+        UpdateKey {
+            auth: self.auth.clone(),
+            epoch: self.epoch,
+            k: g + gid_hashed.mul(self.b),
+            k_not: g + gid_hashed.mul(self.b_not),
+        }
+    }
+}
+
+impl<T: PartialKey> FullKey<T> {
+    pub fn get_epoch(&self, auth: &str) -> Option<u64> {
+        self.get_partial_key(auth).map(|key| key.get_epoch())
+    }
+}
+
+// Checks that an update key is the next one for the given partial key
+pub fn check_update_key(usk: Option<&impl PartialKey>, uk: &UpdateKey) -> Result<(), String> {
+    match usk {
+        None => Err(format!(
+            "The key holds no attributes of authority '{}'",
+            uk.auth
+        )),
+        Some(usk) if usk.get_epoch() + 1 != uk.epoch => Err(format!(
+            "Update key for epoch {} of authority '{}' does not follow the key's epoch {}",
+            uk.epoch,
+            uk.auth,
+            usk.get_epoch()
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// The epochs of all authorities in the policy, as recorded in the ciphertext
+pub fn policy_epochs(mpk: &FullKey<impl PartialKey>, policy: &Policy) -> HashMap<String, u64> {
+    let mut epochs = HashMap::new();
+    for j in 0..policy.len() {
+        let auth = policy.get(j).0.auth;
+        if let Some(epoch) = mpk.get_epoch(&auth) {
+            epochs.insert(auth, epoch);
+        }
+    }
+    epochs
+}
+
+// Whether the user's partial keys for the authorities of the given rows are from the
+// epochs the ciphertext was encrypted under
+pub fn epochs_match<'a>(
+    usk: &FullKey<impl PartialKey>,
+    epochs: &HashMap<String, u64>,
+    policy: &Policy,
+    rows: impl IntoIterator<Item = &'a usize>,
+) -> bool {
+    rows.into_iter().all(|j| {
+        let auth = policy.get(*j).0.auth;
+        usk.get_epoch(&auth) == epochs.get(&auth).copied()
+    })
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::policy::UserAttribute;
 
+#[derive(Clone)]
 pub struct Iota {
     storage: HashMap<String, HashMap<(String, String), usize>>,
     m: usize,
@@ -41,10 +42,40 @@ impl Iota {
         Iota { storage, m }
     }
 
+    // Adds attributes issued to an existing key. They are indexed above all attributes
+    // already in the mapping, so that the new key components never share randomness with
+    // the existing ones. Attributes which are already mapped keep their index.
+    pub fn extend(&mut self, user_attrs: &Vec<UserAttribute>) {
+        let base = if self.storage.values().all(|inner| inner.is_empty()) {
+            0
+        } else {
+            self.m + 1
+        };
+        let mut next_by_auth_lbl = HashMap::new();
+        for ua in user_attrs.iter() {
+            let inner = self.storage.entry(String::from(&ua.auth)).or_default();
+            let key = (ua.lbl.clone(), ua.attr.clone());
+            if inner.contains_key(&key) {
+                continue;
+            }
+            let i = next_by_auth_lbl.entry(ua.auth_lbl()).or_insert(base);
+            inner.insert(key, *i);
+            self.m = std::cmp::max(self.m, *i);
+            *i += 1;
+        }
+    }
+
     pub fn get_max(&self) -> usize {
         self.m
     }
 
+    pub fn contains(&self, auth: &str, lbl: &str, attr: &str) -> bool {
+        let key = (String::from(lbl), String::from(attr));
+        self.storage
+            .get(auth)
+            .is_some_and(|inner| inner.contains_key(&key))
+    }
+
     pub fn get(&self, auth: &str, lbl: &str, attr: &str) -> usize {
         let key = (String::from(lbl), String::from(attr));
         *self.storage.get(auth).unwrap().get(&key).unwrap()
@@ -106,3 +137,33 @@ fn test_iota_complex() {
     assert_eq!(iota.get("1", "2", "3"), 2);
     assert_eq!(iota.get("1", "2", "4"), 3);
 }
+
+#[test]
+fn test_iota_extend() {
+    let user_attrs = vec![
+        UserAttribute::new("0", "0", "0"),
+        UserAttribute::new("0", "0", "1"),
+        UserAttribute::new("1", "0", "0"),
+    ];
+    let mut iota = Iota::new(&user_attrs);
+    iota.extend(&vec![
+        UserAttribute::new("0", "0", "1"),
+        UserAttribute::new("0", "0", "2"),
+        UserAttribute::new("0", "1", "0"),
+        UserAttribute::new("0", "1", "1"),
+        UserAttribute::new("2", "0", "0"),
+    ]);
+    assert_eq!(iota.m, 3);
+    assert_eq!(iota.get("0", "0", "0"), 0);
+    assert_eq!(iota.get("0", "0", "1"), 1);
+    assert_eq!(iota.get("1", "0", "0"), 0);
+    assert_eq!(iota.get("0", "0", "2"), 2);
+    assert_eq!(iota.get("0", "1", "0"), 2);
+    assert_eq!(iota.get("0", "1", "1"), 3);
+    assert_eq!(iota.get("2", "0", "0"), 2);
+
+    let mut iota = Iota::new(&Vec::new());
+    iota.extend(&user_attrs);
+    assert_eq!(iota.m, 1);
+    assert_eq!(iota.get("0", "0", "1"), 1);
+}
@@ -0,0 +1,61 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::policy::UserAttribute;
+
+use super::{FullKey, Iota, PartialKey};
+
+// The checks of `Scheme::keygen_extend` which all variants share. Every authority of the
+// new attributes needs a partial MSK, and a partial key which the user already holds has to
+// be in the current epoch and pass the checks of the variant. Iota is extended by the new
+// attributes, which are returned grouped by authority for the variant to issue.
+pub fn extend_attrs<M: PartialKey, U: PartialKey>(
+    msk: &FullKey<M>,
+    usk: &FullKey<U>,
+    held: &[UserAttribute],
+    user_attrs: &[UserAttribute],
+    iota: &mut Iota,
+    check_partial: impl Fn(&U, &[UserAttribute]) -> Result<(), String>,
+) -> Result<HashMap<String, Vec<UserAttribute>>, String> {
+    let user_attr_by_auth = new_attrs_by_auth(held, user_attrs)?;
+    for (auth, uas) in user_attr_by_auth.iter() {
+        let Some(partial_msk) = msk.get_partial_key(auth) else {
+            return Err(format!("No partial MSK given for authority '{}'", auth));
+        };
+        if let Some(partial_usk) = usk.get_partial_key(auth) {
+            if partial_usk.get_epoch() != partial_msk.get_epoch() {
+                return Err(format!(
+                    "The key for authority '{}' has to be updated to the current epoch first",
+                    auth
+                ));
+            }
+            check_partial(partial_usk, uas)?;
+        }
+    }
+    let new_attrs = user_attr_by_auth.values().flatten().cloned().collect();
+    iota.extend(&new_attrs);
+    Ok(user_attr_by_auth)
+}
+
+// Groups the attributes to be added to an existing key by authority and drops duplicates.
+// Adding an attribute which the key already holds is an error.
+fn new_attrs_by_auth(
+    held: &[UserAttribute],
+    user_attrs: &[UserAttribute],
+) -> Result<HashMap<String, Vec<UserAttribute>>, String> {
+    let held: HashSet<(String, String, String)> =
+        held.iter().map(|ua| ua.auth_lbl_attr()).collect();
+    let mut seen = HashSet::new();
+    let mut user_attr_by_auth: HashMap<String, Vec<UserAttribute>> = HashMap::new();
+    for ua in user_attrs.iter() {
+        if held.contains(&ua.auth_lbl_attr()) {
+            return Err(format!("Attribute {:?} is already part of the key", ua));
+        }
+        if seen.insert(ua.auth_lbl_attr()) {
+            user_attr_by_auth
+                .entry(ua.auth.clone())
+                .or_default()
+                .push(ua.clone());
+        }
+    }
+    Ok(user_attr_by_auth)
+}
@@ -0,0 +1,92 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::curve::GAffine;
+use crate::hashing::{
+    hash_lbl,
+    HashSign::{NEG, POS},
+};
+use crate::policy::UserAttribute;
+
+// The hashes of a label which go into the key of every user with an attribute under it
+#[derive(Clone, Copy)]
+pub struct LabelHashes {
+    pub pos_0: GAffine,
+    pub pos_1: GAffine,
+    pub neg_0: GAffine,
+    pub neg_1: GAffine,
+}
+
+impl LabelHashes {
+    pub fn new(auth: &str, lbl: &str) -> Self {
+        LabelHashes {
+            pos_0: hash_lbl(auth, lbl, POS, 0),
+            pos_1: hash_lbl(auth, lbl, POS, 1),
+            neg_0: hash_lbl(auth, lbl, NEG, 0),
+            neg_1: hash_lbl(auth, lbl, NEG, 1),
+        }
+    }
+}
+
+// The labels of the given attributes, by authority
+pub fn lbls_by_auth<'a>(
+    user_attrs: impl IntoIterator<Item = &'a UserAttribute>,
+) -> HashMap<String, HashSet<String>> {
+    let mut lbls: HashMap<String, HashSet<String>> = HashMap::new();
+    for ua in user_attrs {
+        lbls.entry(ua.auth.clone())
+            .or_default()
+            .insert(ua.lbl.clone());
+    }
+    lbls
+}
+
+// Maps the items on all available threads. Every item gets its own RNG, seeded from `rng`
+// in the order of the items, so the result does not depend on the number of threads.
+pub fn par_map_seeded<T: Sync, U: Send>(
+    mut rng: impl Rng,
+    items: &[T],
+    f: impl Fn(&mut StdRng, &T) -> U + Sync,
+) -> Vec<U> {
+    let seeds: Vec<[u8; 32]> = items.iter().map(|_| rng.gen()).collect();
+    let num_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = items.len().div_ceil(num_threads).max(1);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .zip(seeds.chunks(chunk_size))
+            .map(|(items, seeds)| {
+                scope.spawn(move || {
+                    items
+                        .iter()
+                        .zip(seeds.iter())
+                        .map(|(item, seed)| f(&mut StdRng::from_seed(*seed), item))
+                        .collect::<Vec<U>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn test_par_map_seeded() {
+    let items: Vec<u64> = (0..100).collect();
+    let mapped = par_map_seeded(ark_std::test_rng(), &items, |rng, i| (*i, rng.gen::<u64>()));
+    let mut rng = ark_std::test_rng();
+    for (i, (j, x)) in mapped.into_iter().enumerate() {
+        let seed: [u8; 32] = rng.gen();
+        assert_eq!(i as u64, j);
+        assert_eq!(x, StdRng::from_seed(seed).gen::<u64>());
+    }
+}
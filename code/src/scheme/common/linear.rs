@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{AddAssign, Mul};
+
+use crate::curve::ScalarField;
+
+use super::PartialUSK;
+
+// All components of a user key are linear in the master key and the randomness. Thus
+// rerandomization adds a fresh key with the same layout to an existing one, outsourcing
+// raises every component to 1/z, and a threshold authority interpolates the key shares
+// of its servers in the exponent.
+pub trait LinearKey {
+    // Raises every component of the key to e
+    fn scale(&self, e: ScalarField) -> Self;
+
+    // Adds a key with the same layout component-wise
+    fn add(&mut self, delta: Self);
+}
+
+pub fn scale_vec<T: Mul<ScalarField, Output = T> + Copy>(v: &[T], e: ScalarField) -> Vec<T> {
+    v.iter().map(|x| *x * e).collect()
+}
+
+pub fn scale_map<K: Eq + Hash + Clone, T: Mul<ScalarField, Output = T> + Copy>(
+    m: &HashMap<K, T>,
+    e: ScalarField,
+) -> HashMap<K, T> {
+    m.iter().map(|(k, x)| (k.clone(), *x * e)).collect()
+}
+
+pub fn add_vecs<T: AddAssign>(lhs: &mut [T], rhs: Vec<T>) {
+    if lhs.len() != rhs.len() {
+        panic!("Fatal error: cannot add components of different length");
+    }
+    for (l, r) in lhs.iter_mut().zip(rhs) {
+        *l += r;
+    }
+}
+
+pub fn add_maps<K: Eq + Hash, T: AddAssign>(lhs: &mut HashMap<K, T>, mut rhs: HashMap<K, T>) {
+    for (key, l) in lhs.iter_mut() {
+        match rhs.remove(key) {
+            Some(r) => *l += r,
+            None => panic!("Fatal error: cannot add components with different keys"),
+        }
+    }
+}
+
+// Interpolates the key shares of the servers of a threshold authority in the exponent,
+// given the Lagrange coefficient of each share. All shares have to be issued for the same
+// attributes.
+pub fn combine_shares<T: LinearKey>(shares: Vec<(ScalarField, T)>) -> T {
+    let mut shares = shares
+        .into_iter()
+        .map(|(lambda, share)| share.scale(lambda));
+    let Some(mut usk) = shares.next() else {
+        panic!("Fatal error: cannot combine an empty list of key shares");
+    };
+    for share in shares {
+        usk.add(share);
+    }
+    usk
+}
+
+impl LinearKey for PartialUSK {
+    fn scale(&self, e: ScalarField) -> Self {
+        PartialUSK {
+            auth: self.auth.clone(),
+            epoch: self.epoch,
+            k_1_map: scale_map(&self.k_1_map, e),
+            k_2_map: scale_map(&self.k_2_map, e),
+            k_3_map: scale_map(&self.k_3_map, e),
+            k_4_map: scale_map(&self.k_4_map, e),
+            k_5_map: scale_map(&self.k_5_map, e),
+        }
+    }
+
+    fn add(&mut self, delta: Self) {
+        add_maps(&mut self.k_1_map, delta.k_1_map);
+        add_maps(&mut self.k_2_map, delta.k_2_map);
+        add_maps(&mut self.k_3_map, delta.k_3_map);
+        add_maps(&mut self.k_4_map, delta.k_4_map);
+        add_maps(&mut self.k_5_map, delta.k_5_map);
+    }
+}
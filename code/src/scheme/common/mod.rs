@@ -9,3 +9,50 @@ pub use tau::Tau;
 
 mod group_pairs;
 pub use group_pairs::group_pairs;
+
+mod size;
+pub use size::{g_size, h_size};
+
+mod cost;
+pub use cost::{reconstruct_secret_with, CostContext, DecryptionCost, ReconstructionCost};
+
+mod weights;
+pub use weights::{solve_lsss, WeightedRows, Weights};
+
+mod decryption;
+pub use decryption::{decrypt, decrypt_lsss, decrypt_many, transform, SolvedDecryption};
+
+mod issuance;
+pub use issuance::extend_attrs;
+
+mod epoch;
+pub use epoch::{check_update_key, epochs_match, policy_epochs, EpochUpdate, UpdateKey};
+
+mod policy_update;
+pub use policy_update::tighten_policy;
+
+mod linear;
+pub use linear::{add_maps, add_vecs, combine_shares, scale_map, scale_vec, LinearKey};
+
+mod rerandomize;
+pub use rerandomize::{
+    add_ciphertexts, rerandomize_ciphertext, rerandomize_usk, RerandomizeMode, RowCiphertext,
+};
+
+mod outsourcing;
+pub use outsourcing::{blind_usk, RetrievalKey, TransformationKey, TransformedCiphertext};
+
+mod pool;
+pub use pool::{check_pool_epochs, EncryptionPool, Powers, Precompute};
+
+mod batch;
+pub use batch::{KeyCache, PairingProduct, SolutionCache};
+
+mod keygen_batch;
+pub use keygen_batch::{lbls_by_auth, par_map_seeded, LabelHashes};
+
+mod proofs;
+pub use proofs::{prove_key, respond, verify_key, write_point, KeyProof, PublicPoints};
+
+mod verify;
+pub use verify::{check_authority, check_index, check_keys, check_product, IssuedAttrs};
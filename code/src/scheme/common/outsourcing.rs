@@ -0,0 +1,52 @@
+use std::ops::Mul;
+
+use ark_ec::Group;
+use ark_ff::{Field, PrimeField};
+
+use crate::curve::{Gt, ScalarField, G};
+use crate::hashing::hash_gid;
+
+use super::{FullKey, LinearKey, PartialKey};
+
+// Outsourced decryption as proposed by Green, Hohenberger and Waters. Every component of
+// the user's key is raised to 1/z, and so are the generator and the hashed GID which
+// decryption pairs with the ciphertext. Decrypting with the transformation key thus gives
+// the encapsulated key to the power of 1/z, which only the holder of z can undo.
+pub struct TransformationKey<T> {
+    pub g: G,
+    pub gid: G,
+    pub usk: T,
+}
+
+pub struct RetrievalKey {
+    pub z: ScalarField,
+}
+
+// What the server sends back to the user after the transformation
+pub struct TransformedCiphertext {
+    pub k: Gt,
+}
+
+impl RetrievalKey {
+    pub fn retrieve(&self, ct: &TransformedCiphertext) -> Gt {
+        ct.k.pow(self.z.into_bigint())
+    }
+}
+
+// The transformation key for the blinding exponent z
+pub fn blind_usk<T: PartialKey + LinearKey>(
+    usk: &FullKey<T>,
+    gid: &str,
+    z: ScalarField,
+) -> TransformationKey<FullKey<T>> {
+    let z_inv = z.inverse().unwrap();
+    let mut blinded = FullKey::new();
+    for partial_usk in usk.partial_keys.values() {
+        blinded.add_partial_key(partial_usk.scale(z_inv));
+    }
+    TransformationKey {
+        g: G::generator().mul(z_inv),
+        gid: hash_gid(gid).mul(z_inv),
+        usk: blinded,
+    }
+}
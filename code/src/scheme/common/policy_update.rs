@@ -0,0 +1,91 @@
+use ark_ec::{Group, VariableBaseMSM};
+use ark_ff::UniformRand;
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+
+use crate::curve::{ScalarField, G, H};
+use crate::hashing::{
+    hash_attr, hash_lbl,
+    HashSign::{NEG, POS},
+};
+use crate::policy::{builder, Policy};
+
+use super::{Ciphertext, MPK};
+
+// Tightens the policy of a ciphertext to `policy & extra` without decrypting it. This
+// works for the variants in which every row has its own randomness (Opt0 to Opt2): a
+// fresh sharing of zero under the new policy is added to the shares of the existing rows,
+// and the rows of `extra` are appended with these shares and fresh randomness. The existing
+// rows get fresh randomness as well, i.e. an encryption of zero is added to each of them, so
+// that the result cannot be linked to the original ciphertext. This needs the public keys
+// of all authorities in the epochs of the ciphertext. The encapsulated key does not change.
+pub fn tighten_policy(
+    mut rng: impl Rng,
+    mpk: &MPK,
+    policy: &Policy,
+    ct: &Ciphertext,
+    extra: &Policy,
+) -> Result<(Ciphertext, Policy), String> {
+    let n = policy.len();
+    if ct.c_1_vec.len() != n {
+        return Err(String::from("The ciphertext does not belong to the policy"));
+    }
+    let new_policy = Policy::from_expr(builder::and(policy.expr().clone(), extra.expr().clone()));
+    let mut epochs = ct.epochs.clone();
+    for j in 0..new_policy.len() {
+        let auth = new_policy.get(j).0.auth;
+        let Some(epoch) = mpk.get_epoch(&auth) else {
+            return Err(format!("No partial MPK given for authority '{}'", auth));
+        };
+        if *epochs.entry(auth.clone()).or_insert(epoch) != epoch {
+            return Err(format!(
+                "Authority '{}' moved to a new epoch since the ciphertext was created",
+                auth
+            ));
+        }
+    }
+    let lsss = new_policy.lsss();
+    let lambda_vec = lsss.share(&mut rng, ScalarField::from(0));
+    let mu_vec = lsss.share(&mut rng, ScalarField::from(0));
+    let h = H::generator();
+    let mut c_1_vec = ct.c_1_vec.clone();
+    let mut c_2_vec = ct.c_2_vec.clone();
+    let mut c_3_vec = ct.c_3_vec.clone();
+    let mut c_4_vec = ct.c_4_vec.clone();
+    for j in 0..new_policy.len() {
+        let (user_attr, is_neg) = new_policy.get(j);
+        let s = ScalarField::rand(&mut rng);
+        let mpk = mpk.get_partial_key(&user_attr.auth).unwrap();
+        let (b, sign) = if is_neg {
+            (mpk.b_not, NEG)
+        } else {
+            (mpk.b, POS)
+        };
+        let lbl_0 = hash_lbl(&user_attr.auth, &user_attr.lbl, sign, 0);
+        let lbl_1 = hash_lbl(&user_attr.auth, &user_attr.lbl, sign, 1);
+        let x_attr = hash_attr(&user_attr.attr);
+        let c_1 = h.mul(mu_vec[j]) + b.mul(s);
+        let c_2 = G::msm(&[lbl_0, lbl_1], &[s, s * x_attr]).unwrap();
+        let c_3 = h.mul(lambda_vec[j]) + mpk.a.mul(s);
+        let c_4 = h.mul(s);
+        if j < n {
+            c_1_vec[j] += c_1;
+            c_2_vec[j] += c_2;
+            c_3_vec[j] += c_3;
+            c_4_vec[j] += c_4;
+        } else {
+            c_1_vec.push(c_1);
+            c_2_vec.push(c_2);
+            c_3_vec.push(c_3);
+            c_4_vec.push(c_4);
+        }
+    }
+    let ct = Ciphertext {
+        c_1_vec,
+        c_2_vec,
+        c_3_vec,
+        c_4_vec,
+        epochs,
+    };
+    Ok((ct, new_policy))
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use ark_ec::Group;
+use ark_ff::{One, UniformRand, Zero};
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+
+use crate::curve::{pairing, GAffine, Gt, ScalarField, G, H};
+use crate::hashing::{hash_lbl, HashSign};
+use crate::policy::{LsssMatrix, Policy};
+
+use super::{FullKey, PartialKey, PartialMPK};
+
+// The powers of an authority's public key which encryption raises to its randomness
+pub trait Precompute {
+    type Powers;
+
+    fn powers(&self, s: ScalarField) -> Self::Powers;
+}
+
+pub struct Powers {
+    pub a: H,
+    pub b: H,
+    pub b_not: H,
+}
+
+impl Precompute for PartialMPK {
+    type Powers = Powers;
+
+    fn powers(&self, s: ScalarField) -> Powers {
+        Powers {
+            a: self.a.mul(s),
+            b: self.b.mul(s),
+            b_not: self.b_not.mul(s),
+        }
+    }
+}
+
+// One value of the randomness of a ciphertext, with its powers for every authority
+pub struct PoolEntry<T> {
+    pub s: ScalarField,
+    pub h: H,
+    pub auths: HashMap<String, T>,
+}
+
+// Online/offline encryption. The offline phase fills the pool with everything encryption
+// computes before it looks at the policy: the encapsulated keys, h to the random entries
+// of the share vectors and the powers of the public keys. The online phase then computes
+// the shares in the exponent from the LSSS matrix, whose entries are 0, 1 and -1, with
+// group additions only.
+//
+// What stays online is the work which depends on the labels and attributes of the policy.
+// For every row, that is hashing the attribute to a scalar and one MSM of the two label
+// hashes with the randomness of the row, plus one more exponentiation of a label hash for
+// the negated rows of opt5 and opt6. The label hashes themselves are kept in the pool
+// across ciphertexts and epochs, so a label is only hashed to the curve the first time it
+// occurs, or offline with `hash_labels` if the labels are known in advance.
+pub struct EncryptionPool<T> {
+    pub epochs: HashMap<String, u64>,
+    // h^s and e(g, h)^s for the secret s
+    keys: Vec<(H, Gt)>,
+    shares: Vec<H>,
+    entries: Vec<PoolEntry<T>>,
+    lbls: HashMap<(String, String, HashSign, u64), GAffine>,
+}
+
+// What the online phase takes from the pool for one ciphertext
+pub struct PoolDraw<T> {
+    pub k: Gt,
+    pub lambda_vec: Vec<H>,
+    pub mu_vec: Vec<H>,
+    pub entries: Vec<PoolEntry<T>>,
+}
+
+impl<T> EncryptionPool<T> {
+    pub fn new() -> Self {
+        EncryptionPool {
+            epochs: HashMap::new(),
+            keys: Vec::new(),
+            shares: Vec::new(),
+            entries: Vec::new(),
+            lbls: HashMap::new(),
+        }
+    }
+
+    // The offline phase for `num_cts` ciphertexts with up to `max_rows` rows each. A pool
+    // filled under other epochs of the authorities is discarded first.
+    pub fn fill<P: PartialKey + Precompute<Powers = T>>(
+        &mut self,
+        mut rng: impl Rng,
+        mpk: &FullKey<P>,
+        num_cts: usize,
+        max_rows: usize,
+    ) {
+        let epochs: HashMap<String, u64> = mpk
+            .partial_keys
+            .iter()
+            .map(|(auth, mpk)| (auth.clone(), mpk.get_epoch()))
+            .collect();
+        if epochs != self.epochs {
+            self.keys.clear();
+            self.shares.clear();
+            self.entries.clear();
+            self.epochs = epochs;
+        }
+        let h = H::generator();
+        let e = pairing(G::generator(), h);
+        for _ in 0..num_cts {
+            let s = ScalarField::rand(&mut rng);
+            self.keys.push((h.mul(s), e.mul(s).0));
+            for _ in 0..2 * max_rows {
+                self.shares.push(h.mul(ScalarField::rand(&mut rng)));
+            }
+            for _ in 0..max_rows {
+                let s = ScalarField::rand(&mut rng);
+                let auths = mpk
+                    .partial_keys
+                    .iter()
+                    .map(|(auth, mpk)| (auth.clone(), mpk.powers(s)))
+                    .collect();
+                self.entries.push(PoolEntry {
+                    s,
+                    h: h.mul(s),
+                    auths,
+                });
+            }
+        }
+    }
+
+    // Takes a key with its shares and shares of zero in the exponent of h, and
+    // `num_entries` values of randomness. Nothing is taken if the pool runs short.
+    pub fn draw(&mut self, lsss: &LsssMatrix, num_entries: usize) -> Result<PoolDraw<T>, String> {
+        let num_shares = 2 * (lsss.num_cols() - 1);
+        if self.keys.is_empty()
+            || self.shares.len() < num_shares
+            || self.entries.len() < num_entries
+        {
+            return Err(String::from(
+                "The pool is exhausted, it has to be filled in the offline phase",
+            ));
+        }
+        let (h_s, k) = self.keys.pop().unwrap();
+        let mut v = vec![h_s];
+        let mut v_zero = vec![H::zero()];
+        for _ in 1..lsss.num_cols() {
+            v.push(self.shares.pop().unwrap());
+            v_zero.push(self.shares.pop().unwrap());
+        }
+        let entries = self.entries.split_off(self.entries.len() - num_entries);
+        Ok(PoolDraw {
+            k,
+            lambda_vec: share_in_exponent(lsss, &v),
+            mu_vec: share_in_exponent(lsss, &v_zero),
+            entries,
+        })
+    }
+
+    // `hash_lbl`, cached in the pool
+    pub fn hash_lbl(&mut self, auth: &str, lbl: &str, sign: HashSign, i: u64) -> GAffine {
+        *self
+            .lbls
+            .entry((String::from(auth), String::from(lbl), sign, i))
+            .or_insert_with(|| hash_lbl(auth, lbl, sign, i))
+    }
+
+    // Hashes the labels of the policy in the offline phase, with all signs and indices
+    // which the online phase of any variant uses for them
+    pub fn hash_labels(&mut self, policy: &Policy) {
+        for j in 0..policy.len() {
+            let (user_attr, is_neg) = policy.get(j);
+            let (auth, lbl) = (&user_attr.auth, &user_attr.lbl);
+            let (sign, num_hashes) = if is_neg {
+                (HashSign::NEG, 3)
+            } else {
+                (HashSign::POS, 2)
+            };
+            for i in 0..num_hashes {
+                self.lbls
+                    .entry((auth.clone(), lbl.clone(), sign, i))
+                    .or_insert_with(|| hash_lbl(auth, lbl, sign, i));
+            }
+        }
+    }
+}
+
+impl<T> Default for EncryptionPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// h^(M * v) for the given h^v
+fn share_in_exponent(lsss: &LsssMatrix, v: &[H]) -> Vec<H> {
+    let one = ScalarField::one();
+    (0..lsss.num_rows())
+        .map(|i| {
+            let mut share = H::zero();
+            for (m, v) in lsss.row(i).iter().zip(v.iter()) {
+                if m.is_zero() {
+                    continue;
+                } else if *m == one {
+                    share += v;
+                } else if *m == -one {
+                    share -= v;
+                } else {
+                    share += v.mul(m);
+                }
+            }
+            share
+        })
+        .collect()
+}
+
+// Checks that the pool was filled under the current epochs of the authorities in the
+// policy and returns these epochs, as recorded in the ciphertext
+pub fn check_pool_epochs<T>(
+    pool: &EncryptionPool<T>,
+    mpk: &FullKey<impl PartialKey>,
+    policy: &Policy,
+) -> Result<HashMap<String, u64>, String> {
+    let mut epochs = HashMap::new();
+    for j in 0..policy.len() {
+        let auth = policy.get(j).0.auth;
+        let Some(epoch) = mpk.get_epoch(&auth) else {
+            return Err(format!("No partial MPK given for authority '{}'", auth));
+        };
+        if pool.epochs.get(&auth) != Some(&epoch) {
+            return Err(format!(
+                "The pool was filled under another epoch of authority '{}'",
+                auth
+            ));
+        }
+        epochs.insert(auth, epoch);
+    }
+    Ok(epochs)
+}
+
+#[test]
+fn test_share_in_exponent() {
+    let policy = Policy::parse("(A.a:0 & !A.b:1) | (A.c:2 & A.d:3 & A.e:4)").unwrap();
+    let lsss = policy.lsss();
+    let mut rng = ark_std::test_rng();
+    let v: Vec<ScalarField> = (0..lsss.num_cols())
+        .map(|_| ScalarField::rand(&mut rng))
+        .collect();
+    let h = H::generator();
+    let h_v: Vec<H> = v.iter().map(|v| h.mul(v)).collect();
+    let shares = share_in_exponent(&lsss, &h_v);
+    for i in 0..lsss.num_rows() {
+        let lambda: ScalarField = lsss.row(i).iter().zip(v.iter()).map(|(m, v)| *m * v).sum();
+        assert_eq!(shares[i], h.mul(lambda));
+    }
+}
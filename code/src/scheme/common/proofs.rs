@@ -0,0 +1,106 @@
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+
+use crate::curve::ScalarField;
+use crate::hashing::hash_challenge;
+
+use super::{PartialKey, PartialMPK, Scheme};
+
+// The group elements of a public key in a fixed order, over which the challenges of
+// `KeyProof`s are computed
+pub trait PublicPoints {
+    fn write_points(&self, transcript: &mut Vec<u8>);
+}
+
+pub fn write_point(transcript: &mut Vec<u8>, point: &impl CanonicalSerialize) {
+    point.serialize_compressed(transcript).unwrap();
+}
+
+impl PublicPoints for PartialMPK {
+    fn write_points(&self, transcript: &mut Vec<u8>) {
+        write_point(transcript, &self.a);
+        write_point(transcript, &self.b);
+        write_point(transcript, &self.b_not);
+    }
+}
+
+// A Schnorr proof of knowledge of all secrets behind the public key of an authority, made
+// non-interactive with Fiat-Shamir. The public key is linear in the secrets (see
+// `Scheme::setup_authority_from`), so the commitment is the public key of random nonces and
+// the responses are the nonces plus the challenge times the secrets. A single challenge
+// covers the components in G and those in H, which ties them to the same prover.
+pub struct KeyProof<P> {
+    pub commitment: P,
+    pub responses: Vec<ScalarField>,
+}
+
+impl<P: PartialKey + PublicPoints> KeyProof<P> {
+    pub fn challenge(mpk: &P, commitment: &P) -> ScalarField {
+        let auth = mpk.get_auth();
+        let mut transcript = Vec::new();
+        transcript.extend_from_slice(&(auth.len() as u64).to_be_bytes());
+        transcript.extend_from_slice(auth.as_bytes());
+        transcript.extend_from_slice(&mpk.get_epoch().to_be_bytes());
+        mpk.write_points(&mut transcript);
+        commitment.write_points(&mut transcript);
+        hash_challenge(&transcript)
+    }
+}
+
+// The responses to a challenge for the given nonces and secrets
+pub fn respond(
+    nonces: &[ScalarField],
+    secrets: &[ScalarField],
+    c: ScalarField,
+) -> Vec<ScalarField> {
+    nonces
+        .iter()
+        .zip(secrets)
+        .map(|(k, x)| *k + c * x)
+        .collect()
+}
+
+pub fn prove_key<S: Scheme>(
+    rng: impl Rng,
+    scheme: &S,
+    msk: &S::PartialMSK,
+    mpk: &S::PartialMPK,
+) -> KeyProof<S::PartialMPK> {
+    let (nonces, commitment) = scheme.setup_authority(rng, &mpk.get_auth());
+    let c = KeyProof::challenge(mpk, &commitment);
+    let responses = respond(
+        &scheme.authority_secrets(&nonces),
+        &scheme.authority_secrets(msk),
+        c,
+    );
+    KeyProof {
+        commitment,
+        responses,
+    }
+}
+
+pub fn verify_key<S: Scheme>(
+    scheme: &S,
+    mpk: &S::PartialMPK,
+    proof: &KeyProof<S::PartialMPK>,
+) -> Result<(), String> {
+    let auth = mpk.get_auth();
+    if proof.commitment.get_auth() != auth {
+        return Err(format!(
+            "The proof of the public key of authority '{}' was made for authority '{}'",
+            auth,
+            proof.commitment.get_auth()
+        ));
+    }
+    let c = KeyProof::challenge(mpk, &proof.commitment);
+    let (_, lhs) = scheme.setup_authority_from(&auth, &proof.responses)?;
+    let one = ScalarField::from(1);
+    let rhs = scheme.combine_partial_mpks(&[(one, &proof.commitment), (c, mpk)]);
+    if lhs != rhs {
+        return Err(format!(
+            "The proof of the public key of authority '{}' is invalid",
+            auth
+        ));
+    }
+    Ok(())
+}
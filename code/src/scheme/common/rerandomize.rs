@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use ark_ff::UniformRand;
+use rand::Rng;
+
+use crate::curve::{Gt, ScalarField};
+use crate::policy::Policy;
+
+use super::{add_vecs, Ciphertext, FullKey, LinearKey, PartialKey};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RerandomizeMode {
+    // the ciphertext still encapsulates the same key
+    KeepKey,
+    // the key is multiplied by a fresh random factor, which is returned to the caller
+    BlindKey,
+}
+
+// What rerandomization checks on a ciphertext before adding a fresh encryption to it
+pub trait RowCiphertext {
+    fn num_rows(&self) -> usize;
+
+    fn epochs(&self) -> &HashMap<String, u64>;
+}
+
+impl RowCiphertext for Ciphertext {
+    fn num_rows(&self) -> usize {
+        self.c_1_vec.len()
+    }
+
+    fn epochs(&self) -> &HashMap<String, u64> {
+        &self.epochs
+    }
+}
+
+// Adds an encryption under the same policy to the ciphertext, which gives all of its
+// randomness and shares a fresh distribution. Returns the factor by which the
+// encapsulated key was multiplied, which is one if the key is kept. `encrypt_secret`
+// encrypts a given secret under the policy in the variant's layout.
+pub fn rerandomize_ciphertext<R: Rng, C: RowCiphertext>(
+    mut rng: R,
+    policy: &Policy,
+    ct: &mut C,
+    mode: RerandomizeMode,
+    encrypt_secret: impl FnOnce(&mut R, ScalarField) -> (Gt, C),
+    add: impl FnOnce(&mut C, C),
+) -> Result<Gt, String> {
+    if ct.num_rows() != policy.len() {
+        return Err(String::from("The ciphertext does not belong to the policy"));
+    }
+    let s = match mode {
+        RerandomizeMode::KeepKey => ScalarField::from(0),
+        RerandomizeMode::BlindKey => ScalarField::rand(&mut rng),
+    };
+    let (k, delta) = encrypt_secret(&mut rng, s);
+    if delta.epochs() != ct.epochs() {
+        return Err(String::from(
+            "An authority moved to a new epoch since the ciphertext was created",
+        ));
+    }
+    add(ct, delta);
+    Ok(k)
+}
+
+// Encryption is linear in the secret and the randomness as well
+pub fn add_ciphertexts(ct: &mut Ciphertext, delta: Ciphertext) {
+    add_vecs(&mut ct.c_1_vec, delta.c_1_vec);
+    add_vecs(&mut ct.c_2_vec, delta.c_2_vec);
+    add_vecs(&mut ct.c_3_vec, delta.c_3_vec);
+    add_vecs(&mut ct.c_4_vec, delta.c_4_vec);
+}
+
+// Adds a key for the same attributes under the zero master key, which consists of nothing
+// but fresh randomness, to every partial key. The GID binding and the epochs of the key are
+// kept. `keygen_zero` issues this key for the attributes of a partial key.
+pub fn rerandomize_usk<R: Rng, T: PartialKey + LinearKey>(
+    mut rng: R,
+    usk: &mut FullKey<T>,
+    mut keygen_zero: impl FnMut(&mut R, &T) -> Result<T, String>,
+) -> Result<(), String> {
+    for usk in usk.partial_keys.values_mut() {
+        let delta = keygen_zero(&mut rng, usk)?;
+        usk.add(delta);
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use ark_serialize::CanonicalSerialize;
+use ark_std::Zero;
+
+use crate::curve::{G, H};
+
+// Sizes of the group elements in compressed form (in bytes)
+
+pub fn g_size() -> usize {
+    G::zero().compressed_size()
+}
+
+pub fn h_size() -> usize {
+    H::zero().compressed_size()
+}
@@ -5,51 +5,38 @@ use crate::policy::Policy;
 #[cfg(test)]
 use crate::policy::UserAttribute;
 
+// Tau is defined per row of the policy (and not per attribute), so that a literal which
+// occurs several times in the policy gets a separate index for each occurrence.
+#[derive(PartialEq)]
 pub struct Tau {
-    storage_tilde: HashMap<(String, String, String), usize>,
+    storage_tilde: Vec<usize>,
     m_tilde: usize,
-    storage: HashMap<(String, String, String), usize>,
+    storage: Vec<usize>,
     m: usize,
 }
 
 impl Tau {
     pub fn new(policy: &Policy) -> Self {
         let n = policy.len();
-        let mut user_attr_by_auth = HashMap::new();
-        let mut user_attrs_by_auth_lbl = HashMap::new();
-        for i in 0..n {
-            let (ua, _) = policy.get(i);
-            let uas = user_attr_by_auth
-                .entry(String::from(&ua.auth))
-                .or_insert(Vec::new());
-            uas.push(ua.clone());
-            let key = (ua.auth.clone(), ua.lbl.clone());
-            let uas = user_attrs_by_auth_lbl.entry(key).or_insert(Vec::new());
-            uas.push(ua);
-        }
+        let mut count_by_auth = HashMap::new();
+        let mut count_by_auth_lbl = HashMap::new();
 
-        let mut storage_tilde = HashMap::new();
+        let mut storage_tilde = Vec::with_capacity(n);
         let mut m_tilde = 0;
-        for (_, uas) in user_attr_by_auth {
-            let mut i = 0;
-            for ua in uas {
-                let key = (ua.auth, ua.lbl, ua.attr);
-                storage_tilde.insert(key, i);
-                m_tilde = std::cmp::max(m_tilde, i);
-                i += 1;
-            }
-        }
-
-        let mut storage = HashMap::new();
+        let mut storage = Vec::with_capacity(n);
         let mut m = 0;
-        for ((_, _), uas) in user_attrs_by_auth_lbl {
-            let mut i = 0;
-            for ua in uas {
-                let key = (ua.auth, ua.lbl, ua.attr);
-                storage.insert(key, i);
-                m = std::cmp::max(m, i);
-                i += 1;
-            }
+        for j in 0..n {
+            let (ua, _) = policy.get(j);
+
+            let i = count_by_auth.entry(ua.auth.clone()).or_insert(0);
+            storage_tilde.push(*i);
+            m_tilde = std::cmp::max(m_tilde, *i);
+            *i += 1;
+
+            let i = count_by_auth_lbl.entry(ua.auth_lbl()).or_insert(0);
+            storage.push(*i);
+            m = std::cmp::max(m, *i);
+            *i += 1;
         }
 
         Tau {
@@ -64,18 +51,16 @@ impl Tau {
         self.m_tilde
     }
 
-    pub fn get_tilde(&self, auth: &str, lbl: &str, attr: &str) -> usize {
-        let key = (String::from(auth), String::from(lbl), String::from(attr));
-        *self.storage_tilde.get(&key).unwrap()
+    pub fn get_tilde(&self, j: usize) -> usize {
+        self.storage_tilde[j]
     }
 
     pub fn get_max(&self) -> usize {
         self.m
     }
 
-    pub fn get(&self, auth: &str, lbl: &str, attr: &str) -> usize {
-        let key = (String::from(auth), String::from(lbl), String::from(attr));
-        *self.storage.get(&key).unwrap()
+    pub fn get(&self, j: usize) -> usize {
+        self.storage[j]
     }
 }
 
@@ -92,12 +77,12 @@ fn test_tau_simple() {
     let policy = Policy::conjunction_of(&user_attrs, 0);
     let tau = Tau::new(&policy);
     assert_eq!(tau.m, 3);
-    assert_eq!(tau.get("0", "0", "0"), 0);
-    assert_eq!(tau.get("0", "0", "1"), 1);
-    assert_eq!(tau.get("0", "0", "2"), 2);
-    assert_eq!(tau.get("0", "0", "3"), 3);
-    assert_eq!(tau.get("1", "0", "0"), 0);
-    assert_eq!(tau.get("2", "0", "0"), 0);
+    assert_eq!(tau.get(0), 0);
+    assert_eq!(tau.get(1), 1);
+    assert_eq!(tau.get(2), 2);
+    assert_eq!(tau.get(3), 3);
+    assert_eq!(tau.get(4), 0);
+    assert_eq!(tau.get(5), 0);
 }
 
 #[test]
@@ -117,16 +102,16 @@ fn test_tau_complex() {
     let policy = Policy::conjunction_of(&user_attrs, 0);
     let tau = Tau::new(&policy);
     assert_eq!(tau.m, 2);
-    assert_eq!(tau.get("0", "0", "0"), 0);
-    assert_eq!(tau.get("0", "0", "1"), 1);
-    assert_eq!(tau.get("0", "1", "2"), 0);
-    assert_eq!(tau.get("0", "1", "3"), 1);
-    assert_eq!(tau.get("0", "1", "4"), 2);
-    assert_eq!(tau.get("1", "0", "5"), 0);
-    assert_eq!(tau.get("1", "1", "6"), 0);
-    assert_eq!(tau.get("1", "2", "7"), 0);
-    assert_eq!(tau.get("1", "3", "8"), 0);
-    assert_eq!(tau.get("1", "3", "9"), 1);
+    assert_eq!(tau.get(0), 0);
+    assert_eq!(tau.get(1), 1);
+    assert_eq!(tau.get(2), 0);
+    assert_eq!(tau.get(3), 1);
+    assert_eq!(tau.get(4), 2);
+    assert_eq!(tau.get(5), 0);
+    assert_eq!(tau.get(6), 0);
+    assert_eq!(tau.get(7), 0);
+    assert_eq!(tau.get(8), 0);
+    assert_eq!(tau.get(9), 1);
 }
 
 #[test]
@@ -146,16 +131,16 @@ fn test_tau_tilde_simple() {
     let policy = Policy::conjunction_of(&user_attrs, 0);
     let tau = Tau::new(&policy);
     assert_eq!(tau.m_tilde, 4);
-    assert_eq!(tau.get_tilde("0", "0", "0"), 0);
-    assert_eq!(tau.get_tilde("0", "1", "1"), 1);
-    assert_eq!(tau.get_tilde("0", "2", "2"), 2);
-    assert_eq!(tau.get_tilde("0", "3", "3"), 3);
-    assert_eq!(tau.get_tilde("0", "4", "4"), 4);
-    assert_eq!(tau.get_tilde("1", "5", "5"), 0);
-    assert_eq!(tau.get_tilde("1", "6", "6"), 1);
-    assert_eq!(tau.get_tilde("1", "7", "7"), 2);
-    assert_eq!(tau.get_tilde("1", "8", "8"), 3);
-    assert_eq!(tau.get_tilde("1", "9", "9"), 4);
+    assert_eq!(tau.get_tilde(0), 0);
+    assert_eq!(tau.get_tilde(1), 1);
+    assert_eq!(tau.get_tilde(2), 2);
+    assert_eq!(tau.get_tilde(3), 3);
+    assert_eq!(tau.get_tilde(4), 4);
+    assert_eq!(tau.get_tilde(5), 0);
+    assert_eq!(tau.get_tilde(6), 1);
+    assert_eq!(tau.get_tilde(7), 2);
+    assert_eq!(tau.get_tilde(8), 3);
+    assert_eq!(tau.get_tilde(9), 4);
 }
 
 #[test]
@@ -179,18 +164,36 @@ fn test_tau_tilde_complex() {
     let policy = Policy::conjunction_of(&user_attrs, 0);
     let tau = Tau::new(&policy);
     assert_eq!(tau.m_tilde, 4);
-    assert_eq!(tau.get_tilde("0", "0", "0"), 0);
-    assert_eq!(tau.get_tilde("0", "0", "1"), 1);
-    assert_eq!(tau.get_tilde("0", "0", "2"), 2);
-    assert_eq!(tau.get_tilde("0", "1", "3"), 3);
-    assert_eq!(tau.get_tilde("1", "1", "0"), 0);
-    assert_eq!(tau.get_tilde("1", "2", "1"), 1);
-    assert_eq!(tau.get_tilde("1", "2", "2"), 2);
-    assert_eq!(tau.get_tilde("1", "2", "3"), 3);
-    assert_eq!(tau.get_tilde("1", "2", "4"), 4);
-    assert_eq!(tau.get_tilde("2", "1", "0"), 0);
-    assert_eq!(tau.get_tilde("2", "2", "1"), 1);
-    assert_eq!(tau.get_tilde("3", "1", "1"), 0);
-    assert_eq!(tau.get_tilde("3", "1", "2"), 1);
-    assert_eq!(tau.get_tilde("3", "2", "1"), 2);
+    assert_eq!(tau.get_tilde(0), 0);
+    assert_eq!(tau.get_tilde(1), 1);
+    assert_eq!(tau.get_tilde(2), 2);
+    assert_eq!(tau.get_tilde(3), 3);
+    assert_eq!(tau.get_tilde(4), 0);
+    assert_eq!(tau.get_tilde(5), 1);
+    assert_eq!(tau.get_tilde(6), 2);
+    assert_eq!(tau.get_tilde(7), 3);
+    assert_eq!(tau.get_tilde(8), 4);
+    assert_eq!(tau.get_tilde(9), 0);
+    assert_eq!(tau.get_tilde(10), 1);
+    assert_eq!(tau.get_tilde(11), 0);
+    assert_eq!(tau.get_tilde(12), 1);
+    assert_eq!(tau.get_tilde(13), 2);
+}
+
+#[test]
+fn test_tau_repeated_literals() {
+    let policy = Policy::parse("(A.x:1 & B.y:2) | (A.x:1 & C.z:3) | !A.x:1").unwrap();
+    let tau = Tau::new(&policy);
+    assert_eq!(tau.m_tilde, 2);
+    assert_eq!(tau.get_tilde(0), 0);
+    assert_eq!(tau.get_tilde(1), 0);
+    assert_eq!(tau.get_tilde(2), 1);
+    assert_eq!(tau.get_tilde(3), 0);
+    assert_eq!(tau.get_tilde(4), 2);
+    assert_eq!(tau.m, 2);
+    assert_eq!(tau.get(0), 0);
+    assert_eq!(tau.get(1), 0);
+    assert_eq!(tau.get(2), 1);
+    assert_eq!(tau.get(3), 0);
+    assert_eq!(tau.get(4), 2);
 }
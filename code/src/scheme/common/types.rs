@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 
+use ark_ff::UniformRand;
 use rand::Rng;
 
 use crate::curve::{Gt, ScalarField, G, H};
-use crate::policy::{Policy, UserAttribute};
+use crate::policy::{LsssMatrix, Policy, UserAttribute};
 
-use super::{Iota, Tau};
+use super::{
+    combine_shares, EncryptionPool, EpochUpdate, Iota, LinearKey, PublicPoints, RerandomizeMode,
+    RetrievalKey, Tau, TransformationKey, TransformedCiphertext, UpdateKey,
+};
 
 pub trait PartialKey {
     fn get_auth(&self) -> String;
+
+    fn get_epoch(&self) -> u64;
 }
 
 pub struct FullKey<T> {
@@ -38,8 +44,20 @@ impl<T: PartialKey> FullKey<T> {
     }
 }
 
+// Assembles a full key from the partial keys of distinct authorities
+impl<T: PartialKey> FromIterator<T> for FullKey<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut key = FullKey::new();
+        for partial_key in iter {
+            key.add_partial_key(partial_key);
+        }
+        key
+    }
+}
+
 pub struct PartialMSK {
     pub auth: String,
+    pub epoch: u64,
     pub beta: ScalarField,
     pub b: ScalarField,
     pub b_not: ScalarField,
@@ -49,10 +67,16 @@ impl PartialKey for PartialMSK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
+#[derive(Clone, PartialEq)]
 pub struct PartialMPK {
     pub auth: String,
+    pub epoch: u64,
     pub a: H,
     pub b: H,
     pub b_not: H,
@@ -62,10 +86,15 @@ impl PartialKey for PartialMPK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
 pub struct PartialUSK {
     pub auth: String,
+    pub epoch: u64,
     pub k_1_map: HashMap<(String, String), G>,
     pub k_2_map: HashMap<String, G>,
     pub k_3_map: HashMap<(String, String), G>,
@@ -77,6 +106,10 @@ impl PartialKey for PartialUSK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
 pub type MSK = FullKey<PartialMSK>;
@@ -100,16 +133,19 @@ pub struct Ciphertext {
     pub c_2_vec: Vec<G>,
     pub c_3_vec: Vec<H>,
     pub c_4_vec: Vec<H>,
+    // epoch of every authority in the policy
+    pub epochs: HashMap<String, u64>,
 }
 
 pub trait Scheme {
     type MSK;
-    type PartialMSK;
-    type MPK;
-    type PartialMPK;
-    type USK;
-    type PartialUSK;
+    type PartialMSK: PartialKey;
+    type MPK: FromIterator<Self::PartialMPK>;
+    type PartialMPK: PartialKey + Clone + PartialEq + PublicPoints;
+    type USK: FromIterator<Self::PartialUSK>;
+    type PartialUSK: PartialKey + LinearKey;
     type Ciphertext;
+    type Powers;
 
     fn new() -> Self;
 
@@ -117,6 +153,9 @@ pub trait Scheme {
 
     fn setup(&self, rng: impl Rng, auths: &Vec<&str>) -> (Self::MSK, Self::MPK);
 
+    // Sets up a single authority on its own, see `Authority`
+    fn setup_authority(&self, rng: impl Rng, auth: &str) -> (Self::PartialMSK, Self::PartialMPK);
+
     fn keygen(
         &self,
         rng: impl Rng,
@@ -126,6 +165,128 @@ pub trait Scheme {
         iota: &Iota,
     ) -> Self::USK;
 
+    // Issues the partial key of a single authority. All attributes have to be managed by
+    // the authority.
+    fn keygen_authority(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        user_attrs: &[UserAttribute],
+        iota: &Iota,
+    ) -> Self::PartialUSK;
+
+    // The secret scalars of a partial MSK, which a threshold authority shares among its
+    // servers, see `ThresholdAuthority`
+    fn authority_secrets(&self, msk: &Self::PartialMSK) -> Vec<ScalarField>;
+
+    // Sets up a single authority with the given secrets instead of random ones. The public
+    // key is linear in the secrets.
+    fn setup_authority_from(
+        &self,
+        auth: &str,
+        secrets: &[ScalarField],
+    ) -> Result<(Self::PartialMSK, Self::PartialMPK), String>;
+
+    // Computes the public key of a linear combination of secrets from their public keys
+    fn combine_partial_mpks(&self, mpks: &[(ScalarField, &Self::PartialMPK)]) -> Self::PartialMPK;
+
+    // Issues the key share of a single server of a threshold authority, which only holds a
+    // share of the MSK. The MPK of the authority provides what the share lacks.
+    fn keygen_share(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        mpk: &Self::PartialMPK,
+        user_attrs: &[UserAttribute],
+        iota: &Iota,
+    ) -> Self::PartialUSK;
+
+    // The public key against which the key shares of a server are checked: the public key of
+    // its share of the MSK, with what `keygen_share` takes from the MPK of the authority
+    fn share_public_key(
+        &self,
+        share_mpk: &Self::PartialMPK,
+        _mpk: &Self::PartialMPK,
+    ) -> Self::PartialMPK {
+        share_mpk.clone()
+    }
+
+    // Interpolates the key shares of a threshold authority in the exponent, given the
+    // Lagrange coefficient of each share
+    fn combine_usk_shares(&self, shares: Vec<(ScalarField, Self::PartialUSK)>) -> Self::PartialUSK {
+        combine_shares(shares)
+    }
+
+    // Checks the components of a partial key with pairing equations against the MPK of its
+    // authority, so that keys which were computed wrongly are noticed before decryption
+    // fails. The key has to hold exactly the given attributes of the authority.
+    fn verify_partial_usk(
+        &self,
+        mpk: &Self::PartialMPK,
+        gid: &str,
+        user_attrs: &[UserAttribute],
+        iota: &Iota,
+        usk: &Self::PartialUSK,
+    ) -> Result<(), String>;
+
+    // Generates the keys of many users, each with its own GID, attributes and Iota. The
+    // keys are distributed exactly like those of `keygen`. Returns an error before any key
+    // is issued if the MSK lacks an authority of the attributes.
+    fn keygen_batch(
+        &self,
+        rng: impl Rng,
+        msk: &Self::MSK,
+        users: &[(&str, &Vec<UserAttribute>, &Iota)],
+    ) -> Result<Vec<Self::USK>, String>;
+
+    // Issues additional attributes to an existing key of the same GID. The key and its
+    // Iota are extended in place; on error, both are left unchanged.
+    fn keygen_extend(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        msk: &Self::MSK,
+        usk: &mut Self::USK,
+        user_attrs: &Vec<UserAttribute>,
+        iota: &mut Iota,
+    ) -> Result<(), String>;
+
+    // Moves an authority to its next epoch. The returned update stays with the authority,
+    // which issues update keys from it to all users that keep their attributes.
+    // Revocation is per authority and per user, not per attribute: a user without an
+    // update key loses all attributes of the authority at once. To take away a single
+    // attribute, the user is left without an update key and issued a fresh key for the
+    // attributes they keep.
+    fn rotate_epoch(
+        &self,
+        rng: impl Rng,
+        auth: &str,
+        msk: &mut Self::MSK,
+        mpk: &mut Self::MPK,
+    ) -> Result<EpochUpdate, String>;
+
+    // Moves the user's partial key of the update key's authority to its next epoch. The
+    // old key material is not kept, so the key no longer decrypts ciphertexts encrypted
+    // under an earlier epoch of the authority; those have to be decrypted before the
+    // update or encrypted anew.
+    fn update_usk(&self, usk: &mut Self::USK, uk: &UpdateKey) -> Result<(), String>;
+
+    // Raises every component of the key to 1/z, see `TransformationKey`
+    fn blind_usk(&self, usk: &Self::USK, gid: &str, z: ScalarField)
+        -> TransformationKey<Self::USK>;
+
+    // Gives the key fresh randomness while keeping its GID binding. Only public parameters
+    // are needed, so users can do this themselves.
+    fn rerandomize_usk(
+        &self,
+        rng: impl Rng,
+        mpk: &Self::MPK,
+        usk: &mut Self::USK,
+        iota: &Iota,
+    ) -> Result<(), String>;
+
     fn encrypt(
         &self,
         rng: impl Rng,
@@ -143,4 +304,127 @@ pub trait Scheme {
         policy: &Policy,
         ct: &Self::Ciphertext,
     ) -> Option<Gt>;
+
+    // Encrypts under an explicit LSSS matrix, e.g. one read with `LsssMatrix::from_bytes`,
+    // instead of the matrix of a policy. Tau is the one of `lsss.labels()`.
+    fn encrypt_lsss(
+        &self,
+        rng: impl Rng,
+        mpk: &Self::MPK,
+        lsss: &LsssMatrix,
+        tau: &Tau,
+    ) -> (Gt, Self::Ciphertext);
+
+    // Combines the rows whose labels the key satisfies with the reconstruction coefficients
+    // of the matrix, which are not restricted to 0 and 1 as for the rows of a policy
+    fn decrypt_lsss(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &Iota,
+        tau: &Tau,
+        lsss: &LsssMatrix,
+        ct: &Self::Ciphertext,
+    ) -> Option<Gt>;
+
+    // Decrypts many ciphertexts with the same key, with the same results as `decrypt`. What
+    // only depends on the key is computed once for all of them, see `KeyCache`. The pairings
+    // are not batched across ciphertexts: each one encapsulates a key of its own, so each
+    // one keeps its multi-pairing and final exponentiation.
+    fn decrypt_many(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &Iota,
+        cts: &[(&Policy, &Tau, &Self::Ciphertext)],
+    ) -> Vec<Option<Gt>>;
+
+    // Decrypts with a transformation key, which gives a transformed ciphertext only the
+    // holder of the retrieval key can turn into the encapsulated key
+    fn transform(
+        &self,
+        tk: &TransformationKey<Self::USK>,
+        iota: &Iota,
+        tau: &Tau,
+        policy: &Policy,
+        ct: &Self::Ciphertext,
+    ) -> Option<TransformedCiphertext>;
+
+    // Gives the ciphertext fresh randomness and shares without decrypting it. Returns the
+    // factor by which the encapsulated key was multiplied, see `RerandomizeMode`.
+    fn rerandomize_ciphertext(
+        &self,
+        rng: impl Rng,
+        mpk: &Self::MPK,
+        policy: &Policy,
+        tau: &Tau,
+        ct: &mut Self::Ciphertext,
+        mode: RerandomizeMode,
+    ) -> Result<Gt, String>;
+
+    // The offline phase of encryption, see `EncryptionPool`
+    fn fill_pool(
+        &self,
+        rng: impl Rng,
+        pool: &mut EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        num_cts: usize,
+        max_rows: usize,
+    );
+
+    // Encrypts with values drawn from the pool. Fails if the pool is exhausted or was
+    // filled under other epochs of the authorities in the policy.
+    fn encrypt_online(
+        &self,
+        pool: &mut EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        policy: &Policy,
+        tau: &Tau,
+    ) -> Result<(Gt, Self::Ciphertext), String>;
+
+    // Size (in bytes) of the compressed ciphertext for the given policy
+    fn ciphertext_size(&self, policy: &Policy, tau: &Tau) -> usize;
+
+    // Encrypts under the normalized policy, which must then be used (together with
+    // its Tau) for decryption. Contradictory policies are used as they are.
+    fn encrypt_normalized(
+        &self,
+        rng: impl Rng,
+        mpk: &Self::MPK,
+        policy: &Policy,
+    ) -> (Gt, Self::Ciphertext, Policy) {
+        let policy = policy.normalize().unwrap_or_else(|| policy.clone());
+        let tau = Tau::new(&policy);
+        let (k, ct) = self.encrypt(rng, mpk, &policy, &tau);
+        (k, ct, policy)
+    }
+
+    // Restricts the ciphertext to `policy & extra` without decrypting it, returning the new
+    // ciphertext and its policy. Only variants with independent randomness per row support
+    // this; in all others, the rows of different authorities share their randomness.
+    fn update_policy(
+        &self,
+        _rng: impl Rng,
+        _mpk: &Self::MPK,
+        _policy: &Policy,
+        _ct: &Self::Ciphertext,
+        _extra: &Policy,
+    ) -> Result<(Self::Ciphertext, Policy), String> {
+        Err(format!(
+            "{} does not support policy updates",
+            self.get_name()
+        ))
+    }
+
+    // Splits off a transformation key, with which an untrusted server can do the expensive
+    // part of decryption, and a retrieval key, with which the user finishes decryption
+    fn transformation_key(
+        &self,
+        mut rng: impl Rng,
+        usk: &Self::USK,
+        gid: &str,
+    ) -> (TransformationKey<Self::USK>, RetrievalKey) {
+        let z = ScalarField::rand(&mut rng);
+        (self.blind_usk(usk, gid, z), RetrievalKey { z })
+    }
 }
@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use ark_ff::One;
+
+use crate::policy::UserAttribute;
+
+use super::{Iota, PairingProduct, PartialKey};
+
+// The labels and the (label, attribute) pairs of the attributes an authority issued
+pub struct IssuedAttrs {
+    pub lbls: HashSet<String>,
+    pub pairs: HashSet<(String, String)>,
+}
+
+impl IssuedAttrs {
+    pub fn new(auth: &str, user_attrs: &[UserAttribute]) -> Self {
+        let mut lbls = HashSet::new();
+        let mut pairs = HashSet::new();
+        for ua in user_attrs.iter().filter(|ua| ua.auth == auth) {
+            lbls.insert(ua.lbl.clone());
+            pairs.insert((ua.lbl.clone(), ua.attr.clone()));
+        }
+        IssuedAttrs { lbls, pairs }
+    }
+
+    // The attributes of a label, which all contribute to its k_2
+    pub fn attrs_of<'a>(&'a self, lbl: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.pairs
+            .iter()
+            .filter(move |(l, _)| l == lbl)
+            .map(|(_, attr)| attr)
+    }
+}
+
+pub fn check_authority(mpk: &impl PartialKey, usk: &impl PartialKey) -> Result<(), String> {
+    if mpk.get_auth() != usk.get_auth() {
+        return Err(format!(
+            "The partial key of authority '{}' cannot be checked against the MPK of authority '{}'",
+            usk.get_auth(),
+            mpk.get_auth()
+        ));
+    }
+    if mpk.get_epoch() != usk.get_epoch() {
+        return Err(format!(
+            "The partial key of authority '{}' is for epoch {}, but the MPK for epoch {}",
+            usk.get_auth(),
+            usk.get_epoch(),
+            mpk.get_epoch()
+        ));
+    }
+    Ok(())
+}
+
+// Checks that a map of key components holds a component for exactly the given keys
+pub fn check_keys<K: Eq + Hash, T>(
+    map: &HashMap<K, T>,
+    keys: &HashSet<K>,
+    component: &str,
+) -> Result<(), String> {
+    if map.len() != keys.len() || keys.iter().any(|key| !map.contains_key(key)) {
+        return Err(format!(
+            "The components {} do not match the attributes of the key",
+            component
+        ));
+    }
+    Ok(())
+}
+
+// The index of the components of an attribute in the vectors of a key
+pub fn check_index(
+    iota: &Iota,
+    auth: &str,
+    pair: &(String, String),
+    len: usize,
+) -> Result<usize, String> {
+    let (lbl, attr) = pair;
+    if !iota.contains(auth, lbl, attr) {
+        return Err(format!(
+            "Iota does not hold attribute {}.{}:{}",
+            auth, lbl, attr
+        ));
+    }
+    let i = iota.get(auth, lbl, attr);
+    if i >= len {
+        return Err(format!(
+            "The key has no components for attribute {}.{}:{}",
+            auth, lbl, attr
+        ));
+    }
+    Ok(i)
+}
+
+// Checks that a pairing equation of a key component holds, i.e. that the product of all
+// pairings, with the component itself negated, is one
+pub fn check_product(
+    product: PairingProduct,
+    component: &str,
+    of: impl Debug,
+) -> Result<(), String> {
+    if !product.finish().is_one() {
+        return Err(format!(
+            "Key component {} of {:?} is malformed",
+            component, of
+        ));
+    }
+    Ok(())
+}
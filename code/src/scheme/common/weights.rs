@@ -0,0 +1,78 @@
+use ark_ec::Group;
+use ark_ff::{One, Zero};
+
+use crate::curve::ScalarField;
+use crate::policy::{LsssMatrix, UserAttribute};
+
+// The coefficients with which decryption combines the rows it uses. The rows chosen in
+// the policy tree (Lewko-Waters) all have coefficient one, whereas the rows of an
+// arbitrary LSSS matrix need the ones found by Gaussian elimination.
+#[derive(Clone)]
+pub enum Weights {
+    Unit,
+    Rows(Vec<ScalarField>),
+}
+
+impl Weights {
+    // Raises the term of row j to the coefficient of the row
+    pub fn scale<T: Group<ScalarField = ScalarField>>(&self, j: usize, x: T) -> T {
+        match self {
+            Weights::Rows(w) if !w[j].is_one() => x * w[j],
+            _ => x,
+        }
+    }
+}
+
+pub type WeightedRows = ((Vec<usize>, Vec<usize>), Weights);
+
+// Finds the rows with which a user decrypts under an explicit LSSS matrix, split into
+// positive and negated rows like `solve_lse`. Rows with coefficient zero are left out.
+pub fn solve_lsss(lsss: &LsssMatrix, user_attrs: &Vec<UserAttribute>) -> Option<WeightedRows> {
+    let rows = lsss.satisfied_rows(user_attrs);
+    let coeffs = lsss.reconstruction_coefficients(&rows)?;
+    let mut w = vec![ScalarField::zero(); lsss.num_rows()];
+    let mut eps_vec = Vec::new();
+    let mut eps_not_vec = Vec::new();
+    for (j, coeff) in rows.into_iter().zip(coeffs) {
+        if coeff.is_zero() {
+            continue;
+        }
+        w[j] = coeff;
+        if lsss.rho(j).1 {
+            eps_not_vec.push(j);
+        } else {
+            eps_vec.push(j);
+        }
+    }
+    Some(((eps_vec, eps_not_vec), Weights::Rows(w)))
+}
+
+#[test]
+fn test_solve_lsss() {
+    // a 2-out-of-3 threshold with the Vandermonde rows (1, i)
+    let rows = (1..=3)
+        .map(|i| vec![ScalarField::one(), ScalarField::from(i as u64)])
+        .collect();
+    let rho = ["A.a:0", "A.b:0", "A.c:0"]
+        .iter()
+        .map(|ua| (UserAttribute::parse(ua).unwrap(), false))
+        .collect();
+    let lsss = LsssMatrix::new(rows, rho).unwrap();
+
+    let user_attrs = vec![
+        UserAttribute::new("A", "a", "0"),
+        UserAttribute::new("A", "c", "0"),
+    ];
+    let ((eps_vec, eps_not_vec), weights) = solve_lsss(&lsss, &user_attrs).unwrap();
+    assert_eq!(eps_vec, vec![0, 2]);
+    assert!(eps_not_vec.is_empty());
+    let Weights::Rows(w) = weights else {
+        panic!("Expected coefficients per row");
+    };
+    // lambda_1 = s + r and lambda_3 = s + 3r, so s = 3/2 lambda_1 - 1/2 lambda_3
+    let two = ScalarField::from(2u64);
+    assert_eq!(w[0], ScalarField::from(3u64) / two);
+    assert_eq!(w[2], -ScalarField::one() / two);
+
+    assert!(solve_lsss(&lsss, &user_attrs[..1].to_vec()).is_none());
+}
@@ -8,8 +8,21 @@ mod opt4;
 mod opt5;
 mod opt6;
 
+mod api;
+mod authority;
+mod savings;
+mod threshold;
+
 pub use common::Scheme;
-pub use common::{Iota, Tau};
+pub use common::{EpochUpdate, Iota, RerandomizeMode, Tau, UpdateKey};
+pub use common::EncryptionPool;
+pub use common::KeyProof;
+pub use common::{RetrievalKey, TransformationKey, TransformedCiphertext};
+pub use common::{reconstruct_secret_with, CostContext, DecryptionCost, ReconstructionCost};
+pub use api::{Abe, LsssCiphertext, PolicyCiphertext, ServerKey, UserKey};
+pub use authority::{Authority, IssuedKey, MpkDirectory};
+pub use threshold::{DealtShare, Dealing, KeyServer, KeyShare, ThresholdAuthority};
+pub use savings::{normalization_savings, CiphertextSavings};
 
 pub use opt0::Opt0;
 pub use opt1::Opt1;
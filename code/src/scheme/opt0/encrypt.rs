@@ -13,48 +13,108 @@ use crate::hashing::{
     hash_attr, hash_lbl,
     HashSign::{NEG, POS},
 };
-use crate::policy::Policy;
-use crate::scheme::common::{Scheme, Tau};
+use crate::policy::{LsssMatrix, Policy};
+use crate::scheme::common::{
+    add_ciphertexts, check_pool_epochs, policy_epochs, rerandomize_ciphertext, EncryptionPool,
+    Powers, RerandomizeMode, Scheme, Tau,
+};
 
 fn share_secret(
     mut rng: impl Rng,
     secret: ScalarField,
-    policy: &Policy,
+    lsss: &LsssMatrix,
 ) -> (Vec<ScalarField>, Vec<ScalarField>, usize) {
+    let lambda_vec = lsss.share(&mut rng, secret);
+    let mu_vec = lsss.share(&mut rng, ScalarField::from(0));
+    (lambda_vec, mu_vec, lsss.num_rows())
+}
+
+pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, tau: &Tau) -> (Gt, Ciphertext) {
+    let s = ScalarField::rand(&mut rng);
+    encrypt_secret(rng, mpk, policy, &policy.lsss(), tau, s)
+}
+
+pub fn encrypt_lsss(
+    mut rng: impl Rng,
+    mpk: &MPK,
+    lsss: &LsssMatrix,
+    tau: &Tau,
+) -> (Gt, Ciphertext) {
+    let s = ScalarField::rand(&mut rng);
+    encrypt_secret(rng, mpk, &lsss.labels(), lsss, tau, s)
+}
+
+// The online phase of encryption, see `EncryptionPool`
+pub fn encrypt_online(
+    pool: &mut EncryptionPool<Powers>,
+    mpk: &MPK,
+    policy: &Policy,
+    _tau: &Tau,
+) -> Result<(Gt, Ciphertext), String> {
+    let epochs = check_pool_epochs(pool, mpk, policy)?;
     let n = policy.len();
-    let splits = policy.share_secret();
-    let mut v_vec = Vec::with_capacity(n);
-    let mut v_prime_vec = Vec::with_capacity(n);
-    let mut lambda_vec = Vec::with_capacity(n);
-    let mut mu_vec = Vec::with_capacity(n);
-    let zero = ScalarField::from(0);
-    for _ in 0..n {
-        v_vec.push(ScalarField::rand(&mut rng));
-        v_prime_vec.push(ScalarField::rand(&mut rng));
-        lambda_vec.push(zero);
-        mu_vec.push(zero);
-    }
-    v_vec[0] = secret;
-    v_prime_vec[0] = zero;
-    for (i, (_, idcs)) in splits.iter().enumerate() {
-        for j in idcs {
-            if *j >= 0 {
-                let k = *j as usize;
-                lambda_vec[i] += v_vec[k];
-                mu_vec[i] += v_prime_vec[k];
-            } else {
-                let k = (0 - *j) as usize;
-                lambda_vec[i] -= v_vec[k];
-                mu_vec[i] -= v_prime_vec[k];
-            }
-        }
+    let draw = pool.draw(&policy.lsss(), n)?;
+    let mut c_1_vec = Vec::with_capacity(n);
+    let mut c_2_vec = Vec::with_capacity(n);
+    let mut c_3_vec = Vec::with_capacity(n);
+    let mut c_4_vec = Vec::with_capacity(n);
+    for j in 0..n {
+        let (user_attr, is_neg) = policy.get(j);
+        let auth = user_attr.auth;
+        let lbl = user_attr.lbl;
+        let x_attr = hash_attr(&user_attr.attr);
+        let entry = &draw.entries[j];
+        let powers = entry.auths.get(&auth).unwrap();
+        let (b, sign) = if is_neg {
+            (powers.b_not, NEG)
+        } else {
+            (powers.b, POS)
+        };
+        let lbl_0 = pool.hash_lbl(&auth, &lbl, sign, 0);
+        let lbl_1 = pool.hash_lbl(&auth, &lbl, sign, 1);
+        c_1_vec.push(draw.mu_vec[j] + b);
+        let c_2 = G::msm(&[lbl_0, lbl_1], &[entry.s, entry.s * x_attr]).unwrap();
+        c_2_vec.push(c_2);
+        c_3_vec.push(draw.lambda_vec[j] + powers.a);
+        c_4_vec.push(entry.h);
     }
-    (lambda_vec, mu_vec, n)
+    let ct = Ciphertext {
+        c_1_vec,
+        c_2_vec,
+        c_3_vec,
+        c_4_vec,
+        epochs,
+    };
+    Ok((draw.k, ct))
 }
 
-pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, _tau: &Tau) -> (Gt, Ciphertext) {
-    let s = ScalarField::rand(&mut rng);
-    let (lambda_vec, mu_vec, n) = share_secret(&mut rng, s, &policy);
+pub fn rerandomize(
+    rng: impl Rng,
+    mpk: &MPK,
+    policy: &Policy,
+    tau: &Tau,
+    ct: &mut Ciphertext,
+    mode: RerandomizeMode,
+) -> Result<Gt, String> {
+    rerandomize_ciphertext(
+        rng,
+        policy,
+        ct,
+        mode,
+        |rng, s| encrypt_secret(rng, mpk, policy, &policy.lsss(), tau, s),
+        add_ciphertexts,
+    )
+}
+
+fn encrypt_secret(
+    mut rng: impl Rng,
+    mpk: &MPK,
+    policy: &Policy,
+    lsss: &LsssMatrix,
+    _tau: &Tau,
+    s: ScalarField,
+) -> (Gt, Ciphertext) {
+    let (lambda_vec, mu_vec, n) = share_secret(&mut rng, s, lsss);
     let mut s_vec = Vec::with_capacity(n);
     for _ in 0..n {
         s_vec.push(ScalarField::rand(&mut rng));
@@ -116,6 +176,7 @@ pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, _tau: &Tau) -> (Gt
         c_2_vec,
         c_3_vec,
         c_4_vec,
+        epochs: policy_epochs(mpk, policy),
     };
     return (k, ct);
 }
@@ -2,21 +2,24 @@ use std::collections::{HashMap, HashSet};
 
 use crate::curve::{ScalarField, G, H};
 use crate::policy::UserAttribute;
-use crate::scheme::common::{Iota, Scheme};
+use crate::scheme::common::{
+    check_authority, check_keys, check_product, check_update_key, extend_attrs, lbls_by_auth,
+    par_map_seeded, rerandomize_usk, Iota, IssuedAttrs, LabelHashes, PairingProduct, Scheme,
+    UpdateKey,
+};
 
 use ark_ec::{Group, VariableBaseMSM};
 use ark_ff::UniformRand;
 use ark_std::ops::Mul;
 use ark_std::rand::Rng;
+use ark_std::Zero;
 
 type MSK = <super::Opt0 as Scheme>::MSK;
 type PartialMSK = <super::Opt0 as Scheme>::PartialMSK;
+type PartialMPK = <super::Opt0 as Scheme>::PartialMPK;
 type USK = <super::Opt0 as Scheme>::USK;
 type PartialUSK = <super::Opt0 as Scheme>::PartialUSK;
-use crate::hashing::{
-    hash_attr, hash_gid, hash_lbl,
-    HashSign::{NEG, POS},
-};
+use crate::hashing::{hash_attr, hash_gid};
 
 pub fn keygen(
     mut rng: impl Rng,
@@ -45,13 +48,116 @@ pub fn keygen(
     usk
 }
 
+// Generates the keys of many users. The parts which depend on neither the GID nor the
+// randomness are computed once per authority, the keys of the users in parallel. Every
+// authority of the attributes needs a partial MSK, which is checked before any key is issued.
+pub fn keygen_batch(
+    rng: impl Rng,
+    msk: &MSK,
+    users: &[(&str, &Vec<UserAttribute>, &Iota)],
+) -> Result<Vec<USK>, String> {
+    let user_attrs = users
+        .iter()
+        .flat_map(|(_, user_attrs, _)| user_attrs.iter());
+    let mut bases_by_auth = HashMap::new();
+    for (auth, lbls) in lbls_by_auth(user_attrs) {
+        let Some(partial_msk) = msk.get_partial_key(&auth) else {
+            return Err(format!("No partial MSK given for authority '{}'", auth));
+        };
+        bases_by_auth.insert(auth, (partial_msk, bases(partial_msk, &lbls)));
+    }
+    let usks = par_map_seeded(rng, users, |rng, (gid, user_attrs, iota)| {
+        let mut user_attr_by_auth: HashMap<String, Vec<UserAttribute>> = HashMap::new();
+        for ua in user_attrs.iter() {
+            user_attr_by_auth
+                .entry(ua.auth.clone())
+                .or_default()
+                .push(ua.clone());
+        }
+        let mut usk = USK::new();
+        for (auth, uas) in user_attr_by_auth {
+            let (partial_msk, bases) = &bases_by_auth[&auth];
+            let partial_usk = keygen_partial_with(&mut *rng, gid, partial_msk, bases, &uas, iota);
+            usk.add_partial_key(partial_usk);
+        }
+        usk
+    });
+    Ok(usks)
+}
+
 pub fn keygen_partial(
-    mut rng: impl Rng,
+    rng: impl Rng,
     gid: &str,
     msk: &PartialMSK,
-    user_attrs: &Vec<UserAttribute>,
+    user_attrs: &[UserAttribute],
+    iota: &Iota,
+) -> PartialUSK {
+    let bases = bases(msk, user_attrs.iter().map(|ua| &ua.lbl));
+    keygen_partial_with(rng, gid, msk, &bases, user_attrs, iota)
+}
+
+// The parts of a partial key which depend on neither the GID nor the randomness
+struct Bases {
+    g: G,
+    lbls: HashMap<String, LabelHashes>,
+}
+
+fn bases<'a>(msk: &PartialMSK, lbls: impl IntoIterator<Item = &'a String>) -> Bases {
+    let mut hashes = HashMap::new();
+    for lbl in lbls {
+        hashes.insert(lbl.clone(), LabelHashes::new(&msk.auth, lbl));
+    }
+    Bases {
+        g: G::generator().mul(msk.beta),
+        lbls: hashes,
+    }
+}
+
+fn keygen_partial_with(
+    rng: impl Rng,
+    gid: &str,
+    msk: &PartialMSK,
+    bases: &Bases,
+    user_attrs: &[UserAttribute],
     _iota: &Iota,
 ) -> PartialUSK {
+    let gid_hashed = hash_gid(gid);
+    let gid_not = gid_hashed.mul(msk.b_not);
+    let attrs = keygen_attrs(rng, gid_hashed.mul(msk.b), msk, bases, user_attrs);
+    let mut k_2_map = HashMap::new();
+    for (lbl, r_lab) in attrs.r_lab_map {
+        let k_2 = bases.lbls[&lbl].neg_1.mul(r_lab);
+        k_2_map.insert(lbl, bases.g + gid_not + k_2);
+    }
+    PartialUSK {
+        auth: msk.auth.clone(),
+        epoch: msk.epoch,
+        k_1_map: attrs.k_1_map,
+        k_2_map,
+        k_3_map: attrs.k_3_map,
+        k_4_map: attrs.k_4_map,
+        k_5_map: attrs.k_5_map,
+    }
+}
+
+// The components of a partial key which belong to single attributes, together with the
+// sum of the r_not of the attributes of each label. Fresh and extended keys only differ
+// in how these sums enter k_2.
+struct AttrKeys {
+    k_1_map: HashMap<(String, String), G>,
+    k_3_map: HashMap<(String, String), G>,
+    k_4_map: HashMap<String, H>,
+    k_5_map: HashMap<String, H>,
+    r_lab_map: HashMap<String, ScalarField>,
+}
+
+fn keygen_attrs(
+    mut rng: impl Rng,
+    gid: G,
+    msk: &PartialMSK,
+    bases: &Bases,
+    user_attrs: &[UserAttribute],
+) -> AttrKeys {
     let mut r_map = HashMap::new();
     let mut r_not_map = HashMap::new();
     let mut r_lab_map = HashMap::new();
@@ -72,71 +178,203 @@ pub fn keygen_partial(
         if !r_lab_done.contains(&(user_attr.lbl.clone(), user_attr.attr.clone())) {
             let r_not = r_not_map.get(&user_attr.attr).unwrap();
             let r_lab = *r_lab_map.get(&user_attr.lbl).unwrap_or(&zero) + r_not;
-            r_lab_map.insert(&user_attr.lbl, r_lab);
+            r_lab_map.insert(user_attr.lbl.clone(), r_lab);
             r_lab_done.insert((user_attr.lbl.clone(), user_attr.attr.clone()));
         }
     }
-    let g = G::generator().mul(msk.beta);
-    let gid_hashed = hash_gid(gid);
-    let gid = gid_hashed.mul(msk.b);
-    let gid_not = gid_hashed.mul(msk.b_not);
+    let g = bases.g;
     let mut k_1_map = HashMap::new();
     let mut k_3_map = HashMap::new();
-    let mut lbl_pos_0 = HashMap::new();
-    let mut lbl_pos_1 = HashMap::new();
-    let mut lbl_neg_0 = HashMap::new();
-    let mut lbl_neg_1 = HashMap::new();
-    for user_attr in user_attrs.iter() {
-        let key = (msk.auth.clone(), user_attr.lbl.clone());
-        if !lbl_pos_0.contains_key(&key) {
-            lbl_pos_0.insert(key.clone(), hash_lbl(&msk.auth, &user_attr.lbl, POS, 0));
-            lbl_pos_1.insert(key.clone(), hash_lbl(&msk.auth, &user_attr.lbl, POS, 1));
-            lbl_neg_0.insert(key.clone(), hash_lbl(&msk.auth, &user_attr.lbl, NEG, 0));
-            lbl_neg_1.insert(key, hash_lbl(&msk.auth, &user_attr.lbl, NEG, 1));
-        }
-    }
     for user_attr in user_attrs.iter() {
-        let key = (msk.auth.clone(), user_attr.lbl.clone());
-        let lbl_pos_0 = lbl_pos_0.get(&key).unwrap().clone();
-        let lbl_pos_1 = lbl_pos_1.get(&key).unwrap().clone();
-        let lbl_neg_0 = lbl_neg_0.get(&key).unwrap().clone();
-        let lbl_neg_1 = lbl_neg_1.get(&key).unwrap().clone();
+        let lbls = &bases.lbls[&user_attr.lbl];
         let x_attr = hash_attr(&user_attr.attr);
         let r = *r_map.get(&user_attr.attr).unwrap();
         let r_not = *r_not_map.get(&user_attr.attr).unwrap();
-        let k_1 = G::msm(&[lbl_pos_0, lbl_pos_1], &[r, r * x_attr]).unwrap();
+        let k_1 = G::msm(&[lbls.pos_0, lbls.pos_1], &[r, r * x_attr]).unwrap();
         k_1_map.insert(
             (user_attr.lbl.clone(), user_attr.attr.clone()),
             g + gid + k_1,
         );
-        let k_3 = G::msm(&[lbl_neg_0, lbl_neg_1], &[r_not, r_not * x_attr]).unwrap();
+        let k_3 = G::msm(&[lbls.neg_0, lbls.neg_1], &[r_not, r_not * x_attr]).unwrap();
         k_3_map.insert((user_attr.lbl.clone(), user_attr.attr.clone()), k_3);
     }
-    let mut k_2_map = HashMap::new();
-    for user_attr in user_attrs.iter() {
-        if !k_2_map.contains_key(&user_attr.lbl) {
-            let r_lab = r_lab_map.get(&user_attr.lbl).unwrap();
-            let key = (msk.auth.clone(), user_attr.lbl.clone());
-            let k_2 = lbl_neg_1.get(&key).unwrap().clone().mul(r_lab);
-            k_2_map.insert(user_attr.lbl.clone(), g + gid_not + k_2);
-        }
-    }
     let mut k_4_map = HashMap::new();
     let mut k_5_map = HashMap::new();
-    for user_attr in user_attrs.iter() {
-        if !k_4_map.contains_key(&user_attr.attr) {
-            let r = r_map.get(&user_attr.attr).unwrap();
-            let r_not = r_not_map.get(&user_attr.attr).unwrap();
-            k_4_map.insert(user_attr.attr.clone(), H::generator().mul(r));
-            k_5_map.insert(user_attr.attr.clone(), H::generator().mul(r_not));
-        }
+    for (attr, r) in r_map {
+        let r_not = r_not_map.get(&attr).unwrap();
+        k_4_map.insert(attr.clone(), H::generator().mul(r));
+        k_5_map.insert(attr, H::generator().mul(r_not));
     }
-    PartialUSK {
-        auth: msk.auth.clone(),
+    AttrKeys {
         k_1_map,
-        k_2_map,
         k_3_map,
         k_4_map,
         k_5_map,
+        r_lab_map,
+    }
+}
+
+pub fn rerandomize(rng: impl Rng, usk: &mut USK, iota: &Iota) -> Result<(), String> {
+    let zero = ScalarField::from(0);
+    let keygen_zero = |rng: &mut _, usk: &PartialUSK| {
+        let msk = PartialMSK {
+            auth: usk.auth.clone(),
+            epoch: usk.epoch,
+            beta: zero,
+            b: zero,
+            b_not: zero,
+        };
+        let user_attrs: Vec<UserAttribute> = usk
+            .k_3_map
+            .keys()
+            .map(|(lbl, attr)| UserAttribute::new(&usk.auth, lbl, attr))
+            .collect();
+        // the GID does not matter under the zero master key
+        Ok(keygen_partial(rng, "", &msk, &user_attrs, iota))
+    };
+    rerandomize_usk(rng, usk, keygen_zero)
+}
+
+// Checks every component of a partial key with a pairing equation against the MPK of its
+// authority, e.g. e(k_3, h) = e(H(lbl, NEG, 0) H(lbl, NEG, 1)^x, k_5). The key has to hold
+// exactly the given attributes of the authority.
+pub fn verify_partial(
+    mpk: &PartialMPK,
+    gid: &str,
+    user_attrs: &[UserAttribute],
+    _iota: &Iota,
+    usk: &PartialUSK,
+) -> Result<(), String> {
+    check_authority(mpk, usk)?;
+    let issued = IssuedAttrs::new(&usk.auth, user_attrs);
+    check_keys(&usk.k_1_map, &issued.pairs, "k_1")?;
+    check_keys(&usk.k_2_map, &issued.lbls, "k_2")?;
+    check_keys(&usk.k_3_map, &issued.pairs, "k_3")?;
+    let attrs = issued.pairs.iter().map(|(_, attr)| attr.clone()).collect();
+    check_keys(&usk.k_4_map, &attrs, "k_4")?;
+    check_keys(&usk.k_5_map, &attrs, "k_5")?;
+    let g = G::generator();
+    let h = H::generator();
+    let gid_hashed = G::from(hash_gid(gid));
+    let one = ScalarField::from(1);
+    let mut lbls = HashMap::new();
+    for lbl in issued.lbls.iter() {
+        lbls.insert(lbl.clone(), LabelHashes::new(&usk.auth, lbl));
+    }
+    for pair in issued.pairs.iter() {
+        let (lbl, attr) = pair;
+        let hashes = &lbls[lbl];
+        let x_attr = hash_attr(attr);
+        let pos = G::msm(&[hashes.pos_0, hashes.pos_1], &[one, x_attr]).unwrap();
+        let neg = G::msm(&[hashes.neg_0, hashes.neg_1], &[one, x_attr]).unwrap();
+        let k_4 = usk.k_4_map[attr];
+        let k_5 = usk.k_5_map[attr];
+        let mut k_1 = PairingProduct::new();
+        k_1.push(-usk.k_1_map[pair], h);
+        k_1.push(g, mpk.a);
+        k_1.push(gid_hashed, mpk.b);
+        k_1.push(pos, k_4);
+        check_product(k_1, "k_1", pair)?;
+        let mut k_3 = PairingProduct::new();
+        k_3.push(-usk.k_3_map[pair], h);
+        k_3.push(neg, k_5);
+        check_product(k_3, "k_3", pair)?;
+    }
+    for lbl in issued.lbls.iter() {
+        let mut k_5 = H::zero();
+        for attr in issued.attrs_of(lbl) {
+            k_5 += usk.k_5_map[attr];
+        }
+        let mut k_2 = PairingProduct::new();
+        k_2.push(-usk.k_2_map[lbl], h);
+        k_2.push(g, mpk.a);
+        k_2.push(gid_hashed, mpk.b_not);
+        k_2.push(G::from(lbls[lbl].neg_1), k_5);
+        check_product(k_2, "k_2", lbl)?;
+    }
+    Ok(())
+}
+
+// Adds attributes to an existing key. Only the components for the new attributes are
+// generated, and the k_2 of a label which gains attributes is updated in place.
+pub fn keygen_extend(
+    mut rng: impl Rng,
+    gid: &str,
+    msk: &MSK,
+    usk: &mut USK,
+    user_attrs: &[UserAttribute],
+    iota: &mut Iota,
+) -> Result<(), String> {
+    let held = usk.get_user_attributes();
+    let user_attr_by_auth = extend_attrs(msk, usk, &held, user_attrs, iota, check_extend_partial)?;
+    for (auth, uas) in user_attr_by_auth {
+        let partial_msk = msk.get_partial_key(&auth).unwrap();
+        match usk.partial_keys.get_mut(&auth) {
+            None => {
+                let partial_usk = keygen_partial(&mut rng, gid, partial_msk, &uas, iota);
+                usk.add_partial_key(partial_usk);
+            }
+            Some(partial_usk) => {
+                keygen_extend_partial(&mut rng, gid, partial_msk, partial_usk, &uas, iota);
+            }
+        }
+    }
+    Ok(())
+}
+
+// The randomness of an attribute is shared by all labels in which it occurs, so an
+// attribute which the key already holds under another label cannot be added on its own.
+fn check_extend_partial(usk: &PartialUSK, user_attrs: &[UserAttribute]) -> Result<(), String> {
+    for user_attr in user_attrs.iter() {
+        if usk.k_4_map.contains_key(&user_attr.attr) {
+            return Err(format!(
+                "Attribute {:?} shares its randomness with an attribute of the key, which has to be reissued",
+                user_attr
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The new attributes are issued like those of a fresh key, and the k_2 of a label which
+// gains attributes is updated in place
+pub fn keygen_extend_partial(
+    rng: impl Rng,
+    gid: &str,
+    msk: &PartialMSK,
+    usk: &mut PartialUSK,
+    user_attrs: &[UserAttribute],
+    _iota: &Iota,
+) {
+    let bases = bases(msk, user_attrs.iter().map(|ua| &ua.lbl));
+    let gid_hashed = hash_gid(gid);
+    let gid_not = gid_hashed.mul(msk.b_not);
+    let attrs = keygen_attrs(rng, gid_hashed.mul(msk.b), msk, &bases, user_attrs);
+    usk.k_1_map.extend(attrs.k_1_map);
+    usk.k_3_map.extend(attrs.k_3_map);
+    usk.k_4_map.extend(attrs.k_4_map);
+    usk.k_5_map.extend(attrs.k_5_map);
+    for (lbl, r_lab) in attrs.r_lab_map {
+        let k_2 = bases.lbls[&lbl].neg_1.mul(r_lab);
+        match usk.k_2_map.get_mut(&lbl) {
+            Some(k) => *k += k_2,
+            None => {
+                usk.k_2_map.insert(lbl, bases.g + gid_not + k_2);
+            }
+        }
+    }
+}
+
+// Moves the partial key of the update key's authority to the next epoch
+pub fn update_usk(usk: &mut USK, uk: &UpdateKey) -> Result<(), String> {
+    check_update_key(usk.get_partial_key(&uk.auth), uk)?;
+    let usk = usk.partial_keys.get_mut(&uk.auth).unwrap();
+    for k_1 in usk.k_1_map.values_mut() {
+        *k_1 += uk.k;
+    }
+    for k_2 in usk.k_2_map.values_mut() {
+        *k_2 += uk.k_not;
     }
+    usk.epoch = uk.epoch;
+    Ok(())
 }
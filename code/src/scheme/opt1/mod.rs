@@ -4,6 +4,7 @@ mod keygen;
 mod setup;
 
 use super::common::Scheme;
+use super::common::{CostContext, DecryptionCost, ReconstructionCost};
 use crate::curve::Gt;
 
 pub struct Opt1 {
@@ -25,6 +26,8 @@ impl Scheme for Opt1 {
 
     type Ciphertext = super::common::Ciphertext;
 
+    type Powers = super::common::Powers;
+
     fn new() -> Self {
         Opt1 {
             name: String::from("opt1"),
@@ -39,6 +42,14 @@ impl Scheme for Opt1 {
         setup::setup(rng, auths)
     }
 
+    fn setup_authority(
+        &self,
+        rng: impl rand::Rng,
+        auth: &str,
+    ) -> (Self::PartialMSK, Self::PartialMPK) {
+        setup::setup_partial(rng, auth)
+    }
+
     fn keygen(
         &self,
         rng: impl rand::Rng,
@@ -50,6 +61,125 @@ impl Scheme for Opt1 {
         keygen::keygen(rng, gid, msk, user_attrs, iota)
     }
 
+    fn keygen_authority(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+    ) -> Self::PartialUSK {
+        keygen::keygen_partial(rng, gid, msk, user_attrs, iota)
+    }
+
+    fn authority_secrets(&self, msk: &Self::PartialMSK) -> Vec<crate::curve::ScalarField> {
+        setup::secrets(msk)
+    }
+
+    fn setup_authority_from(
+        &self,
+        auth: &str,
+        secrets: &[crate::curve::ScalarField],
+    ) -> Result<(Self::PartialMSK, Self::PartialMPK), String> {
+        if secrets.len() != setup::NUM_SECRETS {
+            return Err(format!(
+                "A partial MSK consists of {} secrets, but {} were given",
+                setup::NUM_SECRETS,
+                secrets.len()
+            ));
+        }
+        Ok(setup::setup_from_secrets(auth, secrets))
+    }
+
+    fn combine_partial_mpks(
+        &self,
+        mpks: &[(crate::curve::ScalarField, &Self::PartialMPK)],
+    ) -> Self::PartialMPK {
+        setup::combine_partial_mpks(mpks)
+    }
+
+    fn keygen_share(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        _mpk: &Self::PartialMPK,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+    ) -> Self::PartialUSK {
+        // all secrets of the MSK are shared, so the MPK is not needed
+        keygen::keygen_partial(rng, gid, msk, user_attrs, iota)
+    }
+
+    fn verify_partial_usk(
+        &self,
+        mpk: &Self::PartialMPK,
+        gid: &str,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+        usk: &Self::PartialUSK,
+    ) -> Result<(), String> {
+        keygen::verify_partial(mpk, gid, user_attrs, iota, usk)
+    }
+
+    fn keygen_batch(
+        &self,
+        rng: impl rand::Rng,
+        msk: &Self::MSK,
+        users: &[(&str, &Vec<crate::policy::UserAttribute>, &super::Iota)],
+    ) -> Result<Vec<Self::USK>, String> {
+        keygen::keygen_batch(rng, msk, users)
+    }
+
+    fn keygen_extend(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::MSK,
+        usk: &mut Self::USK,
+        user_attrs: &Vec<crate::policy::UserAttribute>,
+        iota: &mut super::Iota,
+    ) -> Result<(), String> {
+        keygen::keygen_extend(rng, gid, msk, usk, user_attrs, iota)
+    }
+
+    fn rotate_epoch(
+        &self,
+        rng: impl rand::Rng,
+        auth: &str,
+        msk: &mut Self::MSK,
+        mpk: &mut Self::MPK,
+    ) -> Result<super::common::EpochUpdate, String> {
+        setup::rotate_epoch(rng, auth, msk, mpk)
+    }
+
+    fn update_usk(
+        &self,
+        usk: &mut Self::USK,
+        uk: &super::common::UpdateKey,
+    ) -> Result<(), String> {
+        keygen::update_usk(usk, uk)
+    }
+
+    fn blind_usk(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        z: crate::curve::ScalarField,
+    ) -> super::common::TransformationKey<Self::USK> {
+        super::common::blind_usk(usk, gid, z)
+    }
+
+    fn rerandomize_usk(
+        &self,
+        rng: impl rand::Rng,
+        _mpk: &Self::MPK,
+        usk: &mut Self::USK,
+        iota: &super::Iota,
+    ) -> Result<(), String> {
+        keygen::rerandomize(rng, usk, iota)
+    }
+
     fn encrypt(
         &self,
         rng: impl rand::Rng,
@@ -69,6 +199,115 @@ impl Scheme for Opt1 {
         policy: &crate::policy::Policy,
         ct: &Self::Ciphertext,
     ) -> Option<Gt> {
-        decrypt::decrypt(usk, gid, iota, tau, policy, ct)
+        super::common::decrypt(self, usk, gid, iota, tau, policy, ct)
+    }
+
+    fn encrypt_lsss(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        lsss: &crate::policy::LsssMatrix,
+        tau: &super::Tau,
+    ) -> (Gt, Self::Ciphertext) {
+        encrypt::encrypt_lsss(rng, mpk, lsss, tau)
+    }
+
+    fn decrypt_lsss(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &super::Iota,
+        tau: &super::Tau,
+        lsss: &crate::policy::LsssMatrix,
+        ct: &Self::Ciphertext,
+    ) -> Option<Gt> {
+        super::common::decrypt_lsss::<Self>(usk, gid, iota, tau, lsss, ct)
+    }
+
+    fn decrypt_many(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &super::Iota,
+        cts: &[(&crate::policy::Policy, &super::Tau, &Self::Ciphertext)],
+    ) -> Vec<Option<Gt>> {
+        super::common::decrypt_many(self, usk, gid, iota, cts)
+    }
+
+    fn transform(
+        &self,
+        tk: &super::common::TransformationKey<Self::USK>,
+        iota: &super::Iota,
+        tau: &super::Tau,
+        policy: &crate::policy::Policy,
+        ct: &Self::Ciphertext,
+    ) -> Option<super::common::TransformedCiphertext> {
+        super::common::transform(self, tk, iota, tau, policy, ct)
+    }
+
+    fn rerandomize_ciphertext(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        policy: &crate::policy::Policy,
+        tau: &super::Tau,
+        ct: &mut Self::Ciphertext,
+        mode: super::common::RerandomizeMode,
+    ) -> Result<Gt, String> {
+        encrypt::rerandomize(rng, mpk, policy, tau, ct, mode)
+    }
+
+    fn fill_pool(
+        &self,
+        rng: impl rand::Rng,
+        pool: &mut super::common::EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        num_cts: usize,
+        max_rows: usize,
+    ) {
+        pool.fill(rng, mpk, num_cts, max_rows)
+    }
+
+    fn encrypt_online(
+        &self,
+        pool: &mut super::common::EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        policy: &crate::policy::Policy,
+        tau: &super::Tau,
+    ) -> Result<(Gt, Self::Ciphertext), String> {
+        encrypt::encrypt_online(pool, mpk, policy, tau)
+    }
+
+    fn ciphertext_size(&self, policy: &crate::policy::Policy, _tau: &super::Tau) -> usize {
+        let g = super::common::g_size();
+        let h = super::common::h_size();
+        policy.len() * (3 * h + g)
+    }
+
+    fn update_policy(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        policy: &crate::policy::Policy,
+        ct: &Self::Ciphertext,
+        extra: &crate::policy::Policy,
+    ) -> Result<(Self::Ciphertext, crate::policy::Policy), String> {
+        super::common::tighten_policy(rng, mpk, policy, ct, extra)
+    }
+}
+
+impl ReconstructionCost for Opt1 {
+    fn cost(&self, ctx: &CostContext, rows: &[usize]) -> DecryptionCost {
+        let (pos, neg) = ctx.split(rows);
+        let by_auth_lbl_attr = |j| ctx.policy.get(j).0.auth_lbl_attr();
+        let others = ctx.others_per_attr(&neg);
+        DecryptionCost {
+            pairings: 2
+                + ctx.groups(&pos, by_auth_lbl_attr)
+                + ctx.groups(&pos, |j| ctx.policy.get(j).0.auth_attr())
+                + 2 * ctx.groups(&neg, by_auth_lbl_attr),
+            g_msm_bases: others,
+            h_msm_bases: others,
+        }
     }
 }
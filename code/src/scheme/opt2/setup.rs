@@ -1,10 +1,12 @@
 use crate::curve::{ScalarField, H};
+use crate::scheme::common::EpochUpdate;
 use crate::scheme::Scheme;
 
 use ark_ec::Group;
 use ark_ff::UniformRand;
 use ark_std::ops::Mul;
 use ark_std::rand::Rng;
+use ark_std::Zero;
 
 type MSK = <super::Opt2 as Scheme>::MSK;
 type PartialMSK = <super::Opt2 as Scheme>::PartialMSK;
@@ -26,8 +28,24 @@ pub fn setup_partial(mut rng: impl Rng, auth: &str) -> (PartialMSK, PartialMPK)
     let beta = ScalarField::rand(&mut rng);
     let b = ScalarField::rand(&mut rng);
     let b_not = ScalarField::rand(&mut rng);
+    setup_from_secrets(auth, &[beta, b, b_not])
+}
+
+pub const NUM_SECRETS: usize = 3;
+
+// The secret scalars of a partial MSK, in the order in which `setup_from_secrets` takes
+// them
+pub fn secrets(msk: &PartialMSK) -> Vec<ScalarField> {
+    vec![msk.beta, msk.b, msk.b_not]
+}
+
+pub fn setup_from_secrets(auth: &str, secrets: &[ScalarField]) -> (PartialMSK, PartialMPK) {
+    let &[beta, b, b_not] = secrets else {
+        panic!("Fatal error: a partial MSK consists of {} secrets", NUM_SECRETS);
+    };
     let msk = PartialMSK {
         auth: String::from(auth),
+        epoch: 0,
         beta,
         b,
         b_not,
@@ -38,9 +56,59 @@ pub fn setup_partial(mut rng: impl Rng, auth: &str) -> (PartialMSK, PartialMPK)
     let b_not = H::generator().mul(b_not);
     let mpk = PartialMPK {
         auth: String::from(auth),
+        epoch: 0,
         a,
         b,
         b_not,
     };
     return (msk, mpk);
 }
+
+// Combines public keys of the same authority linearly in the exponent, which gives the
+// public key of the same linear combination of their secrets
+pub fn combine_partial_mpks(mpks: &[(ScalarField, &PartialMPK)]) -> PartialMPK {
+    let Some((_, first)) = mpks.first() else {
+        panic!("Fatal error: cannot combine an empty list of public keys");
+    };
+    let mut mpk = PartialMPK {
+        auth: first.auth.clone(),
+        epoch: first.epoch,
+        a: H::zero(),
+        b: H::zero(),
+        b_not: H::zero(),
+    };
+    for (c, partial_mpk) in mpks {
+        mpk.a += partial_mpk.a.mul(c);
+        mpk.b += partial_mpk.b.mul(c);
+        mpk.b_not += partial_mpk.b_not.mul(c);
+    }
+    mpk
+}
+
+// Moves the authority to its next epoch, see `EpochUpdate`
+pub fn rotate_epoch(
+    mut rng: impl Rng,
+    auth: &str,
+    msk: &mut MSK,
+    mpk: &mut MPK,
+) -> Result<EpochUpdate, String> {
+    let (Some(msk), Some(mpk)) = (
+        msk.partial_keys.get_mut(auth),
+        mpk.partial_keys.get_mut(auth),
+    ) else {
+        return Err(format!(
+            "No partial MSK and MPK given for authority '{}'",
+            auth
+        ));
+    };
+    let update = EpochUpdate::new(&mut rng, auth, msk.epoch + 1);
+    msk.epoch = update.epoch;
+    msk.beta += update.beta;
+    msk.b += update.b;
+    msk.b_not += update.b_not;
+    mpk.epoch = update.epoch;
+    mpk.a = H::generator().mul(msk.beta);
+    mpk.b = H::generator().mul(msk.b);
+    mpk.b_not = H::generator().mul(msk.b_not);
+    Ok(update)
+}
@@ -10,57 +10,121 @@ use ark_std::rand::Rng;
 
 type Ciphertext = <super::Opt4 as Scheme>::Ciphertext;
 type MPK = <super::Opt4 as Scheme>::MPK;
+use super::Powers;
 use crate::hashing::{
     hash_attr, hash_lbl,
     HashSign::{NEG, POS},
 };
-use crate::policy::Policy;
-use crate::scheme::common::Tau;
+use crate::policy::{LsssMatrix, Policy};
+use crate::scheme::common::{
+    add_ciphertexts, check_pool_epochs, policy_epochs, rerandomize_ciphertext, EncryptionPool,
+    RerandomizeMode, Tau,
+};
 
 fn share_secret(
     mut rng: impl Rng,
     secret: ScalarField,
-    policy: &Policy,
+    lsss: &LsssMatrix,
 ) -> (Vec<ScalarField>, Vec<ScalarField>, usize) {
+    let lambda_vec = lsss.share(&mut rng, secret);
+    let mu_vec = lsss.share(&mut rng, ScalarField::from(0));
+    (lambda_vec, mu_vec, lsss.num_rows())
+}
+
+pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, tau: &Tau) -> (Gt, Ciphertext) {
+    let s = ScalarField::rand(&mut rng);
+    encrypt_secret(rng, mpk, policy, &policy.lsss(), tau, s)
+}
+
+pub fn encrypt_lsss(
+    mut rng: impl Rng,
+    mpk: &MPK,
+    lsss: &LsssMatrix,
+    tau: &Tau,
+) -> (Gt, Ciphertext) {
+    let s = ScalarField::rand(&mut rng);
+    encrypt_secret(rng, mpk, &lsss.labels(), lsss, tau, s)
+}
+
+// The online phase of encryption, see `EncryptionPool`
+pub fn encrypt_online(
+    pool: &mut EncryptionPool<Powers>,
+    mpk: &MPK,
+    policy: &Policy,
+    tau: &Tau,
+) -> Result<(Gt, Ciphertext), String> {
+    let epochs = check_pool_epochs(pool, mpk, policy)?;
     let n = policy.len();
-    let splits = policy.share_secret();
-    let mut v_vec = Vec::with_capacity(n);
-    let mut v_prime_vec = Vec::with_capacity(n);
-    let mut lambda_vec = Vec::with_capacity(n);
-    let mut mu_vec = Vec::with_capacity(n);
-    let zero = ScalarField::from(0);
-    for _ in 0..n {
-        v_vec.push(ScalarField::rand(&mut rng));
-        v_prime_vec.push(ScalarField::rand(&mut rng));
-        lambda_vec.push(zero);
-        mu_vec.push(zero);
-    }
-    v_vec[0] = secret;
-    v_prime_vec[0] = zero;
-    for (i, (_, idcs)) in splits.iter().enumerate() {
-        for j in idcs {
-            if *j >= 0 {
-                let k = *j as usize;
-                lambda_vec[i] += v_vec[k];
-                mu_vec[i] += v_prime_vec[k];
-            } else {
-                let k = (0 - *j) as usize;
-                lambda_vec[i] -= v_vec[k];
-                mu_vec[i] -= v_prime_vec[k];
-            }
+    let m = std::cmp::max(tau.get_max(), tau.get_tilde_max());
+    let draw = pool.draw(&policy.lsss(), m + 1)?;
+    let mut c_1_vec = Vec::with_capacity(n);
+    let mut c_2_vec = Vec::with_capacity(n);
+    let mut c_3_vec = Vec::with_capacity(n);
+    for j in 0..n {
+        let (user_attr, is_neg) = policy.get(j);
+        let auth = user_attr.auth;
+        let lbl = user_attr.lbl;
+        let x_attr = hash_attr(&user_attr.attr);
+        let s_tilde = draw.entries[tau.get_tilde(j)].s;
+        let s = draw.entries[tau.get(j)].s;
+        let powers = draw.entries[tau.get_tilde(j)].auths.get(&auth).unwrap();
+        let sign = if is_neg { NEG } else { POS };
+        let lbl_0 = pool.hash_lbl(&auth, &lbl, sign, 0);
+        let lbl_1 = pool.hash_lbl(&auth, &lbl, sign, 1);
+        if is_neg {
+            c_1_vec.push(draw.mu_vec[j] + powers.b_not);
+            let c_2 = G::msm(&[lbl_0, lbl_1], &[s_tilde, s_tilde * x_attr]).unwrap();
+            c_2_vec.push(c_2);
+        } else {
+            c_1_vec.push(draw.mu_vec[j] + powers.b);
+            let c_2 = G::msm(&[lbl_0, lbl_1], &[s, s * x_attr]).unwrap();
+            c_2_vec.push(powers.b_prime + c_2);
         }
+        c_3_vec.push(draw.lambda_vec[j] + powers.a);
     }
-    (lambda_vec, mu_vec, n)
+    let c_4_vec = draw.entries.iter().map(|entry| entry.h).collect();
+    let ct = Ciphertext {
+        c_1_vec,
+        c_2_vec,
+        c_3_vec,
+        c_4_vec,
+        epochs,
+    };
+    Ok((draw.k, ct))
 }
 
-pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, tau: &Tau) -> (Gt, Ciphertext) {
-    let s = ScalarField::rand(&mut rng);
+pub fn rerandomize(
+    rng: impl Rng,
+    mpk: &MPK,
+    policy: &Policy,
+    tau: &Tau,
+    ct: &mut Ciphertext,
+    mode: RerandomizeMode,
+) -> Result<Gt, String> {
+    rerandomize_ciphertext(
+        rng,
+        policy,
+        ct,
+        mode,
+        |rng, s| encrypt_secret(rng, mpk, policy, &policy.lsss(), tau, s),
+        add_ciphertexts,
+    )
+}
+
+fn encrypt_secret(
+    mut rng: impl Rng,
+    mpk: &MPK,
+    policy: &Policy,
+    lsss: &LsssMatrix,
+    tau: &Tau,
+    s: ScalarField,
+) -> (Gt, Ciphertext) {
     let m = std::cmp::max(tau.get_max(), tau.get_tilde_max());
     let mut s_vec = Vec::with_capacity(m + 1);
     for _ in 0..=m {
         s_vec.push(ScalarField::rand(&mut rng));
     }
-    let (lambda_vec, mu_vec, n) = share_secret(&mut rng, s, &policy);
+    let (lambda_vec, mu_vec, n) = share_secret(&mut rng, s, lsss);
     let mut lbl_pos_0 = HashMap::new();
     let mut lbl_pos_1 = HashMap::new();
     let mut lbl_neg_0 = HashMap::new();
@@ -88,8 +152,8 @@ pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, tau: &Tau) -> (Gt,
         let lbl = user_attr.lbl;
         let mu = mu_vec[j];
         let lambda = lambda_vec[j];
-        let s_tilde = s_vec[tau.get_tilde(&auth, &lbl, &attr)];
-        let s = s_vec[tau.get(&auth, &lbl, &attr)];
+        let s_tilde = s_vec[tau.get_tilde(j)];
+        let s = s_vec[tau.get(j)];
         let x_attr = hash_attr(&attr);
         let mpk = mpk.get_partial_key(&auth).unwrap();
         let b = if is_neg { mpk.b_not } else { mpk.b };
@@ -129,6 +193,7 @@ pub fn encrypt(mut rng: impl Rng, mpk: &MPK, policy: &Policy, tau: &Tau) -> (Gt,
         c_2_vec,
         c_3_vec,
         c_4_vec,
+        epochs: policy_epochs(mpk, policy),
     };
     return (k, ct);
 }
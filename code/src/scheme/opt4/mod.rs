@@ -10,7 +10,8 @@ mod keygen;
 mod setup;
 
 use super::common::Scheme;
-use super::common::{FullKey, PartialKey};
+use super::common::{CostContext, DecryptionCost, ReconstructionCost};
+use super::common::{write_point, FullKey, PartialKey, Precompute, PublicPoints};
 
 pub struct Opt4 {
     pub name: String,
@@ -18,6 +19,7 @@ pub struct Opt4 {
 
 pub struct PartialMSK {
     pub auth: String,
+    pub epoch: u64,
     pub beta: ScalarField,
     pub b: ScalarField,
     pub b_not: ScalarField,
@@ -28,12 +30,18 @@ impl PartialKey for PartialMSK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
 pub type MSK = FullKey<PartialMSK>;
 
+#[derive(Clone, PartialEq)]
 pub struct PartialMPK {
     pub auth: String,
+    pub epoch: u64,
     pub a: H,
     pub b: H,
     pub b_not: H,
@@ -44,12 +52,46 @@ impl PartialKey for PartialMPK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
 pub type MPK = FullKey<PartialMPK>;
 
+pub struct Powers {
+    pub a: H,
+    pub b: H,
+    pub b_not: H,
+    pub b_prime: G,
+}
+
+impl PublicPoints for PartialMPK {
+    fn write_points(&self, transcript: &mut Vec<u8>) {
+        write_point(transcript, &self.a);
+        write_point(transcript, &self.b);
+        write_point(transcript, &self.b_not);
+        write_point(transcript, &self.b_prime);
+    }
+}
+
+impl Precompute for PartialMPK {
+    type Powers = Powers;
+
+    fn powers(&self, s: ScalarField) -> Powers {
+        Powers {
+            a: self.a * s,
+            b: self.b * s,
+            b_not: self.b_not * s,
+            b_prime: self.b_prime * s,
+        }
+    }
+}
+
 pub struct PartialUSK {
     pub auth: String,
+    pub epoch: u64,
     pub k_1_1_vec: Vec<G>,
     pub k_1_2_map: HashMap<(String, String), G>,
     pub k_2_map: HashMap<String, G>,
@@ -62,6 +104,10 @@ impl PartialKey for PartialUSK {
     fn get_auth(&self) -> String {
         self.auth.clone()
     }
+
+    fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
 }
 
 pub type USK = FullKey<PartialUSK>;
@@ -93,6 +139,8 @@ impl Scheme for Opt4 {
 
     type Ciphertext = super::common::Ciphertext;
 
+    type Powers = Powers;
+
     fn new() -> Self {
         Opt4 {
             name: String::from("opt4"),
@@ -107,6 +155,14 @@ impl Scheme for Opt4 {
         setup::setup(rng, auths)
     }
 
+    fn setup_authority(
+        &self,
+        rng: impl rand::Rng,
+        auth: &str,
+    ) -> (Self::PartialMSK, Self::PartialMPK) {
+        setup::setup_partial(rng, auth)
+    }
+
     fn keygen(
         &self,
         rng: impl rand::Rng,
@@ -118,6 +174,132 @@ impl Scheme for Opt4 {
         keygen::keygen(rng, gid, msk, user_attrs, iota)
     }
 
+    fn keygen_authority(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+    ) -> Self::PartialUSK {
+        keygen::keygen_partial(rng, gid, msk, user_attrs, iota)
+    }
+
+    fn authority_secrets(&self, msk: &Self::PartialMSK) -> Vec<crate::curve::ScalarField> {
+        setup::secrets(msk)
+    }
+
+    fn setup_authority_from(
+        &self,
+        auth: &str,
+        secrets: &[crate::curve::ScalarField],
+    ) -> Result<(Self::PartialMSK, Self::PartialMPK), String> {
+        if secrets.len() != setup::NUM_SECRETS {
+            return Err(format!(
+                "A partial MSK consists of {} secrets, but {} were given",
+                setup::NUM_SECRETS,
+                secrets.len()
+            ));
+        }
+        Ok(setup::setup_from_secrets(auth, secrets))
+    }
+
+    fn combine_partial_mpks(
+        &self,
+        mpks: &[(crate::curve::ScalarField, &Self::PartialMPK)],
+    ) -> Self::PartialMPK {
+        setup::combine_partial_mpks(mpks)
+    }
+
+    fn keygen_share(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::PartialMSK,
+        mpk: &Self::PartialMPK,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+    ) -> Self::PartialUSK {
+        keygen::keygen_share(rng, gid, msk, mpk, user_attrs, iota)
+    }
+
+    fn share_public_key(
+        &self,
+        share_mpk: &Self::PartialMPK,
+        mpk: &Self::PartialMPK,
+    ) -> Self::PartialMPK {
+        keygen::share_public_key(share_mpk, mpk)
+    }
+
+    fn verify_partial_usk(
+        &self,
+        mpk: &Self::PartialMPK,
+        gid: &str,
+        user_attrs: &[crate::policy::UserAttribute],
+        iota: &super::Iota,
+        usk: &Self::PartialUSK,
+    ) -> Result<(), String> {
+        keygen::verify_partial(mpk, gid, user_attrs, iota, usk)
+    }
+
+    fn keygen_batch(
+        &self,
+        rng: impl rand::Rng,
+        msk: &Self::MSK,
+        users: &[(&str, &Vec<crate::policy::UserAttribute>, &super::Iota)],
+    ) -> Result<Vec<Self::USK>, String> {
+        keygen::keygen_batch(rng, msk, users)
+    }
+
+    fn keygen_extend(
+        &self,
+        rng: impl rand::Rng,
+        gid: &str,
+        msk: &Self::MSK,
+        usk: &mut Self::USK,
+        user_attrs: &Vec<crate::policy::UserAttribute>,
+        iota: &mut super::Iota,
+    ) -> Result<(), String> {
+        keygen::keygen_extend(rng, gid, msk, usk, user_attrs, iota)
+    }
+
+    fn rotate_epoch(
+        &self,
+        rng: impl rand::Rng,
+        auth: &str,
+        msk: &mut Self::MSK,
+        mpk: &mut Self::MPK,
+    ) -> Result<super::common::EpochUpdate, String> {
+        setup::rotate_epoch(rng, auth, msk, mpk)
+    }
+
+    fn update_usk(
+        &self,
+        usk: &mut Self::USK,
+        uk: &super::common::UpdateKey,
+    ) -> Result<(), String> {
+        keygen::update_usk(usk, uk)
+    }
+
+    fn blind_usk(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        z: crate::curve::ScalarField,
+    ) -> super::common::TransformationKey<Self::USK> {
+        super::common::blind_usk(usk, gid, z)
+    }
+
+    fn rerandomize_usk(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        usk: &mut Self::USK,
+        iota: &super::Iota,
+    ) -> Result<(), String> {
+        keygen::rerandomize(rng, mpk, usk, iota)
+    }
+
     fn encrypt(
         &self,
         rng: impl rand::Rng,
@@ -137,6 +319,107 @@ impl Scheme for Opt4 {
         policy: &crate::policy::Policy,
         ct: &Self::Ciphertext,
     ) -> Option<Gt> {
-        decrypt::decrypt(usk, gid, iota, tau, policy, ct)
+        super::common::decrypt(self, usk, gid, iota, tau, policy, ct)
+    }
+
+    fn encrypt_lsss(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        lsss: &crate::policy::LsssMatrix,
+        tau: &super::Tau,
+    ) -> (Gt, Self::Ciphertext) {
+        encrypt::encrypt_lsss(rng, mpk, lsss, tau)
+    }
+
+    fn decrypt_lsss(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &super::Iota,
+        tau: &super::Tau,
+        lsss: &crate::policy::LsssMatrix,
+        ct: &Self::Ciphertext,
+    ) -> Option<Gt> {
+        super::common::decrypt_lsss::<Self>(usk, gid, iota, tau, lsss, ct)
+    }
+
+    fn decrypt_many(
+        &self,
+        usk: &Self::USK,
+        gid: &str,
+        iota: &super::Iota,
+        cts: &[(&crate::policy::Policy, &super::Tau, &Self::Ciphertext)],
+    ) -> Vec<Option<Gt>> {
+        super::common::decrypt_many(self, usk, gid, iota, cts)
+    }
+
+    fn transform(
+        &self,
+        tk: &super::common::TransformationKey<Self::USK>,
+        iota: &super::Iota,
+        tau: &super::Tau,
+        policy: &crate::policy::Policy,
+        ct: &Self::Ciphertext,
+    ) -> Option<super::common::TransformedCiphertext> {
+        super::common::transform(self, tk, iota, tau, policy, ct)
+    }
+
+    fn rerandomize_ciphertext(
+        &self,
+        rng: impl rand::Rng,
+        mpk: &Self::MPK,
+        policy: &crate::policy::Policy,
+        tau: &super::Tau,
+        ct: &mut Self::Ciphertext,
+        mode: super::common::RerandomizeMode,
+    ) -> Result<Gt, String> {
+        encrypt::rerandomize(rng, mpk, policy, tau, ct, mode)
+    }
+
+    fn fill_pool(
+        &self,
+        rng: impl rand::Rng,
+        pool: &mut super::common::EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        num_cts: usize,
+        max_rows: usize,
+    ) {
+        pool.fill(rng, mpk, num_cts, max_rows)
+    }
+
+    fn encrypt_online(
+        &self,
+        pool: &mut super::common::EncryptionPool<Self::Powers>,
+        mpk: &Self::MPK,
+        policy: &crate::policy::Policy,
+        tau: &super::Tau,
+    ) -> Result<(Gt, Self::Ciphertext), String> {
+        encrypt::encrypt_online(pool, mpk, policy, tau)
+    }
+
+    fn ciphertext_size(&self, policy: &crate::policy::Policy, tau: &super::Tau) -> usize {
+        let g = super::common::g_size();
+        let h = super::common::h_size();
+        policy.len() * (2 * h + g) + (std::cmp::max(tau.get_max(), tau.get_tilde_max()) + 1) * h
+    }
+}
+
+impl ReconstructionCost for Opt4 {
+    fn cost(&self, ctx: &CostContext, rows: &[usize]) -> DecryptionCost {
+        let (pos, neg) = ctx.split(rows);
+        let by_auth_iota = ctx.groups(&pos, |j| ctx.auth_iota(j));
+        // decryption picks the cheaper way of handling k_1_1 and k_1_2
+        let cost_a_pos = by_auth_iota + ctx.groups(&pos, |j| ctx.tau.get(j));
+        let cost_b_pos = ctx.tau_union(&pos);
+        DecryptionCost {
+            pairings: 2
+                + std::cmp::min(cost_a_pos, cost_b_pos)
+                + by_auth_iota
+                + ctx.groups(&neg, |j| ctx.tau.get_tilde(j))
+                + ctx.groups(&neg, |j| ctx.policy.get(j).0.auth_lbl_attr()),
+            g_msm_bases: neg.iter().map(|j| ctx.others(*j)).sum(),
+            h_msm_bases: ctx.others_per_attr(&neg),
+        }
     }
 }
@@ -1,61 +1,71 @@
 use std::collections::HashSet;
 
-use crate::curve::{pairing, Gt, ScalarField, G, H};
+use crate::curve::{Gt, G, H};
 use crate::scheme::common::group_pairs;
 use crate::scheme::Scheme;
 
-use ark_ec::{CurveGroup, Group, VariableBaseMSM};
-use ark_ff::Field;
 use ark_std::{ops::Neg, Zero};
 
 type Ciphertext = <super::Opt5 as Scheme>::Ciphertext;
 type USK = <super::Opt5 as Scheme>::USK;
-use crate::hashing::{hash_attr, hash_gid};
-use crate::policy::Policy;
-use crate::scheme::common::{Iota, Tau};
+use crate::policy::{Policy, UserAttribute};
+use crate::scheme::common::{
+    epochs_match, Iota, KeyCache, PairingProduct, SolvedDecryption, Tau, WeightedRows,
+};
+
+impl SolvedDecryption for super::Opt5 {
+    fn user_attributes(usk: &USK) -> Vec<UserAttribute> {
+        usk.get_user_attributes()
+    }
 
-fn solve_lse(usk: &USK, policy: &Policy) -> Option<(Vec<usize>, Vec<usize>)> {
-    let user_attrs = usk.get_user_attributes();
-    let eps_all = policy.reconstruct_secret(&user_attrs)?;
-    let (eps_not_vec, eps_vec) = eps_all.into_iter().partition(|i| policy.get(*i).1);
-    Some((eps_vec, eps_not_vec))
+    fn decrypt_solved(
+        usk: &USK,
+        iota: &Iota,
+        tau: &Tau,
+        policy: &Policy,
+        ct: &Ciphertext,
+        rows: WeightedRows,
+        cache: &mut KeyCache,
+    ) -> Option<Gt> {
+        decrypt_solved(usk, iota, tau, policy, ct, rows, cache)
+    }
 }
 
-pub fn decrypt(
+fn decrypt_solved(
     usk: &USK,
-    gid: &str,
     iota: &Iota,
     tau: &Tau,
     policy: &Policy,
     ct: &Ciphertext,
+    ((eps_vec, eps_not_vec), weights): WeightedRows,
+    cache: &mut KeyCache,
 ) -> Option<Gt> {
-    let (eps_vec, eps_not_vec) = solve_lse(&usk, &policy)?;
-    let mut k = Gt::ONE;
+    if !epochs_match(
+        usk,
+        &ct.epochs,
+        policy,
+        eps_vec.iter().chain(eps_not_vec.iter()),
+    ) {
+        return None;
+    }
+    let (g, gid) = cache.bases;
+    let mut k = PairingProduct::new();
     let mut c_1 = H::zero();
     let mut c_3 = H::zero();
     for j in eps_vec.iter().chain(eps_not_vec.iter()) {
-        c_1 += ct.c_1_vec[*j];
-        c_3 += ct.c_3_vec[*j];
+        c_1 += weights.scale(*j, ct.c_1_vec[*j]);
+        c_3 += weights.scale(*j, ct.c_3_vec[*j]);
     }
-    k *= pairing(G::generator(), c_3).0;
-    k *= pairing(hash_gid(gid), c_1).0;
+    k.push(g, c_3);
+    k.push(gid, c_1);
 
     let eps_by_auth_iota = group_pairs(&eps_vec, |j| {
         let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
         (auth.clone(), iota.get(&auth, &lbl, &attr))
     });
-    let eps_by_tau_tilde = group_pairs(&eps_vec, |j| {
-        let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
-        tau.get_tilde(&auth, &lbl, &attr)
-    });
-    let eps_not_by_tau_tilde = group_pairs(&eps_not_vec, |j| {
-        let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
-        tau.get_tilde(&auth, &lbl, &attr)
-    });
-    let eps_not_by_tau = group_pairs(&eps_not_vec, |j| {
-        let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
-        tau.get(&auth, &lbl, &attr)
-    });
+    let eps_by_tau_tilde = group_pairs(&eps_vec, |j| tau.get_tilde(j));
+    let eps_not_by_tau_tilde = group_pairs(&eps_not_vec, |j| tau.get_tilde(j));
+    let eps_not_by_tau = group_pairs(&eps_not_vec, |j| tau.get(j));
     let eps_not_by_auth_lbl_attr = group_pairs(&eps_not_vec, |j| policy.get(j).0.auth_lbl_attr());
 
     let eps_not_by_auth = group_pairs(&eps_not_vec, |j| policy.get(j).0.auth);
@@ -77,20 +87,20 @@ pub fn decrypt(
         for j in js {
             let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
             let usk = usk.get_partial_key(&auth).unwrap();
-            k_1 += usk.k_1_map.get(&(lbl, attr)).unwrap().neg();
+            k_1 += weights.scale(j, usk.k_1_map.get(&(lbl, attr)).unwrap().neg());
         }
-        k *= pairing(k_1, c_4).0;
+        k.push(k_1, c_4);
     }
 
     for ((auth, iota), js) in eps_by_auth_iota {
         let mut c_2 = G::zero();
         for j in js {
-            c_2 += ct.c_2_vec[j];
+            c_2 += weights.scale(j, ct.c_2_vec[j]);
         }
         let usk = usk.get_partial_key(&auth).unwrap();
         let k_4: ark_ec::short_weierstrass::Projective<ark_bls12_381::g2::Config> =
             usk.k_4_vec[iota];
-        k *= pairing(c_2, k_4).0;
+        k.push(c_2, k_4);
     }
 
     // Statically fix one branch for testing
@@ -111,11 +121,9 @@ pub fn decrypt(
             let k_2_1 = usk.get_partial_key(&auth).unwrap().k_2_1.neg();
             let mut c_4 = H::zero();
             for j in js {
-                let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
-                let s_tilde = tau.get_tilde(&auth, &lbl, &attr);
-                c_4 += ct.c_4_vec[s_tilde];
+                c_4 += weights.scale(j, ct.c_4_vec[tau.get_tilde(j)]);
             }
-            k *= pairing(k_2_1, c_4).0;
+            k.push(k_2_1, c_4);
         }
 
         for (j_under_tau, js) in eps_not_by_tau {
@@ -126,39 +134,11 @@ pub fn decrypt(
             for j in js {
                 let (auth, lbl, attr) = policy.get(j).0.auth_lbl_attr();
                 let usk = usk.get_partial_key(&auth).unwrap();
-                k_2_2 += usk.k_2_2_map.get(&lbl).unwrap().neg();
-
-                let x_attr_not = hash_attr(&attr);
-
-                let attrs: Vec<String> = usk
-                    .k_1_map
-                    .keys()
-                    .filter_map(|k| {
-                        if k.0.eq(&lbl) {
-                            Some(k.1.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
+                k_2_2 += weights.scale(j, usk.k_2_2_map.get(&lbl).unwrap().neg());
 
-                let mut k_3_bases = Vec::with_capacity(attrs.len());
-                let mut k_3_exps = Vec::with_capacity(attrs.len());
-                let one = ScalarField::from(1);
-                for attr in attrs {
-                    let x_attr = hash_attr(&attr);
-                    let e = -one / (x_attr_not - x_attr);
-                    k_3_exps.push(e);
-                    k_3_bases.push(
-                        usk.k_3_map
-                            .get(&(lbl.clone(), attr.clone()))
-                            .unwrap()
-                            .into_affine(),
-                    );
-                }
-                k_3 += G::msm(&k_3_bases, &k_3_exps).unwrap();
+                k_3 += weights.scale(j, cache.k_3((&auth, &lbl, &attr), &usk.k_3_map));
             }
-            k *= pairing(k_2_2 + k_3, c_4).0;
+            k.push(k_2_2 + k_3, c_4);
         }
     } else {
         for j_under_tau_or_tau_tilde in domain_neg {
@@ -174,37 +154,9 @@ pub fn decrypt(
                 let (auth, lbl, attr) = policy.get(*j).0.auth_lbl_attr();
                 let usk = usk.get_partial_key(&auth).unwrap();
 
-                k_2_2 += usk.k_2_2_map.get(&lbl).unwrap().neg();
+                k_2_2 += weights.scale(*j, usk.k_2_2_map.get(&lbl).unwrap().neg());
 
-                let x_attr_not = hash_attr(&attr);
-
-                let attrs: Vec<String> = usk
-                    .k_1_map
-                    .keys()
-                    .filter_map(|k| {
-                        if k.0.eq(&lbl) {
-                            Some(k.1.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                let mut k_3_bases = Vec::with_capacity(attrs.len());
-                let mut k_3_exps = Vec::with_capacity(attrs.len());
-                let one = ScalarField::from(1);
-                for attr in attrs {
-                    let x_attr = hash_attr(&attr);
-                    let e = -one / (x_attr_not - x_attr);
-                    k_3_exps.push(e);
-                    k_3_bases.push(
-                        usk.k_3_map
-                            .get(&(lbl.clone(), attr.clone()))
-                            .unwrap()
-                            .into_affine(),
-                    );
-                }
-                k_3 += G::msm(&k_3_bases, &k_3_exps).unwrap();
+                k_3 += weights.scale(*j, cache.k_3((&auth, &lbl, &attr), &usk.k_3_map));
             }
 
             let js = eps_not_by_tau_tilde
@@ -213,10 +165,10 @@ pub fn decrypt(
             let mut k_2_1 = G::zero();
             for j in js {
                 let auth = policy.get(*j).0.auth;
-                k_2_1 += usk.get_partial_key(&auth).unwrap().k_2_1.neg();
+                k_2_1 += weights.scale(*j, usk.get_partial_key(&auth).unwrap().k_2_1.neg());
             }
 
-            k *= pairing(k_2_1 + k_2_2 + k_3, c_4).0;
+            k.push(k_2_1 + k_2_2 + k_3, c_4);
         }
     }
 
@@ -225,43 +177,23 @@ pub fn decrypt(
         let mut c_5 = G::zero();
 
         for j in js.iter() {
-            c_5 += ct.c_5_vec[*j];
+            c_5 += weights.scale(*j, ct.c_5_vec[*j]);
         }
-        k *= pairing(c_5, usk.k_6).0;
+        k.push(c_5, usk.k_6);
     }
 
     for ((auth, lbl, attr), js) in eps_not_by_auth_lbl_attr {
         let mut c_2 = G::zero();
         for j in js {
-            c_2 += ct.c_2_vec[j];
+            c_2 += weights.scale(j, ct.c_2_vec[j]);
         }
 
-        let x_attr_not = hash_attr(&attr);
         let usk = usk.get_partial_key(&auth).unwrap();
 
-        let attrs: Vec<String> = usk
-            .k_1_map
-            .keys()
-            .filter_map(|k| {
-                if k.0.eq(&lbl) {
-                    Some(k.1.clone())
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let mut k_5_bases = Vec::with_capacity(attrs.len());
-        let mut k_5_exps = Vec::with_capacity(attrs.len());
-        let one = ScalarField::from(1);
-        for attr in attrs {
-            let x_attr = hash_attr(&attr);
-            let e = one / (x_attr_not - x_attr);
-            let iota = iota.get(&auth, &lbl, &attr);
-            k_5_exps.push(e);
-            k_5_bases.push(usk.k_5_vec[iota].into_affine());
-        }
-        let k_5 = H::msm(&k_5_bases, &k_5_exps).unwrap();
-        k *= pairing(c_2, k_5).0;
+        let k_5 = cache.k_5((&auth, &lbl, &attr), &usk.k_3_map, |attr| {
+            usk.k_5_vec[iota.get(&auth, &lbl, attr)]
+        });
+        k.push(c_2, k_5);
     }
-    Some(k)
+    Some(k.finish())
 }
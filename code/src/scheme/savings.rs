@@ -0,0 +1,37 @@
+use crate::policy::Policy;
+
+use super::{Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme, Tau};
+
+pub struct CiphertextSavings {
+    pub variant: String,
+    pub original_size: usize,
+    pub normalized_size: usize,
+}
+
+impl CiphertextSavings {
+    fn new(scheme: &impl Scheme, original: &Policy, normalized: &Policy) -> Self {
+        CiphertextSavings {
+            variant: scheme.get_name(),
+            original_size: scheme.ciphertext_size(original, &Tau::new(original)),
+            normalized_size: scheme.ciphertext_size(normalized, &Tau::new(normalized)),
+        }
+    }
+
+    pub fn saved_bytes(&self) -> usize {
+        self.original_size.saturating_sub(self.normalized_size)
+    }
+}
+
+// Reports how many bytes `Scheme::encrypt_normalized` saves over `Scheme::encrypt` per variant
+pub fn normalization_savings(policy: &Policy) -> Vec<CiphertextSavings> {
+    let normalized = policy.normalize().unwrap_or_else(|| policy.clone());
+    vec![
+        CiphertextSavings::new(&Opt0::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt1::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt2::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt3::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt4::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt5::new(), policy, &normalized),
+        CiphertextSavings::new(&Opt6::new(), policy, &normalized),
+    ]
+}
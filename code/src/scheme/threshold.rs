@@ -0,0 +1,476 @@
+use ark_ff::Field;
+use rand::Rng;
+
+use crate::curve::ScalarField;
+use crate::policy::UserAttribute;
+
+use super::authority::authority_attrs;
+use super::common::{respond, KeyProof, PartialKey};
+use super::{Abe, Iota, IssuedKey, Scheme};
+
+// A t-of-n threshold authority. No party ever holds its partial MSK: the n servers run a
+// distributed key generation, after which each one holds a Shamir share of every secret
+// of the MSK. Any t servers issue key shares for a user, which a combiner interpolates into
+// the partial key the authority would have issued on its own, see
+// `Abe::combine_key_shares`. Fewer than t servers learn nothing about the secrets.
+//
+// Keygen is linear in the secrets and the randomness, so every server uses randomness of
+// its own. The primed secrets of opt4 to opt6 only enter keygen through their powers in
+// the MPK, which the servers know.
+//
+// The servers run in-process here. They only communicate through the key generation, in
+// which the `Dealing`s are broadcast and every `DealtShare` is sent to its server only, so
+// they can be run by separate parties as well.
+pub struct ThresholdAuthority<S: Scheme> {
+    name: String,
+    threshold: usize,
+    dealings: Vec<Dealing<S>>,
+    servers: Vec<KeyServer<S>>,
+}
+
+// A single server of a threshold authority, which holds a share of the partial MSK
+pub struct KeyServer<S: Scheme> {
+    pub abe: Abe<S>,
+    name: String,
+    index: u64,
+    threshold: usize,
+    msk: S::PartialMSK,
+    mpk: S::PartialMPK,
+}
+
+// What a server broadcasts during the distributed key generation. It shares fresh secrets
+// with a random polynomial of degree t - 1 for each of them. The commitments are the public
+// keys of the coefficients, against which every server checks its share; the public key of
+// the authority is the product of the first commitments of all dealings.
+pub struct Dealing<S: Scheme> {
+    pub dealer: u64,
+    pub commitments: Vec<S::PartialMPK>,
+}
+
+// The evaluations of the polynomials of a dealing for a single server, which only that
+// server may receive
+pub struct DealtShare {
+    pub dealer: u64,
+    pub server: u64,
+    secrets: Vec<ScalarField>,
+}
+
+// A share of a partial key issued by a single server of a t-of-n threshold authority
+pub struct KeyShare<S: Scheme> {
+    pub server: u64,
+    pub threshold: usize,
+    pub gid: String,
+    pub user_attrs: Vec<UserAttribute>,
+    pub usk: S::PartialUSK,
+}
+
+impl<S: Scheme> Dealing<S> {
+    // Returns the public dealing and the shares of the servers 1 to num_servers
+    pub fn deal(
+        mut rng: impl Rng,
+        scheme: &S,
+        auth: &str,
+        dealer: u64,
+        threshold: usize,
+        num_servers: usize,
+    ) -> (Self, Vec<DealtShare>) {
+        // every coefficient is as random as the secrets of a fresh authority
+        let mut coeffs = Vec::with_capacity(threshold);
+        let mut commitments = Vec::with_capacity(threshold);
+        for _ in 0..threshold {
+            let (msk, mpk) = scheme.setup_authority(&mut rng, auth);
+            coeffs.push(scheme.authority_secrets(&msk));
+            commitments.push(mpk);
+        }
+        let shares = (1..=num_servers as u64)
+            .map(|server| DealtShare {
+                dealer,
+                server,
+                secrets: evaluate(&coeffs, ScalarField::from(server)),
+            })
+            .collect();
+        let dealing = Dealing {
+            dealer,
+            commitments,
+        };
+        (dealing, shares)
+    }
+}
+
+impl<S: Scheme> KeyServer<S> {
+    // Checks the shares the server received against the broadcast dealings and derives its
+    // share of the MSK and the public key of the authority from them
+    pub fn new(
+        auth: &str,
+        index: u64,
+        threshold: usize,
+        dealings: &[Dealing<S>],
+        shares: &[DealtShare],
+    ) -> Result<Self, String> {
+        if dealings.is_empty() {
+            return Err(String::from("No dealings given"));
+        }
+        if shares.len() != dealings.len() {
+            return Err(format!(
+                "Server {} received {} shares for {} dealings",
+                index,
+                shares.len(),
+                dealings.len()
+            ));
+        }
+        let abe = Abe::new(S::new());
+        let scheme = &abe.scheme;
+        let mut dealers = Vec::new();
+        let mut secrets: Vec<ScalarField> = Vec::new();
+        for dealing in dealings {
+            if dealers.contains(&dealing.dealer) {
+                return Err(format!("Server {} dealt more than once", dealing.dealer));
+            }
+            dealers.push(dealing.dealer);
+            if dealing.commitments.len() != threshold
+                || dealing.commitments.iter().any(|c| c.get_auth() != auth)
+            {
+                return Err(format!(
+                    "The commitments of dealer {} do not match the authority",
+                    dealing.dealer
+                ));
+            }
+            let Some(share) = shares
+                .iter()
+                .find(|share| share.dealer == dealing.dealer && share.server == index)
+            else {
+                return Err(format!(
+                    "Dealer {} sent no share to server {}",
+                    dealing.dealer, index
+                ));
+            };
+            let share = &share.secrets;
+            let x = ScalarField::from(index);
+            let mut x_k = ScalarField::from(1);
+            let mut expected = Vec::with_capacity(threshold);
+            for commitment in dealing.commitments.iter() {
+                expected.push((x_k, commitment));
+                x_k *= x;
+            }
+            let (_, share_mpk) = scheme.setup_authority_from(auth, share)?;
+            if share_mpk != scheme.combine_partial_mpks(&expected) {
+                return Err(format!(
+                    "Dealer {} sent an invalid share to server {}",
+                    dealing.dealer, index
+                ));
+            }
+            if secrets.is_empty() {
+                secrets = share.clone();
+            } else {
+                for (secret, s) in secrets.iter_mut().zip(share) {
+                    *secret += s;
+                }
+            }
+        }
+        let (msk, _) = scheme.setup_authority_from(auth, &secrets)?;
+        let mpk = share_mpk(scheme, dealings, 0);
+        Ok(KeyServer {
+            abe,
+            name: String::from(auth),
+            index,
+            threshold,
+            msk,
+            mpk,
+        })
+    }
+
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    pub fn public_key(&self) -> S::PartialMPK {
+        self.mpk.clone()
+    }
+
+    // The first round of a joint proof of the public key, in which the server commits to
+    // nonces of its own, see `ThresholdAuthority::prove_public_key`
+    pub fn proof_nonces(&self, rng: impl Rng) -> (Vec<ScalarField>, S::PartialMPK) {
+        let scheme = &self.abe.scheme;
+        let (nonces, commitment) = scheme.setup_authority(rng, &self.name);
+        (scheme.authority_secrets(&nonces), commitment)
+    }
+
+    // The second round of a joint proof of the public key, in which the server responds to
+    // the challenge with its share of the secrets
+    pub fn proof_responses(&self, nonces: &[ScalarField], c: ScalarField) -> Vec<ScalarField> {
+        respond(nonces, &self.abe.scheme.authority_secrets(&self.msk), c)
+    }
+
+    // Issues the server's share of the partial key for the given attributes, which all have
+    // to be managed by the authority, and the identity attribute of the GID
+    pub fn keygen(
+        &self,
+        rng: impl Rng,
+        gid: &str,
+        user_attrs: &[UserAttribute],
+    ) -> Result<KeyShare<S>, String> {
+        let user_attrs = authority_attrs(&self.name, gid, user_attrs)?;
+        let iota = Iota::new(&user_attrs);
+        let usk = self
+            .abe
+            .scheme
+            .keygen_share(rng, gid, &self.msk, &self.mpk, &user_attrs, &iota);
+        Ok(KeyShare {
+            server: self.index,
+            threshold: self.threshold,
+            gid: String::from(gid),
+            user_attrs,
+            usk,
+        })
+    }
+}
+
+impl<S: Scheme> ThresholdAuthority<S> {
+    // Runs the distributed key generation of the servers 1 to num_servers, each of which
+    // deals once
+    pub fn setup(
+        mut rng: impl Rng,
+        name: &str,
+        threshold: usize,
+        num_servers: usize,
+    ) -> Result<Self, String> {
+        if threshold == 0 || threshold > num_servers {
+            return Err(format!(
+                "Invalid threshold {} for {} servers",
+                threshold, num_servers
+            ));
+        }
+        let scheme = S::new();
+        let mut dealings = Vec::with_capacity(num_servers);
+        let mut received: Vec<Vec<DealtShare>> = (0..num_servers).map(|_| Vec::new()).collect();
+        for dealer in 1..=num_servers as u64 {
+            let (dealing, shares) =
+                Dealing::deal(&mut rng, &scheme, name, dealer, threshold, num_servers);
+            dealings.push(dealing);
+            for (received, share) in received.iter_mut().zip(shares) {
+                received.push(share);
+            }
+        }
+        let servers = (1..=num_servers as u64)
+            .zip(received)
+            .map(|(index, shares)| KeyServer::new(name, index, threshold, &dealings, &shares))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(ThresholdAuthority {
+            name: String::from(name),
+            threshold,
+            dealings,
+            servers,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn public_key(&self) -> S::PartialMPK {
+        self.servers[0].public_key()
+    }
+
+    // The broadcast dealings, against which `Abe::combine_key_shares` checks the key shares
+    pub fn dealings(&self) -> &[Dealing<S>] {
+        &self.dealings
+    }
+
+    pub fn server(&self, index: u64) -> Option<&KeyServer<S>> {
+        self.servers.iter().find(|server| server.index == index)
+    }
+
+    // Proves knowledge of the secrets behind the public key without reconstructing them.
+    // The first `threshold` servers run a Schnorr proof together: the commitments to their
+    // nonces and their responses are interpolated like key shares, which gives a `KeyProof`
+    // like that of a single authority.
+    pub fn prove_public_key(&self, mut rng: impl Rng) -> KeyProof<S::PartialMPK> {
+        let provers = &self.servers[..self.threshold];
+        let indices: Vec<u64> = provers.iter().map(|server| server.index).collect();
+        let lambdas: Vec<ScalarField> = indices
+            .iter()
+            .map(|&i| lagrange_at_zero(i, &indices))
+            .collect();
+        let (nonces, commitments): (Vec<_>, Vec<_>) = provers
+            .iter()
+            .map(|server| server.proof_nonces(&mut rng))
+            .unzip();
+        let weighted: Vec<(ScalarField, &S::PartialMPK)> =
+            lambdas.iter().copied().zip(commitments.iter()).collect();
+        let commitment = provers[0].abe.scheme.combine_partial_mpks(&weighted);
+        let c = KeyProof::challenge(&self.public_key(), &commitment);
+        let mut responses = vec![ScalarField::from(0); nonces[0].len()];
+        for ((server, nonces), lambda) in provers.iter().zip(nonces.iter()).zip(lambdas) {
+            let server_responses = server.proof_responses(nonces, c);
+            for (response, r) in responses.iter_mut().zip(server_responses) {
+                *response += lambda * r;
+            }
+        }
+        KeyProof {
+            commitment,
+            responses,
+        }
+    }
+}
+
+impl<S: Scheme> Abe<S> {
+    // Interpolates the key shares of at least t distinct servers of the same authority into
+    // the partial key of that authority. The threshold t and the public key of every server
+    // follow from the dealings of the authority, against which each share is checked before
+    // the interpolation, so that a single bad share is blamed on its server instead of
+    // silently giving a wrong key.
+    pub fn combine_key_shares(
+        &self,
+        dealings: &[Dealing<S>],
+        shares: Vec<KeyShare<S>>,
+    ) -> Result<IssuedKey<S>, String> {
+        let Some(threshold) = dealings.first().map(|dealing| dealing.commitments.len()) else {
+            return Err(String::from("No dealings given"));
+        };
+        if dealings
+            .iter()
+            .any(|dealing| dealing.commitments.len() != threshold)
+        {
+            return Err(String::from("The dealings do not agree on the threshold"));
+        }
+        if shares.len() < threshold {
+            return Err(format!(
+                "At least {} key shares are needed, but only {} were given",
+                threshold,
+                shares.len()
+            ));
+        }
+        let Some(first) = shares.first() else {
+            return Err(String::from("No key shares given"));
+        };
+        let gid = first.gid.clone();
+        let user_attrs = first.user_attrs.clone();
+        let auth = first.usk.get_auth();
+        let scheme = &self.scheme;
+        let mpk = share_mpk(scheme, dealings, 0);
+        let iota = Iota::new(&user_attrs);
+        let mut servers = Vec::new();
+        for share in shares.iter() {
+            if share.gid != gid || share.user_attrs != user_attrs || share.usk.get_auth() != auth {
+                return Err(format!(
+                    "The key share of server {} was issued for a different key",
+                    share.server
+                ));
+            }
+            if share.server == 0 || servers.contains(&share.server) {
+                return Err(format!("Invalid or repeated server index {}", share.server));
+            }
+            if share.threshold != threshold {
+                return Err(format!(
+                    "The key share of server {} was issued for threshold {}, not {}",
+                    share.server, share.threshold, threshold
+                ));
+            }
+            let server_mpk = share_mpk(scheme, dealings, share.server);
+            let server_mpk = scheme.share_public_key(&server_mpk, &mpk);
+            scheme
+                .verify_partial_usk(&server_mpk, &gid, &user_attrs, &iota, &share.usk)
+                .map_err(|e| {
+                    format!("The key share of server {} is invalid: {}", share.server, e)
+                })?;
+            servers.push(share.server);
+        }
+        let shares = shares
+            .into_iter()
+            .map(|share| (lagrange_at_zero(share.server, &servers), share.usk))
+            .collect();
+        Ok(IssuedKey {
+            gid,
+            user_attrs,
+            usk: self.scheme.combine_usk_shares(shares),
+        })
+    }
+}
+
+// The public key of the share of the MSK which the dealings give the server with the given
+// index. The share at index 0 is the MSK itself, whose public key is the product of the
+// first commitments.
+fn share_mpk<S: Scheme>(scheme: &S, dealings: &[Dealing<S>], index: u64) -> S::PartialMPK {
+    let x = ScalarField::from(index);
+    let mut terms = Vec::new();
+    for dealing in dealings {
+        let mut x_k = ScalarField::from(1);
+        for commitment in dealing.commitments.iter() {
+            terms.push((x_k, commitment));
+            x_k *= x;
+        }
+    }
+    scheme.combine_partial_mpks(&terms)
+}
+
+// Evaluates the polynomials with the given coefficients, one vector of secrets per degree,
+// at x
+fn evaluate(coeffs: &[Vec<ScalarField>], x: ScalarField) -> Vec<ScalarField> {
+    let mut values = vec![ScalarField::from(0); coeffs[0].len()];
+    for coeff in coeffs.iter().rev() {
+        for (value, c) in values.iter_mut().zip(coeff) {
+            *value = *value * x + c;
+        }
+    }
+    values
+}
+
+// The Lagrange coefficient of server i for interpolating at 0 from the given servers
+fn lagrange_at_zero(i: u64, servers: &[u64]) -> ScalarField {
+    let x_i = ScalarField::from(i);
+    let mut lambda = ScalarField::from(1);
+    for &j in servers.iter().filter(|&&j| j != i) {
+        let x_j = ScalarField::from(j);
+        lambda *= x_j * (x_j - x_i).inverse().unwrap();
+    }
+    lambda
+}
+
+#[test]
+fn test_lagrange_at_zero() {
+    // f(x) = 3 + 5x + 7x^2
+    let coeffs = vec![
+        vec![ScalarField::from(3)],
+        vec![ScalarField::from(5)],
+        vec![ScalarField::from(7)],
+    ];
+    let servers = [2, 5, 9];
+    let mut f_0 = ScalarField::from(0);
+    for &i in servers.iter() {
+        let f_i = evaluate(&coeffs, ScalarField::from(i))[0];
+        f_0 += lagrange_at_zero(i, &servers) * f_i;
+    }
+    assert_eq!(f_0, ScalarField::from(3));
+}
+
+#[test]
+fn test_invalid_dealing() {
+    use super::Opt4;
+
+    let mut rng = ark_std::test_rng();
+    let scheme = Opt4::new();
+    let (dealings, shares): (Vec<Dealing<Opt4>>, Vec<Vec<DealtShare>>) = (1..=3)
+        .map(|dealer| Dealing::deal(&mut rng, &scheme, "A", dealer, 2, 3))
+        .unzip();
+    // the shares received by server j
+    let mut received: Vec<Vec<DealtShare>> = vec![Vec::new(), Vec::new(), Vec::new()];
+    for shares in shares {
+        for (j, share) in shares.into_iter().enumerate() {
+            received[j].push(share);
+        }
+    }
+    assert!(KeyServer::new("A", 2, 2, &dealings, &received[1]).is_ok());
+    assert!(KeyServer::new("B", 2, 2, &dealings, &received[1]).is_err());
+    assert!(KeyServer::new("A", 2, 2, &dealings[..0], &received[1][..0]).is_err());
+    // a server only holds its own shares
+    assert!(KeyServer::new("A", 2, 2, &dealings, &received[0]).is_err());
+    assert!(KeyServer::new("A", 4, 2, &dealings, &received[2]).is_err());
+    assert!(KeyServer::new("A", 2, 2, &dealings, &received[1][..2]).is_err());
+    received[1][1].secrets[0] += ScalarField::from(1);
+    assert!(KeyServer::new("A", 1, 2, &dealings, &received[0]).is_ok());
+    assert!(KeyServer::new("A", 2, 2, &dealings, &received[1]).is_err());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, USER_ID};
+
+const POLICY: &str = "(A.a:0 | A.a:1) & !B.b:0 & (A.c:0 | !A.a:1)";
+
+fn test_scheme(scheme: impl Scheme, user_attrs: Vec<&str>) -> (curve::Gt, Option<curve::Gt>) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let user_attrs = parse_attrs(&user_attrs);
+    let policy = Policy::parse(POLICY).unwrap();
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let usk = abe.keygen(&mut rng, USER_ID, &msk, &user_attrs).unwrap();
+    let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let k_dec = abe.decrypt(&usk, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(scheme: impl Scheme, user_attrs: Vec<&str>) {
+    let (k_enc, k_dec) = test_scheme(scheme, user_attrs);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(scheme: impl Scheme, user_attrs: Vec<&str>) {
+    let (_, k_dec) = test_scheme(scheme, user_attrs);
+    assert!(k_dec.is_none());
+}
+
+#[test]
+fn high_level_api_ok() {
+    let user_attrs = vec!["A.a:1", "A.a:0", "B.b:1", "A.c:0", "B.b:2"];
+    for_each_scheme!(assert_decryption_ok, user_attrs.clone());
+}
+
+#[test]
+fn high_level_api_fail() {
+    let user_attrs = vec!["A.a:1", "B.b:1", "B.b:0", "A.c:0"];
+    for_each_scheme!(assert_decryption_fail, user_attrs.clone());
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Authority, MpkDirectory, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, AUTHORITY_POLICIES, USER_ID};
+
+fn test_authority<S: Scheme>(scheme: S) {
+    let mut rng = ark_std::test_rng();
+    let auth_a = Authority::setup(&mut rng, scheme, "A");
+    let auth_b = Authority::setup(&mut rng, S::new(), "B");
+    let mut directory = MpkDirectory::<S>::new();
+    directory
+        .publish(auth_a.public_key(), &auth_a.prove_public_key(&mut rng))
+        .unwrap();
+    directory
+        .publish(auth_b.public_key(), &auth_b.prove_public_key(&mut rng))
+        .unwrap();
+    assert!(directory
+        .publish(auth_b.public_key(), &auth_b.prove_public_key(&mut rng))
+        .is_err());
+    let rogue = Authority::setup(&mut rng, S::new(), "A");
+    assert!(directory
+        .publish(rogue.public_key(), &rogue.prove_public_key(&mut rng))
+        .is_err());
+    let mpk = directory.mpk();
+
+    let issued_a = auth_a
+        .keygen(&mut rng, USER_ID, &parse_attrs(&["A.a:0", "A.b:0"]))
+        .unwrap();
+    let issued_b = auth_b
+        .keygen(&mut rng, USER_ID, &parse_attrs(&["B.c:0"]))
+        .unwrap();
+    let abe = &auth_a.abe;
+    let usk = abe.assemble_key(vec![issued_a, issued_b]).unwrap();
+
+    for (policy, satisfied) in AUTHORITY_POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert_eq!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)), satisfied);
+    }
+    let policy = Policy::parse("A.a:0 & B.c:0").unwrap();
+    let (_, ct) = abe.encrypt_revoking(&mut rng, &mpk, &policy, &[USER_ID]);
+    assert!(abe.decrypt(&usk, &ct).is_none());
+
+    // authorities only issue their own attributes
+    assert!(auth_a
+        .keygen(&mut rng, USER_ID, &parse_attrs(&["B.c:0"]))
+        .is_err());
+    assert!(auth_a
+        .keygen(&mut rng, USER_ID, &parse_attrs(&["A.__gid:OTHER"]))
+        .is_err());
+
+    // partial keys of different GIDs or of the same authority cannot be combined
+    let issued = |auth: &Authority<S>, gid: &str, attrs: &[&str]| {
+        auth.keygen(ark_std::test_rng(), gid, &parse_attrs(attrs))
+            .unwrap()
+    };
+    assert!(abe
+        .assemble_key(vec![
+            issued(&auth_a, USER_ID, &["A.a:0"]),
+            issued(&auth_b, "OTHER_USER_ID", &["B.c:0"]),
+        ])
+        .is_err());
+    assert!(abe
+        .assemble_key(vec![
+            issued(&auth_a, USER_ID, &["A.a:0"]),
+            issued(&auth_a, USER_ID, &["A.b:0"]),
+        ])
+        .is_err());
+    assert!(abe.assemble_key(vec![]).is_err());
+}
+
+#[test]
+fn authority() {
+    for_each_scheme!(test_authority);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+    scheme::{Abe, Opt0, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, USER_ID};
+
+const USERS: [(&str, &[&str]); 3] = [
+    ("USER_0", &["A.a:0", "A.a:1", "A.b:1", "B.c:0"]),
+    ("USER_1", &["A.a:2", "B.c:1"]),
+    ("USER_2", &["A.b:0", "B.c:0", "B.d:0"]),
+];
+
+// which of the users satisfy the policy
+const POLICIES: [(&str, [bool; 3]); 4] = [
+    ("A.a:0 & B.c:0", [true, false, false]),
+    ("!A.a:0 & B.c:1", [false, true, false]),
+    ("B.c:0 & !A.b:1", [false, false, true]),
+    ("(A.a:1 & !A.b:0) | B.d:0", [true, false, true]),
+];
+
+fn test_keygen_batch(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let user_attrs: Vec<Vec<UserAttribute>> =
+        USERS.iter().map(|(_, attrs)| parse_attrs(attrs)).collect();
+    let users: Vec<(&str, &[UserAttribute])> = USERS
+        .iter()
+        .zip(user_attrs.iter())
+        .map(|((gid, _), attrs)| (*gid, attrs.as_slice()))
+        .collect();
+    let usks = abe.keygen_batch(&mut rng, &msk, &users).unwrap();
+    assert_eq!(usks.len(), USERS.len());
+
+    for (policy, satisfied) in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        for (usk, satisfied) in usks.iter().zip(satisfied) {
+            let k_dec = abe.decrypt(usk, &ct);
+            assert_eq!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)), satisfied);
+        }
+    }
+    assert!(abe.keygen_batch(&mut rng, &msk, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn keygen_batch() {
+    for_each_scheme!(test_keygen_batch);
+}
+
+#[test]
+fn keygen_batch_unknown_authority() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt0::new());
+    let (msk, _) = abe.setup(&mut rng, &vec!["A"]);
+    let user_attrs = parse_attrs(&["A.a:0", "C.a:0"]);
+    assert!(abe
+        .keygen_batch(&mut rng, &msk, &[(USER_ID, &user_attrs)])
+        .is_err());
+}
@@ -0,0 +1,49 @@
+// Fixtures shared by the integration tests. Each test crate which includes this module
+// uses only parts of it.
+#![allow(dead_code)]
+
+use abe_cubed::policy::UserAttribute;
+
+pub const USER_ID: &str = "TEST_USER_ID";
+
+pub const USER_ATTRS: [&str; 4] = ["A.a:0", "A.a:1", "A.b:1", "B.c:0"];
+
+// Policies which the holder of `USER_ATTRS` satisfies
+pub const POLICIES: [&str; 4] = [
+    "A.a:0 & B.c:0",
+    "!A.a:2 & B.c:0",
+    "(A.a:1 & !A.b:0) | B.c:1",
+    "A.b:1 | !A.b:0",
+];
+
+// Policies over the authorities A and B, and whether the holder of A.a:0, A.b:0 and B.c:0
+// satisfies them. Used by the tests in which each authority issues its own partial key.
+pub const AUTHORITY_POLICIES: [(&str, bool); 4] = [
+    ("A.a:0 & B.c:0", true),
+    ("!A.a:2 & B.c:0", true),
+    ("(A.a:1 & !A.b:0) | B.c:1", false),
+    ("A.b:1 & !B.c:0", false),
+];
+
+pub fn parse_attrs(user_attrs: &[&str]) -> Vec<UserAttribute> {
+    user_attrs
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect()
+}
+
+// Calls the test with a new instance of every variant, followed by the given arguments
+#[allow(unused_macros)]
+macro_rules! for_each_scheme {
+    ($test:ident $(, $arg:expr)*) => {
+        $test(<abe_cubed::scheme::Opt0 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt1 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt2 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt3 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt4 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt5 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+        $test(<abe_cubed::scheme::Opt6 as abe_cubed::scheme::Scheme>::new() $(, $arg)*);
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use for_each_scheme;
@@ -0,0 +1,48 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, POLICIES, USER_ATTRS, USER_ID};
+
+fn test_decrypt_many(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&USER_ATTRS))
+        .unwrap();
+
+    let mut keys = Vec::new();
+    let mut cts = Vec::new();
+    // the first policy occurs twice, a negated attribute of the second one occurs again in
+    // another policy and the last one is not satisfied by the key
+    let unsatisfied = "A.a:2 | B.c:1";
+    let mut policies = POLICIES.to_vec();
+    policies.extend([POLICIES[0], "!A.a:2 & A.b:1", unsatisfied]);
+    for policy in policies {
+        let policy = Policy::parse(policy).unwrap();
+        let (k, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        keys.push(k);
+        cts.push(ct);
+    }
+    let ct_refs: Vec<_> = cts.iter().collect();
+    let k_dec_vec = abe.decrypt_many(&usk, &ct_refs);
+    assert_eq!(k_dec_vec.len(), cts.len());
+    for ((k_enc, ct), k_dec) in keys.iter().zip(cts.iter()).zip(k_dec_vec.iter()) {
+        assert_eq!(*k_dec, abe.decrypt(&usk, ct));
+        if ct.policy != Policy::parse(unsatisfied).unwrap() {
+            assert!(k_dec.is_some_and(|k| curve::Gt::eq(k_enc, &k)));
+        } else {
+            assert!(k_dec.is_none());
+        }
+    }
+    assert!(abe.decrypt_many(&usk, &[]).is_empty());
+}
+
+#[test]
+fn decrypt_many() {
+    for_each_scheme!(test_decrypt_many);
+}
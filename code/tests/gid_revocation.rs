@@ -0,0 +1,65 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+    scheme::{Abe, Opt3, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, USER_ID};
+
+const REVOKED_ID: &str = "REVOKED_USER_ID";
+const OTHER_REVOKED_ID: &str = "OTHER_REVOKED_USER_ID";
+const B_USER_ID: &str = "B_USER_ID";
+
+const POLICIES: [&str; 3] = ["A.a:0 | B.b:0", "(A.a:0 & !A.a:1) | !B.b:1", "B.b:0"];
+
+fn test_gid_revocation(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let user_attrs = parse_attrs(&["A.a:0", "B.b:0"]);
+    let revoked_usk = abe.keygen(&mut rng, REVOKED_ID, &msk, &user_attrs).unwrap();
+    let usk = abe.keygen(&mut rng, USER_ID, &msk, &user_attrs).unwrap();
+    // holds attributes of authority B only
+    let b_usk = abe
+        .keygen(&mut rng, B_USER_ID, &msk, &parse_attrs(&["B.b:0"]))
+        .unwrap();
+
+    for policy in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let k_dec = abe.decrypt(&revoked_usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+
+        let revoked = [REVOKED_ID, OTHER_REVOKED_ID];
+        let (k_enc, ct) = abe.encrypt_revoking(&mut rng, &mpk, &policy, &revoked);
+        assert!(abe.decrypt(&revoked_usk, &ct).is_none());
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+        let k_dec = abe.decrypt(&b_usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+    }
+}
+
+#[test]
+fn gid_revocation() {
+    for_each_scheme!(test_gid_revocation);
+}
+
+#[test]
+fn gid_label_is_reserved() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt3::new());
+    let (msk, _) = abe.setup(&mut rng, &vec!["A"]);
+    let mut usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&["A.a:0"]))
+        .unwrap();
+    let forged = vec![UserAttribute::gid("A", REVOKED_ID)];
+    assert!(abe
+        .keygen_extend(&mut rng, &msk, &mut usk, &forged)
+        .is_err());
+    assert!(abe.keygen(&mut rng, USER_ID, &msk, &forged).is_err());
+    let user_attrs = parse_attrs(&["A.a:0"]);
+    let users = [(USER_ID, &user_attrs[..]), (B_USER_ID, &forged[..])];
+    assert!(abe.keygen_batch(&mut rng, &msk, &users).is_err());
+}
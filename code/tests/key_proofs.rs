@@ -0,0 +1,49 @@
+mod common;
+
+use abe_cubed::{
+    curve::ScalarField,
+    scheme::{Authority, MpkDirectory, Scheme, ThresholdAuthority},
+};
+use common::for_each_scheme;
+
+fn test_key_proofs<S: Scheme>(scheme: S) {
+    let mut rng = ark_std::test_rng();
+    let auth_a = Authority::setup(&mut rng, S::new(), "A");
+    let rogue = Authority::setup(&mut rng, S::new(), "A");
+    let auth_b = Authority::setup(&mut rng, S::new(), "B");
+    let mpk = auth_a.public_key();
+
+    // proofs only hold for the public key they were made for
+    let mut directory = MpkDirectory::<S>::new();
+    let proof = rogue.prove_public_key(&mut rng);
+    assert!(directory.publish(mpk.clone(), &proof).is_err());
+    let proof = auth_b.prove_public_key(&mut rng);
+    assert!(directory.publish(mpk.clone(), &proof).is_err());
+
+    // a public key with a component of an unknown secret is rejected
+    let mut proof = auth_a.prove_public_key(&mut rng);
+    let one = ScalarField::from(1);
+    let shifted = scheme.combine_partial_mpks(&[(one, &mpk), (one, &proof.commitment)]);
+    assert!(directory.publish(shifted, &proof).is_err());
+
+    // so are proofs with a changed or missing response
+    proof.responses[0] += one;
+    assert!(directory.publish(mpk.clone(), &proof).is_err());
+    let mut proof = auth_a.prove_public_key(&mut rng);
+    proof.responses.pop();
+    assert!(directory.publish(mpk.clone(), &proof).is_err());
+
+    let proof = auth_a.prove_public_key(&mut rng);
+    directory.publish(mpk, &proof).unwrap();
+
+    // the servers of a threshold authority prove their public key together
+    let auth_c = ThresholdAuthority::<S>::setup(&mut rng, "C", 3, 4).unwrap();
+    let proof = auth_c.prove_public_key(&mut rng);
+    assert!(directory.publish(rogue.public_key(), &proof).is_err());
+    directory.publish(auth_c.public_key(), &proof).unwrap();
+}
+
+#[test]
+fn key_proofs() {
+    for_each_scheme!(test_key_proofs);
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme},
+};
+use common::{parse_attrs, USER_ID};
+
+const USER_ATTRS: [&str; 3] = ["A.a:0", "A.b:1", "B.c:0"];
+
+// New attributes under an existing label, an existing authority and a new authority
+const ADDED_ATTRS: [&str; 3] = ["A.a:2", "B.c:4", "C.e:0"];
+
+const POLICIES: [&str; 8] = [
+    "A.a:2 & B.c:4 & C.e:0",
+    "A.a:0 & !A.a:5 & !B.c:1",
+    "!A.a:0 | !A.a:2",
+    "(A.b:1 | A.a:3) & !C.e:1",
+    "A.a:2 & !B.c:0",
+    "B.c:4 & (A.a:7 | !A.b:1 | C.e:0)",
+    "!C.e:0",
+    "!A.b:2 & !D.f:0",
+];
+
+// Policies over a label which is new to the key
+const NEW_LABEL_POLICIES: [&str; 3] = ["A.d:3 & A.a:2", "!A.d:4", "!A.d:3 | B.c:5"];
+
+fn assert_same_decryption(scheme: impl Scheme, new_label: bool) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B", "C", "D"]);
+    let mut all_attrs = parse_attrs(&USER_ATTRS);
+    all_attrs.append(&mut parse_attrs(&ADDED_ATTRS));
+    let mut policies = POLICIES.to_vec();
+
+    let mut usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&USER_ATTRS))
+        .unwrap();
+    abe.keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&ADDED_ATTRS))
+        .unwrap();
+    assert!(abe
+        .keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["A.a:2"]))
+        .is_err());
+    assert!(abe
+        .keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["E.a:0"]))
+        .is_err());
+    let res = abe.keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["A.d:3"]));
+    if new_label {
+        res.unwrap();
+        all_attrs.append(&mut parse_attrs(&["A.d:3"]));
+        policies.extend(NEW_LABEL_POLICIES);
+    } else {
+        assert!(res.is_err());
+    }
+    let fresh_usk = abe.keygen(&mut rng, USER_ID, &msk, &all_attrs).unwrap();
+
+    for policy in policies {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let k_dec = abe.decrypt(&usk, &ct);
+        let k_fresh = abe.decrypt(&fresh_usk, &ct);
+        assert_eq!(k_dec.is_some(), k_fresh.is_some());
+        assert_eq!(k_dec.is_some(), policy.evaluate(&all_attrs));
+        if let Some(k) = k_dec {
+            assert!(curve::Gt::eq(&k_enc, &k));
+        }
+    }
+}
+
+#[test]
+fn keygen_extend_decrypts_like_fresh_key() {
+    assert_same_decryption(Opt0::new(), true);
+    assert_same_decryption(Opt1::new(), true);
+    assert_same_decryption(Opt2::new(), true);
+    assert_same_decryption(Opt3::new(), true);
+    assert_same_decryption(Opt4::new(), true);
+    assert_same_decryption(Opt5::new(), false);
+    assert_same_decryption(Opt6::new(), false);
+}
+
+#[test]
+fn keygen_extend_shared_attribute_value() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt0::new());
+    let (msk, _) = abe.setup(&mut rng, &vec!["A"]);
+    let mut usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&["A.a:0"]))
+        .unwrap();
+    let res = abe.keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["A.b:1", "A.c:0"]));
+    assert!(res.is_err());
+    // A.a:0 and the identity attribute
+    assert_eq!(usk.usk.get_user_attributes().len(), 2);
+}
+
+#[test]
+fn keygen_extend_value_under_another_label() {
+    fn extend(scheme: impl Scheme) -> Result<(), String> {
+        let mut rng = ark_std::test_rng();
+        let abe = Abe::new(scheme);
+        let (msk, mpk) = abe.setup(&mut rng, &vec!["A"]);
+        let mut usk = abe
+            .keygen(
+                &mut rng,
+                USER_ID,
+                &msk,
+                &parse_attrs(&["A.dept:eng", "A.team:ops"]),
+            )
+            .unwrap();
+        let res = abe.keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["A.team:eng"]));
+        let policy = Policy::parse("A.team:eng & A.dept:eng").unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        match abe.decrypt(&usk, &ct) {
+            Some(k) => assert!(res.is_ok() && curve::Gt::eq(&k_enc, &k)),
+            None => assert!(res.is_err()),
+        }
+        res
+    }
+
+    let err = extend(Opt0::new()).unwrap_err();
+    assert_eq!(
+        err,
+        "Attribute A.team:eng shares its randomness with an attribute of the key, which has to be reissued"
+    );
+    assert_eq!(extend(Opt1::new()), Err(err));
+    extend(Opt2::new()).unwrap();
+    extend(Opt3::new()).unwrap();
+    extend(Opt4::new()).unwrap();
+    extend(Opt5::new()).unwrap();
+    extend(Opt6::new()).unwrap();
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use abe_cubed::{
+    curve::ScalarField,
+    policy::{LsssMatrix, Policy, UserAttribute},
+    scheme::{Abe, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme},
+};
+use common::{parse_attrs, USER_ID};
+
+// A 2-out-of-3 threshold over the labels, with the Vandermonde rows (1, i). Unlike the
+// rows of a policy, these need coefficients other than 0 and 1 to reconstruct the secret.
+fn threshold_matrix() -> LsssMatrix {
+    let rows = (1..=3)
+        .map(|i| vec![ScalarField::from(1u64), ScalarField::from(i as u64)])
+        .collect();
+    let rho = vec![
+        (UserAttribute::parse("A.a:0").unwrap(), false),
+        (UserAttribute::parse("B.b:0").unwrap(), false),
+        (UserAttribute::parse("A.c:0").unwrap(), true),
+    ];
+    let lsss = LsssMatrix::new(rows, rho).unwrap();
+    LsssMatrix::from_bytes(&lsss.to_bytes()).unwrap()
+}
+
+fn decrypts<S: Scheme>(lsss: &LsssMatrix, user_attrs: &[&str]) -> bool {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(S::new());
+    let user_attrs = parse_attrs(user_attrs);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let usk = abe.keygen(&mut rng, USER_ID, &msk, &user_attrs).unwrap();
+    let (k_enc, ct) = abe.encrypt_lsss(&mut rng, &mpk, lsss);
+    abe.decrypt_lsss(&usk, &ct) == Some(k_enc)
+}
+
+fn assert_threshold<S: Scheme>() {
+    let lsss = threshold_matrix();
+    assert!(decrypts::<S>(&lsss, &["A.a:0", "B.b:0"]));
+    assert!(decrypts::<S>(&lsss, &["A.a:0", "A.c:1"]));
+    assert!(decrypts::<S>(&lsss, &["B.b:0", "A.c:1", "A.c:2"]));
+    assert!(decrypts::<S>(&lsss, &["A.a:0", "B.b:0", "A.c:1"]));
+    assert!(!decrypts::<S>(&lsss, &["A.a:0", "A.c:0"]));
+    assert!(!decrypts::<S>(&lsss, &["B.b:0", "B.b:1"]));
+    assert!(!decrypts::<S>(&lsss, &["A.a:1", "B.b:0", "A.c:0"]));
+}
+
+#[test]
+fn lsss_threshold_opt0() {
+    assert_threshold::<Opt0>();
+}
+
+#[test]
+fn lsss_threshold_opt1() {
+    assert_threshold::<Opt1>();
+}
+
+#[test]
+fn lsss_threshold_opt2() {
+    assert_threshold::<Opt2>();
+}
+
+#[test]
+fn lsss_threshold_opt3() {
+    assert_threshold::<Opt3>();
+}
+
+#[test]
+fn lsss_threshold_opt4() {
+    assert_threshold::<Opt4>();
+}
+
+#[test]
+fn lsss_threshold_opt5() {
+    assert_threshold::<Opt5>();
+}
+
+#[test]
+fn lsss_threshold_opt6() {
+    assert_threshold::<Opt6>();
+}
+
+// The matrix of a policy with every row scaled still shares the same secret
+#[test]
+fn lsss_scaled_policy_matrix() {
+    let policy = Policy::parse("(A.a:0 | B.b:0) & !A.c:0").unwrap();
+    let lsss = policy.lsss();
+    let rows = (0..lsss.num_rows())
+        .map(|i| {
+            let f = ScalarField::from(i as u64 + 2);
+            lsss.row(i).iter().map(|m| *m * f).collect()
+        })
+        .collect();
+    let rho = (0..lsss.num_rows()).map(|i| lsss.rho(i)).collect();
+    let scaled = LsssMatrix::new(rows, rho).unwrap();
+    assert!(scaled.verify(&policy).is_ok());
+    assert!(decrypts::<Opt0>(&scaled, &["B.b:0", "A.c:1"]));
+    assert!(decrypts::<Opt6>(&scaled, &["A.a:0", "A.c:1"]));
+    assert!(!decrypts::<Opt6>(&scaled, &["A.a:0", "B.b:0"]));
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{normalization_savings, Iota, Opt0, Opt6, Scheme, Tau},
+};
+use common::{for_each_scheme, parse_attrs, USER_ID};
+
+const POLICY: &str = "(A.a:0 & B.b:0) | (B.b:0 & A.a:0 & C.c:0) | (A.a:0 & !A.a:0)";
+
+fn test_scheme(scheme: impl Scheme, user_attrs: Vec<&str>) -> (curve::Gt, Option<curve::Gt>) {
+    let mut rng = ark_std::test_rng();
+    let user_attrs = parse_attrs(&user_attrs);
+    let policy = Policy::parse(POLICY).unwrap();
+    let (msk, mpk) = scheme.setup(&mut rng, &vec!["A", "B", "C"]);
+    let iota = Iota::new(&user_attrs);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let (k_enc, ct, policy) = scheme.encrypt_normalized(&mut rng, &mpk, &policy);
+    assert_eq!(policy.len(), 2);
+    let tau = Tau::new(&policy);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(scheme: impl Scheme, user_attrs: Vec<&str>) {
+    let (k_enc, k_dec) = test_scheme(scheme, user_attrs);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(scheme: impl Scheme, user_attrs: Vec<&str>) {
+    let (_, k_dec) = test_scheme(scheme, user_attrs);
+    assert!(k_dec.is_none());
+}
+
+#[test]
+fn normalized_encryption_ok() {
+    let user_attrs = vec!["A.a:0", "B.b:0"];
+    for_each_scheme!(assert_decryption_ok, user_attrs.clone());
+}
+
+#[test]
+fn normalized_encryption_fail() {
+    let user_attrs = vec!["A.a:0", "C.c:0"];
+    for_each_scheme!(assert_decryption_fail, user_attrs.clone());
+}
+
+#[test]
+fn normalization_savings_per_variant() {
+    let policy = Policy::parse(POLICY).unwrap();
+    let savings = normalization_savings(&policy);
+    assert_eq!(savings.len(), 7);
+    for s in savings {
+        assert!(s.normalized_size < s.original_size, "{}", s.variant);
+        assert!(s.saved_bytes() > 0);
+    }
+}
+
+#[test]
+fn ciphertext_size_matches_ciphertext() {
+    let mut rng = ark_std::test_rng();
+    let policy = Policy::parse(POLICY).unwrap();
+    let tau = Tau::new(&policy);
+    let g = 48;
+    let h = 96;
+
+    let scheme = Opt0::new();
+    let (_, mpk) = scheme.setup(&mut rng, &vec!["A", "B", "C"]);
+    let (_, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let expected =
+        (ct.c_1_vec.len() + ct.c_3_vec.len() + ct.c_4_vec.len()) * h + ct.c_2_vec.len() * g;
+    assert_eq!(scheme.ciphertext_size(&policy, &tau), expected);
+
+    let scheme = Opt6::new();
+    let (_, mpk) = scheme.setup(&mut rng, &vec!["A", "B", "C"]);
+    let (_, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let expected = (ct.c_1_vec.len() + ct.c_3_vec.len() + ct.c_4_vec.len()) * h
+        + (ct.c_2_vec.len() + ct.c_5_vec.len()) * g;
+    assert_eq!(scheme.ciphertext_size(&policy, &tau), expected);
+}
@@ -0,0 +1,65 @@
+use abe_cubed::{
+    curve,
+    policy::{BitWidths, Policy, UserAttribute},
+    scheme::{Iota, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme, Tau},
+};
+
+const USER_ID: &str = "TEST_USER_ID";
+
+const POLICY: &str = "A.clearance >= 3 & !(B.age < 18)";
+
+fn widths() -> BitWidths {
+    let mut widths = BitWidths::new();
+    widths
+        .declare("A", "clearance", 3)
+        .and_then(|widths| widths.declare("B", "age", 7))
+        .unwrap();
+    widths
+}
+
+fn test_scheme(scheme: impl Scheme, clearance: u64, age: u64) -> (curve::Gt, Option<curve::Gt>) {
+    let mut rng = ark_std::test_rng();
+    let widths = widths();
+    let mut user_attrs = UserAttribute::numeric("A", "clearance", clearance, &widths).unwrap();
+    user_attrs.append(&mut UserAttribute::numeric("B", "age", age, &widths).unwrap());
+    let policy = Policy::parse_with_widths(POLICY, &widths).unwrap();
+    let (msk, mpk) = scheme.setup(&mut rng, &vec!["A", "B"]);
+    let iota = Iota::new(&user_attrs);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let tau = Tau::new(&policy);
+    let (k_enc, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(scheme: impl Scheme, clearance: u64, age: u64) {
+    let (k_enc, k_dec) = test_scheme(scheme, clearance, age);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(scheme: impl Scheme, clearance: u64, age: u64) {
+    let (_, k_dec) = test_scheme(scheme, clearance, age);
+    assert!(k_dec.is_none());
+}
+
+#[test]
+fn numeric_comparison_ok() {
+    assert_decryption_ok(Opt0::new(), 3, 18);
+    assert_decryption_ok(Opt1::new(), 7, 42);
+    assert_decryption_ok(Opt2::new(), 4, 18);
+    assert_decryption_ok(Opt3::new(), 5, 127);
+    assert_decryption_ok(Opt4::new(), 3, 64);
+    assert_decryption_ok(Opt5::new(), 6, 19);
+    assert_decryption_ok(Opt6::new(), 7, 18);
+}
+
+#[test]
+fn numeric_comparison_fail() {
+    assert_decryption_fail(Opt0::new(), 2, 18);
+    assert_decryption_fail(Opt1::new(), 7, 17);
+    assert_decryption_fail(Opt2::new(), 0, 42);
+    assert_decryption_fail(Opt3::new(), 5, 0);
+    assert_decryption_fail(Opt4::new(), 1, 64);
+    assert_decryption_fail(Opt5::new(), 6, 16);
+    assert_decryption_fail(Opt6::new(), 2, 17);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, EncryptionPool, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, POLICIES, USER_ATTRS, USER_ID};
+
+fn test_online_offline(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (mut msk, mut mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&USER_ATTRS))
+        .unwrap();
+
+    let mut pool = EncryptionPool::new();
+    let mut policies = POLICIES.to_vec();
+    policies.push("A.a:0 & A.a:1 & A.b:1 & !B.c:1");
+    abe.fill_pool(&mut rng, &mut pool, &mpk, policies.len(), 4);
+    for policy in policies {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt_online(&mut pool, &mpk, &policy).unwrap();
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+    }
+    let policy = Policy::parse("A.a:0").unwrap();
+    assert!(abe.encrypt_online(&mut pool, &mpk, &policy).is_err());
+
+    // authorities that are not in the pool cannot be used
+    abe.fill_pool(&mut rng, &mut pool, &mpk, 1, 4);
+    let policy = Policy::parse("A.a:0 & C.d:0").unwrap();
+    assert!(abe.encrypt_online(&mut pool, &mpk, &policy).is_err());
+
+    // a pool filled before an epoch rotation is stale and is discarded when refilled
+    let update = abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    let policy = Policy::parse("A.a:0 & B.c:0").unwrap();
+    assert!(abe.encrypt_online(&mut pool, &mpk, &policy).is_err());
+    abe.fill_pool(&mut rng, &mut pool, &mpk, 1, 4);
+    let (k_enc, ct) = abe.encrypt_online(&mut pool, &mpk, &policy).unwrap();
+    let mut usk = usk;
+    abe.update_usk(&mut usk, &update.update_key(USER_ID))
+        .unwrap();
+    let k_dec = abe.decrypt(&usk, &ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+    assert!(abe.encrypt_online(&mut pool, &mpk, &policy).is_err());
+
+    // the labels can be hashed in the offline phase as well
+    abe.fill_pool(&mut rng, &mut pool, &mpk, 1, 4);
+    let policy = Policy::parse("!A.a:2 & A.b:1 & B.c:0").unwrap();
+    pool.hash_labels(&policy);
+    let (k_enc, ct) = abe.encrypt_online(&mut pool, &mpk, &policy).unwrap();
+    let k_dec = abe.decrypt(&usk, &ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+#[test]
+fn online_offline() {
+    for_each_scheme!(test_online_offline);
+}
@@ -0,0 +1,336 @@
+use std::{collections::HashSet, vec};
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+};
+
+const USER_ID: &str = "TEST_USER_ID";
+
+fn prepare_test(user_attrs: &Vec<&str>, policy: &str) -> (Vec<String>, Vec<UserAttribute>, Policy) {
+    let policy = Policy::parse(policy).unwrap();
+    let user_attrs: Vec<UserAttribute> = user_attrs
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let mut auths: HashSet<String> = HashSet::new();
+    for ua in user_attrs.iter() {
+        auths.insert(ua.auth.clone());
+    }
+    for idx in 0..policy.len() {
+        auths.insert(policy.get(idx).0.auth.clone());
+    }
+    if auths.is_empty() {
+        panic!(
+            "Fatal error: cannot execute test case if both user attributes and policy are empty"
+        );
+    }
+    (auths.into_iter().collect(), user_attrs, policy)
+}
+
+fn test_scheme(user_attrs: Vec<&str>, policy: &str) -> (curve::Gt, Option<curve::Gt>) {
+    use abe_cubed::scheme::{Iota, Opt0, Scheme, Tau};
+    let (auths, user_attrs, policy) = prepare_test(&user_attrs, &policy);
+    let mut rng = ark_std::test_rng();
+    let scheme = Opt0::new();
+    let auths: Vec<&str> = auths.iter().map(|s| s as &str).collect();
+    let iota = Iota::new(&user_attrs);
+    let (msk, mpk) = scheme.setup(&mut rng, &auths);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let tau = Tau::new(&policy);
+    let (k_enc, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(user_attrs: Vec<&str>, policy: &str) {
+    let (k_enc, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(user_attrs: Vec<&str>, policy: &str) {
+    let (_, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_none());
+}
+
+// Auto-generated test cases with repeated literals
+
+#[test]
+fn opt0_reuse_generated_test_case_000_ok() {
+    let user_attrs = vec!["C.a:3", "D.a:0_00", "D.a:0_01", "D.a:0_02", "D.a:0_03", "B.d:6", "D.e:1", "D.d:6"];
+    let policy = "((((!D.a:0 | C.a:3) & !D.a:0) & B.d:6) & (D.c:2 | (D.e:1 & D.d:6)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_000_fail() {
+    let user_attrs = vec!["D.e:1", "D.a:0_01", "D.a:0_03", "B.d:6", "D.a:0_02"];
+    let policy = "((((!D.a:0 | C.a:3) & !D.a:0) & B.d:6) & (D.c:2 | (D.e:1 & D.d:6)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_001_ok() {
+    let user_attrs = vec!["A.d:3", "A.d:3", "C.b:0_00", "C.b:0_01"];
+    let policy = "((((A.d:3 & A.d:3) | A.a:6) | A.a:4) & !C.b:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_001_fail() {
+    let user_attrs = vec!["A.d:3"];
+    let policy = "((((A.d:3 & A.d:3) | A.a:6) | A.a:4) & !C.b:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_002_ok() {
+    let user_attrs = vec!["B.e:1"];
+    let policy = "((B.e:1 | (C.c:1 & (D.c:4 | (C.c:1 & B.e:1)))) | C.c:1)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_002_fail() {
+    let user_attrs = vec![];
+    let policy = "((B.e:1 | (C.c:1 & (D.c:4 | (C.c:1 & B.e:1)))) | C.c:1)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_003_ok() {
+    let user_attrs = vec!["B.d:0"];
+    let policy = "B.d:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_003_fail() {
+    let user_attrs = vec![];
+    let policy = "B.d:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_004_ok() {
+    let user_attrs = vec!["C.b:1_00", "C.b:1_01", "C.b:1_02", "C.b:1_03", "D.c:0_00", "D.c:0_01", "D.c:0_02"];
+    let policy = "(!C.b:1 & !D.c:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_004_fail() {
+    let user_attrs = vec!["C.b:1_02"];
+    let policy = "(!C.b:1 & !D.c:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_005_ok() {
+    let user_attrs = vec!["B.e:4_00", "B.e:4_01", "B.e:4_02", "A.b:1_00", "A.b:1_01", "A.b:1_02", "C.a:5"];
+    let policy = "((!B.e:4 & !A.b:1) & ((C.a:5 | D.c:0) | A.e:5))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_005_fail() {
+    let user_attrs = vec!["A.b:1_01", "B.e:4_01", "B.e:4_00", "A.b:1_00", "A.b:1_02"];
+    let policy = "((!B.e:4 & !A.b:1) & ((C.a:5 | D.c:0) | A.e:5))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_006_ok() {
+    let user_attrs = vec!["A.a:6"];
+    let policy = "((B.b:3 & ((((B.b:1 | (A.a:6 & B.b:1)) & C.d:4) & B.b:3) & A.a:4)) | A.a:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_006_fail() {
+    let user_attrs = vec![];
+    let policy = "((B.b:3 & ((((B.b:1 | (A.a:6 & B.b:1)) & C.d:4) & B.b:3) & A.a:4)) | A.a:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_007_ok() {
+    let user_attrs = vec!["C.b:0_00", "C.b:0_01", "C.b:0_02", "C.b:0_03"];
+    let policy = "!C.b:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_007_fail() {
+    let user_attrs = vec![];
+    let policy = "!C.b:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_008_ok() {
+    let user_attrs = vec!["C.c:5_00", "C.c:5_01", "C.c:5_02", "B.d:5"];
+    let policy = "((!C.c:5 | (!C.c:5 & B.d:5)) | B.b:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_008_fail() {
+    let user_attrs = vec![];
+    let policy = "((!C.c:5 | (!C.c:5 & B.d:5)) | B.b:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_009_ok() {
+    let user_attrs = vec!["D.d:4", "D.d:4", "A.d:0"];
+    let policy = "((D.d:4 & D.d:4) & ((D.d:4 | A.d:0) | D.d:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_009_fail() {
+    let user_attrs = vec!["A.d:0"];
+    let policy = "((D.d:4 & D.d:4) & ((D.d:4 | A.d:0) | D.d:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_010_ok() {
+    let user_attrs = vec!["C.e:6"];
+    let policy = "(((A.e:0 & A.e:0) | C.e:6) | C.e:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_010_fail() {
+    let user_attrs = vec![];
+    let policy = "(((A.e:0 & A.e:0) | C.e:6) | C.e:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_011_ok() {
+    let user_attrs = vec!["B.b:3_00"];
+    let policy = "(!B.b:3 | ((C.c:5 & C.c:5) & (B.e:1 | A.b:5)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_011_fail() {
+    let user_attrs = vec![];
+    let policy = "(!B.b:3 | ((C.c:5 & C.c:5) & (B.e:1 | A.b:5)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_012_ok() {
+    let user_attrs = vec!["B.e:5"];
+    let policy = "B.e:5";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_012_fail() {
+    let user_attrs = vec![];
+    let policy = "B.e:5";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_013_ok() {
+    let user_attrs = vec!["C.d:2_00", "C.d:2_01", "C.d:2_02"];
+    let policy = "!C.d:2";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_013_fail() {
+    let user_attrs = vec![];
+    let policy = "!C.d:2";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_014_ok() {
+    let user_attrs = vec!["A.c:6", "A.c:6", "A.c:6", "D.b:5_00", "D.b:5_01", "D.b:5_02"];
+    let policy = "(((A.c:6 & A.c:6) & A.c:6) & (!D.b:5 | (C.b:4 & C.b:4)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_014_fail() {
+    let user_attrs = vec!["A.c:6"];
+    let policy = "(((A.c:6 & A.c:6) & A.c:6) & (!D.b:5 | (C.b:4 & C.b:4)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_015_ok() {
+    let user_attrs = vec!["D.c:6"];
+    let policy = "(D.c:6 | D.c:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_015_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.c:6 | D.c:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_016_ok() {
+    let user_attrs = vec!["A.b:1", "A.b:1", "B.c:6"];
+    let policy = "((A.b:1 & (A.b:1 & B.c:6)) | ((C.b:5 | B.c:0) | B.c:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_016_fail() {
+    let user_attrs = vec!["B.c:6"];
+    let policy = "((A.b:1 & (A.b:1 & B.c:6)) | ((C.b:5 | B.c:0) | B.c:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_017_ok() {
+    let user_attrs = vec!["D.b:4", "C.e:1", "A.a:1", "C.d:5"];
+    let policy = "((((C.d:5 & C.e:1) & C.e:1) & C.d:5) | ((D.b:4 & ((C.e:1 | (A.a:1 & A.b:2)) & A.a:1)) & C.d:5))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_017_fail() {
+    let user_attrs = vec!["C.e:1"];
+    let policy = "((((C.d:5 & C.e:1) & C.e:1) & C.d:5) | ((D.b:4 & ((C.e:1 | (A.a:1 & A.b:2)) & A.a:1)) & C.d:5))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_018_ok() {
+    let user_attrs = vec!["C.e:1", "C.e:1", "C.e:1"];
+    let policy = "(C.e:1 & ((C.e:1 & C.e:1) | (A.e:5 | A.b:0)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_018_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.e:1 & ((C.e:1 & C.e:1) | (A.e:5 | A.b:0)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_019_ok() {
+    let user_attrs = vec!["C.b:3", "C.b:3"];
+    let policy = "(C.b:3 & C.b:3)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt0_reuse_generated_test_case_019_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.b:3 & C.b:3)";
+    assert_decryption_fail(user_attrs, policy);
+}
@@ -0,0 +1,336 @@
+use std::{collections::HashSet, vec};
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+};
+
+const USER_ID: &str = "TEST_USER_ID";
+
+fn prepare_test(user_attrs: &Vec<&str>, policy: &str) -> (Vec<String>, Vec<UserAttribute>, Policy) {
+    let policy = Policy::parse(policy).unwrap();
+    let user_attrs: Vec<UserAttribute> = user_attrs
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let mut auths: HashSet<String> = HashSet::new();
+    for ua in user_attrs.iter() {
+        auths.insert(ua.auth.clone());
+    }
+    for idx in 0..policy.len() {
+        auths.insert(policy.get(idx).0.auth.clone());
+    }
+    if auths.is_empty() {
+        panic!(
+            "Fatal error: cannot execute test case if both user attributes and policy are empty"
+        );
+    }
+    (auths.into_iter().collect(), user_attrs, policy)
+}
+
+fn test_scheme(user_attrs: Vec<&str>, policy: &str) -> (curve::Gt, Option<curve::Gt>) {
+    use abe_cubed::scheme::{Iota, Opt1, Scheme, Tau};
+    let (auths, user_attrs, policy) = prepare_test(&user_attrs, &policy);
+    let mut rng = ark_std::test_rng();
+    let scheme = Opt1::new();
+    let auths: Vec<&str> = auths.iter().map(|s| s as &str).collect();
+    let iota = Iota::new(&user_attrs);
+    let (msk, mpk) = scheme.setup(&mut rng, &auths);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let tau = Tau::new(&policy);
+    let (k_enc, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(user_attrs: Vec<&str>, policy: &str) {
+    let (k_enc, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(user_attrs: Vec<&str>, policy: &str) {
+    let (_, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_none());
+}
+
+// Auto-generated test cases with repeated literals
+
+#[test]
+fn opt1_reuse_generated_test_case_000_ok() {
+    let user_attrs = vec!["B.a:0", "C.a:3", "B.a:0"];
+    let policy = "(((C.a:3 | C.a:3) | B.a:0) & (((C.a:3 & ((C.e:0 & B.c:2) | B.a:0)) | C.a:3) | C.b:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_000_fail() {
+    let user_attrs = vec!["B.a:0"];
+    let policy = "(((C.a:3 | C.a:3) | B.a:0) & (((C.a:3 & ((C.e:0 & B.c:2) | B.a:0)) | C.a:3) | C.b:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_001_ok() {
+    let user_attrs = vec!["A.d:3"];
+    let policy = "A.d:3";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_001_fail() {
+    let user_attrs = vec![];
+    let policy = "A.d:3";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_002_ok() {
+    let user_attrs = vec!["B.b:4", "C.d:5"];
+    let policy = "((B.b:4 | (B.b:4 | ((D.c:6 & D.c:2) | !C.e:2))) & C.d:5)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_002_fail() {
+    let user_attrs = vec!["B.b:4"];
+    let policy = "((B.b:4 | (B.b:4 | ((D.c:6 & D.c:2) | !C.e:2))) & C.d:5)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_003_ok() {
+    let user_attrs = vec!["D.e:6"];
+    let policy = "(D.e:6 | D.e:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_003_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.e:6 | D.e:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_004_ok() {
+    let user_attrs = vec!["A.d:1", "A.c:2_00", "A.c:2_01", "A.c:2_02", "A.c:2_03"];
+    let policy = "((B.d:4 | A.d:1) & !A.c:2)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_004_fail() {
+    let user_attrs = vec!["A.d:1"];
+    let policy = "((B.d:4 | A.d:1) & !A.c:2)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_005_ok() {
+    let user_attrs = vec!["D.a:0"];
+    let policy = "D.a:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_005_fail() {
+    let user_attrs = vec![];
+    let policy = "D.a:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_006_ok() {
+    let user_attrs = vec!["B.c:4"];
+    let policy = "(B.c:4 | B.c:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_006_fail() {
+    let user_attrs = vec![];
+    let policy = "(B.c:4 | B.c:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_007_ok() {
+    let user_attrs = vec!["D.a:4", "D.a:4"];
+    let policy = "(D.a:4 | (D.a:4 & D.a:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_007_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.a:4 | (D.a:4 & D.a:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_008_ok() {
+    let user_attrs = vec!["D.c:6"];
+    let policy = "D.c:6";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_008_fail() {
+    let user_attrs = vec![];
+    let policy = "D.c:6";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_009_ok() {
+    let user_attrs = vec!["B.a:3"];
+    let policy = "((((B.a:4 | (B.a:3 & ((B.a:3 | B.a:3) & B.a:4))) & B.a:4) | B.a:3) | ((D.b:0 & A.a:4) & B.a:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_009_fail() {
+    let user_attrs = vec![];
+    let policy = "((((B.a:4 | (B.a:3 & ((B.a:3 | B.a:3) & B.a:4))) & B.a:4) | B.a:3) | ((D.b:0 & A.a:4) & B.a:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_010_ok() {
+    let user_attrs = vec!["D.d:5_00", "D.d:5_01", "D.d:5_02"];
+    let policy = "!D.d:5";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_010_fail() {
+    let user_attrs = vec![];
+    let policy = "!D.d:5";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_011_ok() {
+    let user_attrs = vec!["A.c:1"];
+    let policy = "(A.c:1 | A.c:1)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_011_fail() {
+    let user_attrs = vec![];
+    let policy = "(A.c:1 | A.c:1)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_012_ok() {
+    let user_attrs = vec!["B.a:4"];
+    let policy = "(C.b:2 | B.a:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_012_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.b:2 | B.a:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_013_ok() {
+    let user_attrs = vec!["B.e:1", "B.e:1"];
+    let policy = "(B.e:1 & B.e:1)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_013_fail() {
+    let user_attrs = vec![];
+    let policy = "(B.e:1 & B.e:1)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_014_ok() {
+    let user_attrs = vec!["C.a:6", "C.d:5_00", "C.d:5_00", "C.d:5_01"];
+    let policy = "(A.e:0 | ((C.a:6 & !C.d:5) & !C.d:5))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_014_fail() {
+    let user_attrs = vec!["C.d:5_00", "C.d:5_00", "C.d:5_01"];
+    let policy = "(A.e:0 | ((C.a:6 & !C.d:5) & !C.d:5))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_015_ok() {
+    let user_attrs = vec!["D.b:4", "D.b:4"];
+    let policy = "(((D.b:4 & D.b:4) | !A.a:3) | B.e:6)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_015_fail() {
+    let user_attrs = vec![];
+    let policy = "(((D.b:4 & D.b:4) | !A.a:3) | B.e:6)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_016_ok() {
+    let user_attrs = vec!["B.a:0"];
+    let policy = "B.a:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_016_fail() {
+    let user_attrs = vec![];
+    let policy = "B.a:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_017_ok() {
+    let user_attrs = vec!["B.e:0_00", "B.e:0_01", "C.c:0"];
+    let policy = "((!B.e:0 | (C.c:1 & (C.c:1 | ((!C.d:1 & (C.c:1 | (B.d:5 | A.d:3))) & D.c:3)))) & C.c:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_017_fail() {
+    let user_attrs = vec!["C.c:0"];
+    let policy = "((!B.e:0 | (C.c:1 & (C.c:1 | ((!C.d:1 & (C.c:1 | (B.d:5 | A.d:3))) & D.c:3)))) & C.c:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_018_ok() {
+    let user_attrs = vec!["D.b:0"];
+    let policy = "(D.a:4 | D.b:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_018_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.a:4 | D.b:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_019_ok() {
+    let user_attrs = vec!["A.c:6", "D.c:3", "D.c:3"];
+    let policy = "(((D.c:3 & (D.c:3 & (A.b:1 | (A.b:1 & (C.e:6 | D.c:3))))) & A.c:6) | (A.c:6 & (D.c:3 & D.c:3)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt1_reuse_generated_test_case_019_fail() {
+    let user_attrs = vec!["A.c:6"];
+    let policy = "(((D.c:3 & (D.c:3 & (A.b:1 | (A.b:1 & (C.e:6 | D.c:3))))) & A.c:6) | (A.c:6 & (D.c:3 & D.c:3)))";
+    assert_decryption_fail(user_attrs, policy);
+}
@@ -0,0 +1,336 @@
+use std::{collections::HashSet, vec};
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+};
+
+const USER_ID: &str = "TEST_USER_ID";
+
+fn prepare_test(user_attrs: &Vec<&str>, policy: &str) -> (Vec<String>, Vec<UserAttribute>, Policy) {
+    let policy = Policy::parse(policy).unwrap();
+    let user_attrs: Vec<UserAttribute> = user_attrs
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let mut auths: HashSet<String> = HashSet::new();
+    for ua in user_attrs.iter() {
+        auths.insert(ua.auth.clone());
+    }
+    for idx in 0..policy.len() {
+        auths.insert(policy.get(idx).0.auth.clone());
+    }
+    if auths.is_empty() {
+        panic!(
+            "Fatal error: cannot execute test case if both user attributes and policy are empty"
+        );
+    }
+    (auths.into_iter().collect(), user_attrs, policy)
+}
+
+fn test_scheme(user_attrs: Vec<&str>, policy: &str) -> (curve::Gt, Option<curve::Gt>) {
+    use abe_cubed::scheme::{Iota, Opt2, Scheme, Tau};
+    let (auths, user_attrs, policy) = prepare_test(&user_attrs, &policy);
+    let mut rng = ark_std::test_rng();
+    let scheme = Opt2::new();
+    let auths: Vec<&str> = auths.iter().map(|s| s as &str).collect();
+    let iota = Iota::new(&user_attrs);
+    let (msk, mpk) = scheme.setup(&mut rng, &auths);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let tau = Tau::new(&policy);
+    let (k_enc, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(user_attrs: Vec<&str>, policy: &str) {
+    let (k_enc, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(user_attrs: Vec<&str>, policy: &str) {
+    let (_, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_none());
+}
+
+// Auto-generated test cases with repeated literals
+
+#[test]
+fn opt2_reuse_generated_test_case_000_ok() {
+    let user_attrs = vec!["A.d:1"];
+    let policy = "A.d:1";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_000_fail() {
+    let user_attrs = vec![];
+    let policy = "A.d:1";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_001_ok() {
+    let user_attrs = vec!["B.b:1"];
+    let policy = "B.b:1";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_001_fail() {
+    let user_attrs = vec![];
+    let policy = "B.b:1";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_002_ok() {
+    let user_attrs = vec!["B.e:5"];
+    let policy = "B.e:5";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_002_fail() {
+    let user_attrs = vec![];
+    let policy = "B.e:5";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_003_ok() {
+    let user_attrs = vec!["A.a:6"];
+    let policy = "(A.a:6 | (A.a:6 & (A.a:6 & (B.c:1 & (D.b:1 | B.c:5)))))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_003_fail() {
+    let user_attrs = vec![];
+    let policy = "(A.a:6 | (A.a:6 & (A.a:6 & (B.c:1 & (D.b:1 | B.c:5)))))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_004_ok() {
+    let user_attrs = vec!["C.a:1_00", "C.a:1_01", "C.a:1_02", "A.c:0", "C.c:4", "A.d:1"];
+    let policy = "((((B.e:4 | !C.a:1) | !C.a:1) | B.a:3) & (A.c:0 & (!C.a:1 | (C.c:4 & A.d:1))))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_004_fail() {
+    let user_attrs = vec!["C.a:1_02", "C.a:1_00", "C.a:1_01", "C.c:4"];
+    let policy = "((((B.e:4 | !C.a:1) | !C.a:1) | B.a:3) & (A.c:0 & (!C.a:1 | (C.c:4 & A.d:1))))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_005_ok() {
+    let user_attrs = vec!["A.b:0", "C.d:2_00", "C.d:2_01"];
+    let policy = "(A.b:0 & ((C.b:4 | (((!C.d:2 & C.b:2) | B.a:6) | C.b:4)) | !C.d:2))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_005_fail() {
+    let user_attrs = vec!["C.d:2_01"];
+    let policy = "(A.b:0 & ((C.b:4 | (((!C.d:2 & C.b:2) | B.a:6) | C.b:4)) | !C.d:2))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_006_ok() {
+    let user_attrs = vec!["C.d:6"];
+    let policy = "((D.c:1 & (B.b:2 | C.a:6)) | ((((B.b:2 | B.b:2) | C.d:2) | C.d:6) | C.d:6))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_006_fail() {
+    let user_attrs = vec![];
+    let policy = "((D.c:1 & (B.b:2 | C.a:6)) | ((((B.b:2 | B.b:2) | C.d:2) | C.d:6) | C.d:6))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_007_ok() {
+    let user_attrs = vec!["C.e:3"];
+    let policy = "(C.e:3 | (((C.e:3 & B.d:0) | ((C.c:0 & C.e:3) & !D.b:6)) | B.e:6))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_007_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.e:3 | (((C.e:3 & B.d:0) | ((C.c:0 & C.e:3) & !D.b:6)) | B.e:6))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_008_ok() {
+    let user_attrs = vec!["C.e:3", "C.b:1", "D.d:6"];
+    let policy = "((C.b:1 | (C.e:3 & C.b:1)) & (D.d:6 | (((D.d:6 & C.b:1) | C.b:1) | D.d:6)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_008_fail() {
+    let user_attrs = vec![];
+    let policy = "((C.b:1 | (C.e:3 & C.b:1)) & (D.d:6 | (((D.d:6 & C.b:1) | C.b:1) | D.d:6)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_009_ok() {
+    let user_attrs = vec!["B.c:1", "B.b:5", "B.d:3", "B.b:5"];
+    let policy = "((((B.b:2 & (A.c:2 | (B.b:2 & ((A.c:2 | B.b:2) & A.c:2)))) & A.e:6) | B.c:1) & (((B.b:5 | B.b:5) & B.d:3) & B.b:5))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_009_fail() {
+    let user_attrs = vec!["B.d:3", "B.b:5", "B.b:5"];
+    let policy = "((((B.b:2 & (A.c:2 | (B.b:2 & ((A.c:2 | B.b:2) & A.c:2)))) & A.e:6) | B.c:1) & (((B.b:5 | B.b:5) & B.d:3) & B.b:5))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_010_ok() {
+    let user_attrs = vec!["B.d:6", "D.c:6_00", "B.d:6", "B.d:6", "B.a:2"];
+    let policy = "(((B.d:6 & (!D.c:6 & ((B.d:6 & (B.a:2 | B.d:6)) | B.d:6))) & B.a:2) | (B.d:6 & !D.c:6))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_010_fail() {
+    let user_attrs = vec!["D.c:6_00"];
+    let policy = "(((B.d:6 & (!D.c:6 & ((B.d:6 & (B.a:2 | B.d:6)) | B.d:6))) & B.a:2) | (B.d:6 & !D.c:6))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_011_ok() {
+    let user_attrs = vec!["A.c:5_00", "A.c:5_01"];
+    let policy = "(!A.c:5 | !A.c:5)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_011_fail() {
+    let user_attrs = vec![];
+    let policy = "(!A.c:5 | !A.c:5)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_012_ok() {
+    let user_attrs = vec!["D.c:5", "D.c:5", "D.c:5", "D.c:5", "D.c:5"];
+    let policy = "((D.c:5 | D.c:5) & (D.c:5 | (((D.c:5 & ((D.c:5 | D.c:5) | D.c:5)) & D.c:5) & D.c:5)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_012_fail() {
+    let user_attrs = vec![];
+    let policy = "((D.c:5 | D.c:5) & (D.c:5 | (((D.c:5 & ((D.c:5 | D.c:5) | D.c:5)) & D.c:5) & D.c:5)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_013_ok() {
+    let user_attrs = vec!["A.c:2"];
+    let policy = "((A.c:2 | (A.c:2 | A.c:2)) | (A.c:2 & (!D.e:6 & (!D.e:6 & A.c:2))))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_013_fail() {
+    let user_attrs = vec![];
+    let policy = "((A.c:2 | (A.c:2 | A.c:2)) | (A.c:2 & (!D.e:6 & (!D.e:6 & A.c:2))))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_014_ok() {
+    let user_attrs = vec!["A.e:2"];
+    let policy = "(C.e:0 | (((A.a:5 | (((C.e:0 & ((!D.e:2 | (((!A.d:4 & A.a:5) | !B.a:3) & B.c:0)) & !A.d:4)) & !C.b:2) & C.e:1)) & B.e:3) | A.e:2))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_014_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.e:0 | (((A.a:5 | (((C.e:0 & ((!D.e:2 | (((!A.d:4 & A.a:5) | !B.a:3) & B.c:0)) & !A.d:4)) & !C.b:2) & C.e:1)) & B.e:3) | A.e:2))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_015_ok() {
+    let user_attrs = vec!["D.e:2_00", "D.e:2_01", "D.e:2_02", "A.e:4", "A.e:4"];
+    let policy = "((A.e:6 | !D.e:2) & ((A.e:4 | !D.e:2) & A.e:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_015_fail() {
+    let user_attrs = vec!["D.e:2_02", "D.e:2_00"];
+    let policy = "((A.e:6 | !D.e:2) & ((A.e:4 | !D.e:2) & A.e:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_016_ok() {
+    let user_attrs = vec!["D.a:4"];
+    let policy = "(A.e:5 | D.a:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_016_fail() {
+    let user_attrs = vec![];
+    let policy = "(A.e:5 | D.a:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_017_ok() {
+    let user_attrs = vec!["C.d:4", "C.b:3_00"];
+    let policy = "((C.d:4 & ((C.d:4 | D.b:3) | !C.b:3)) | (D.a:1 & D.a:0))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_017_fail() {
+    let user_attrs = vec!["C.b:3_00"];
+    let policy = "((C.d:4 & ((C.d:4 | D.b:3) | !C.b:3)) | (D.a:1 & D.a:0))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_018_ok() {
+    let user_attrs = vec!["C.a:1", "C.c:3", "C.c:3", "C.c:4"];
+    let policy = "(C.a:1 & ((C.c:3 & (C.c:3 | D.b:1)) & C.c:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_018_fail() {
+    let user_attrs = vec!["C.a:1", "C.c:3", "C.c:3"];
+    let policy = "(C.a:1 & ((C.c:3 & (C.c:3 | D.b:1)) & C.c:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_019_ok() {
+    let user_attrs = vec!["C.e:3", "C.e:3"];
+    let policy = "(C.e:3 & C.e:3)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt2_reuse_generated_test_case_019_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.e:3 & C.e:3)";
+    assert_decryption_fail(user_attrs, policy);
+}
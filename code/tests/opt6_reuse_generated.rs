@@ -0,0 +1,336 @@
+use std::{collections::HashSet, vec};
+
+use abe_cubed::{
+    curve,
+    policy::{Policy, UserAttribute},
+};
+
+const USER_ID: &str = "TEST_USER_ID";
+
+fn prepare_test(user_attrs: &Vec<&str>, policy: &str) -> (Vec<String>, Vec<UserAttribute>, Policy) {
+    let policy = Policy::parse(policy).unwrap();
+    let user_attrs: Vec<UserAttribute> = user_attrs
+        .iter()
+        .map(|ua| UserAttribute::parse(ua).unwrap())
+        .collect();
+    let mut auths: HashSet<String> = HashSet::new();
+    for ua in user_attrs.iter() {
+        auths.insert(ua.auth.clone());
+    }
+    for idx in 0..policy.len() {
+        auths.insert(policy.get(idx).0.auth.clone());
+    }
+    if auths.is_empty() {
+        panic!(
+            "Fatal error: cannot execute test case if both user attributes and policy are empty"
+        );
+    }
+    (auths.into_iter().collect(), user_attrs, policy)
+}
+
+fn test_scheme(user_attrs: Vec<&str>, policy: &str) -> (curve::Gt, Option<curve::Gt>) {
+    use abe_cubed::scheme::{Iota, Opt6, Scheme, Tau};
+    let (auths, user_attrs, policy) = prepare_test(&user_attrs, &policy);
+    let mut rng = ark_std::test_rng();
+    let scheme = Opt6::new();
+    let auths: Vec<&str> = auths.iter().map(|s| s as &str).collect();
+    let iota = Iota::new(&user_attrs);
+    let (msk, mpk) = scheme.setup(&mut rng, &auths);
+    let usk = scheme.keygen(&mut rng, USER_ID, &msk, &user_attrs, &iota);
+    let tau = Tau::new(&policy);
+    let (k_enc, ct) = scheme.encrypt(&mut rng, &mpk, &policy, &tau);
+    let k_dec = scheme.decrypt(&usk, USER_ID, &iota, &tau, &policy, &ct);
+    (k_enc, k_dec)
+}
+
+fn assert_decryption_ok(user_attrs: Vec<&str>, policy: &str) {
+    let (k_enc, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+fn assert_decryption_fail(user_attrs: Vec<&str>, policy: &str) {
+    let (_, k_dec) = test_scheme(user_attrs, policy);
+    assert!(k_dec.is_none());
+}
+
+// Auto-generated test cases with repeated literals
+
+#[test]
+fn opt6_reuse_generated_test_case_000_ok() {
+    let user_attrs = vec!["C.b:2", "C.c:6", "C.b:2", "C.b:2"];
+    let policy = "(C.b:2 & (C.c:6 & (C.b:2 & C.b:2)))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_000_fail() {
+    let user_attrs = vec!["C.b:2", "C.b:2", "C.b:2"];
+    let policy = "(C.b:2 & (C.c:6 & (C.b:2 & C.b:2)))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_001_ok() {
+    let user_attrs = vec!["C.b:4_00", "C.b:4_01", "C.b:4_02", "C.b:4_00", "C.b:4_01"];
+    let policy = "(!C.b:4 & !C.b:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_001_fail() {
+    let user_attrs = vec![];
+    let policy = "(!C.b:4 & !C.b:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_002_ok() {
+    let user_attrs = vec!["C.e:3_00", "C.e:3_01", "C.e:3_02", "C.e:3_03"];
+    let policy = "(!C.e:3 | (!C.e:3 & (!C.e:3 | (!C.e:3 | A.d:4))))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_002_fail() {
+    let user_attrs = vec![];
+    let policy = "(!C.e:3 | (!C.e:3 & (!C.e:3 | (!C.e:3 | A.d:4))))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_003_ok() {
+    let user_attrs = vec!["C.e:0", "D.a:1", "D.a:1", "D.d:6_00", "D.d:6_01", "D.d:6_02", "D.d:6_03", "B.e:5_00", "B.e:5_01", "B.e:5_02", "C.a:1"];
+    let policy = "((C.e:0 & ((D.a:1 | C.e:1) | C.a:1)) & ((A.d:2 | (B.e:3 | (D.a:1 & (!D.d:6 & !B.e:5)))) & C.a:1))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_003_fail() {
+    let user_attrs = vec!["D.a:1", "D.d:6_01", "D.d:6_00", "C.a:1", "C.e:0"];
+    let policy = "((C.e:0 & ((D.a:1 | C.e:1) | C.a:1)) & ((A.d:2 | (B.e:3 | (D.a:1 & (!D.d:6 & !B.e:5)))) & C.a:1))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_004_ok() {
+    let user_attrs = vec!["C.a:6", "A.a:5", "A.a:5", "A.a:0"];
+    let policy = "(((A.a:0 & A.a:5) | (C.a:6 & (A.a:5 & (A.a:5 & A.a:0)))) | !C.d:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_004_fail() {
+    let user_attrs = vec![];
+    let policy = "(((A.a:0 & A.a:5) | (C.a:6 & (A.a:5 & (A.a:5 & A.a:0)))) | !C.d:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_005_ok() {
+    let user_attrs = vec!["C.d:1", "C.e:3", "C.e:3"];
+    let policy = "((C.d:1 & C.e:3) & C.e:3)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_005_fail() {
+    let user_attrs = vec!["C.e:3", "C.e:3"];
+    let policy = "((C.d:1 & C.e:3) & C.e:3)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_006_ok() {
+    let user_attrs = vec!["C.b:3", "A.d:3"];
+    let policy = "((C.b:3 | (!A.a:2 & C.b:3)) & A.d:3)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_006_fail() {
+    let user_attrs = vec!["C.b:3"];
+    let policy = "((C.b:3 | (!A.a:2 & C.b:3)) & A.d:3)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_007_ok() {
+    let user_attrs = vec!["D.c:2", "C.e:5"];
+    let policy = "(D.c:2 & C.e:5)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_007_fail() {
+    let user_attrs = vec!["D.c:2"];
+    let policy = "(D.c:2 & C.e:5)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_008_ok() {
+    let user_attrs = vec!["B.c:2", "C.d:2_00"];
+    let policy = "((B.a:1 | B.c:2) & !C.d:2)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_008_fail() {
+    let user_attrs = vec!["C.d:2_00"];
+    let policy = "((B.a:1 | B.c:2) & !C.d:2)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_009_ok() {
+    let user_attrs = vec!["A.e:3", "A.e:3", "A.e:3", "A.c:6_00", "A.c:6_01", "B.c:4"];
+    let policy = "((A.e:3 & ((A.e:3 & A.e:3) & !A.c:6)) & ((!A.c:6 & (!A.c:6 | C.b:0)) | B.c:4))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_009_fail() {
+    let user_attrs = vec!["A.e:3", "A.e:3"];
+    let policy = "((A.e:3 & ((A.e:3 & A.e:3) & !A.c:6)) & ((!A.c:6 & (!A.c:6 | C.b:0)) | B.c:4))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_010_ok() {
+    let user_attrs = vec!["D.b:0"];
+    let policy = "D.b:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_010_fail() {
+    let user_attrs = vec![];
+    let policy = "D.b:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_011_ok() {
+    let user_attrs = vec!["B.a:1_00", "B.a:1_01", "A.d:2_00", "A.d:2_01", "A.d:2_02", "A.d:2_03", "B.d:1_00", "B.d:1_01", "B.d:1_02"];
+    let policy = "((((!B.c:0 | C.e:4) | !B.c:0) | !B.e:6) | ((!B.a:1 & ((!B.a:1 & (A.a:1 & ((C.b:0 | C.b:0) | A.a:1))) | !A.d:2)) & !B.d:1))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_011_fail() {
+    let user_attrs = vec!["B.a:1_00", "B.a:1_01", "B.d:1_01"];
+    let policy = "((((!B.c:0 | C.e:4) | !B.c:0) | !B.e:6) | ((!B.a:1 & ((!B.a:1 & (A.a:1 & ((C.b:0 | C.b:0) | A.a:1))) | !A.d:2)) & !B.d:1))";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_012_ok() {
+    let user_attrs = vec!["A.e:4", "A.e:4"];
+    let policy = "(A.e:4 & A.e:4)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_012_fail() {
+    let user_attrs = vec![];
+    let policy = "(A.e:4 & A.e:4)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_013_ok() {
+    let user_attrs = vec!["D.d:0"];
+    let policy = "D.d:0";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_013_fail() {
+    let user_attrs = vec![];
+    let policy = "D.d:0";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_014_ok() {
+    let user_attrs = vec!["D.e:1"];
+    let policy = "(D.e:1 | D.e:1)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_014_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.e:1 | D.e:1)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_015_ok() {
+    let user_attrs = vec!["C.d:0"];
+    let policy = "(((C.d:0 & (B.c:2 | (B.c:2 | B.c:2))) | C.e:3) | C.d:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_015_fail() {
+    let user_attrs = vec![];
+    let policy = "(((C.d:0 & (B.c:2 | (B.c:2 | B.c:2))) | C.e:3) | C.d:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_016_ok() {
+    let user_attrs = vec!["C.d:3"];
+    let policy = "(C.d:3 | C.d:3)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_016_fail() {
+    let user_attrs = vec![];
+    let policy = "(C.d:3 | C.d:3)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_017_ok() {
+    let user_attrs = vec!["D.b:6"];
+    let policy = "D.b:6";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_017_fail() {
+    let user_attrs = vec![];
+    let policy = "D.b:6";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_018_ok() {
+    let user_attrs = vec!["D.e:0"];
+    let policy = "((((D.e:0 & !B.d:6) & D.e:0) | (D.e:0 & !B.d:6)) | D.e:0)";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_018_fail() {
+    let user_attrs = vec![];
+    let policy = "((((D.e:0 & !B.d:6) & D.e:0) | (D.e:0 & !B.d:6)) | D.e:0)";
+    assert_decryption_fail(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_019_ok() {
+    let user_attrs = vec!["D.d:5"];
+    let policy = "(D.d:5 | (D.d:5 | D.d:5))";
+    assert_decryption_ok(user_attrs, policy);
+}
+
+#[test]
+fn opt6_reuse_generated_test_case_019_fail() {
+    let user_attrs = vec![];
+    let policy = "(D.d:5 | (D.d:5 | D.d:5))";
+    assert_decryption_fail(user_attrs, policy);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, RetrievalKey, Scheme, UserKey},
+};
+use common::{for_each_scheme, parse_attrs, POLICIES, USER_ATTRS, USER_ID};
+
+fn test_outsourcing(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&USER_ATTRS))
+        .unwrap();
+    let (sk, rk) = abe.transformation_key(&mut rng, &usk);
+
+    for policy in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let transformed = abe.transform(&sk, &ct).unwrap();
+        // the server learns nothing about the key
+        assert!(!curve::Gt::eq(&transformed.k, &k_enc));
+        assert!(curve::Gt::eq(&rk.retrieve(&transformed), &k_enc));
+    }
+
+    let policy = Policy::parse("A.a:2 | B.c:1").unwrap();
+    let (_, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    assert!(abe.transform(&sk, &ct).is_none());
+
+    // retrieving needs the z of this transformation key
+    let policy = Policy::parse(POLICIES[0]).unwrap();
+    let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let transformed = abe.transform(&sk, &ct).unwrap();
+    let (_, other_rk) = abe.transformation_key(&mut rng, &usk);
+    assert!(!curve::Gt::eq(&other_rk.retrieve(&transformed), &k_enc));
+    let wrong_rk = RetrievalKey {
+        z: rk.z + curve::ScalarField::from(1),
+    };
+    assert!(!curve::Gt::eq(&wrong_rk.retrieve(&transformed), &k_enc));
+
+    // the transformation key alone is no user key
+    let blinded = UserKey {
+        gid: String::from(USER_ID),
+        iota: sk.iota,
+        usk: sk.tk.usk,
+    };
+    let k_dec = abe.decrypt(&blinded, &ct);
+    assert!(!k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+#[test]
+fn outsourcing() {
+    for_each_scheme!(test_outsourcing);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme},
+};
+use common::{parse_attrs, USER_ID};
+
+const POLICIES: [&str; 3] = ["A.a:0 | B.b:0", "(A.a:0 & !A.a:1) | !B.b:1", "B.b:0"];
+const EXTRA: [&str; 2] = ["!A.status:terminated", "B.b:0 | C.c:0"];
+
+fn test_update_policy(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A", "B", "C"]);
+    let usk = abe
+        .keygen(
+            &mut rng,
+            USER_ID,
+            &msk,
+            &parse_attrs(&["A.a:0", "A.status:active", "B.b:0"]),
+        )
+        .unwrap();
+    // satisfies all of the policies but none of the extra ones
+    let excluded_usk = abe
+        .keygen(
+            &mut rng,
+            "EXCLUDED_USER_ID",
+            &msk,
+            &parse_attrs(&["A.a:0", "A.status:terminated", "B.b:0", "B.b:2"]),
+        )
+        .unwrap();
+    let excluded_usk_b = abe
+        .keygen(
+            &mut rng,
+            "EXCLUDED_B_USER_ID",
+            &msk,
+            &parse_attrs(&["B.b:0", "A.status:terminated"]),
+        )
+        .unwrap();
+
+    for policy in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let k_dec = abe.decrypt(&excluded_usk_b, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+
+        let ct = abe
+            .update_policy(&mut rng, &mpk, &ct, &Policy::parse(EXTRA[0]).unwrap())
+            .unwrap();
+        assert_eq!(ct.policy.len(), policy.len() + 1);
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+        assert!(abe.decrypt(&excluded_usk, &ct).is_none());
+        assert!(abe.decrypt(&excluded_usk_b, &ct).is_none());
+
+        // updates can be chained
+        let ct = abe
+            .update_policy(&mut rng, &mpk, &ct, &Policy::parse(EXTRA[1]).unwrap())
+            .unwrap();
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+        assert!(abe.decrypt(&excluded_usk_b, &ct).is_none());
+    }
+}
+
+#[test]
+fn update_policy() {
+    test_update_policy(Opt0::new());
+    test_update_policy(Opt1::new());
+    test_update_policy(Opt2::new());
+}
+
+#[test]
+fn update_policy_after_epoch_change() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt0::new());
+    let (mut msk, mut mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let policy = Policy::parse("A.a:0 | B.b:0").unwrap();
+    let (_, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    let extra = Policy::parse("!A.status:terminated").unwrap();
+    assert!(abe.update_policy(&mut rng, &mpk, &ct, &extra).is_err());
+    // the rows of A cannot be given fresh randomness under the old public key anymore
+    let extra = Policy::parse("!B.status:terminated").unwrap();
+    assert!(abe.update_policy(&mut rng, &mpk, &ct, &extra).is_err());
+    let policy = Policy::parse("B.b:0 | B.b:1").unwrap();
+    let (_, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    assert!(abe.update_policy(&mut rng, &mpk, &ct, &extra).is_ok());
+}
+
+#[test]
+fn update_policy_rerandomizes_rows() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt0::new());
+    let (_, mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let policy = Policy::parse("A.a:0 | (A.a:1 & !B.b:0)").unwrap();
+    let (_, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let extra = Policy::parse("!A.status:terminated").unwrap();
+    let updated = abe.update_policy(&mut rng, &mpk, &ct, &extra).unwrap();
+    for j in 0..policy.len() {
+        assert_ne!(ct.ct.c_2_vec[j], updated.ct.c_2_vec[j]);
+        assert_ne!(ct.ct.c_4_vec[j], updated.ct.c_4_vec[j]);
+    }
+}
+
+fn test_update_policy_unsupported(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (_, mpk) = abe.setup(&mut rng, &vec!["A"]);
+    let policy = Policy::parse("A.a:0").unwrap();
+    let (_, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let extra = Policy::parse("!A.status:terminated").unwrap();
+    assert!(abe.update_policy(&mut rng, &mpk, &ct, &extra).is_err());
+}
+
+#[test]
+fn update_policy_unsupported() {
+    test_update_policy_unsupported(Opt3::new());
+    test_update_policy_unsupported(Opt4::new());
+    test_update_policy_unsupported(Opt5::new());
+    test_update_policy_unsupported(Opt6::new());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Opt6, RerandomizeMode, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, POLICIES, USER_ATTRS, USER_ID};
+
+fn test_rerandomize(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (mut msk, mut mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let mut usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&USER_ATTRS))
+        .unwrap();
+    abe.rerandomize_key(&mut rng, &mpk, &mut usk).unwrap();
+    abe.keygen_extend(&mut rng, &msk, &mut usk, &parse_attrs(&["A.b:2"]))
+        .unwrap();
+    let update = abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    abe.update_usk(&mut usk, &update.update_key(USER_ID))
+        .unwrap();
+    abe.rerandomize_key(&mut rng, &mpk, &mut usk).unwrap();
+
+    for policy in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, mut ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let one = abe
+            .rerandomize(&mut rng, &mpk, &mut ct, RerandomizeMode::KeepKey)
+            .unwrap();
+        assert!(curve::Gt::eq(&one, &curve::Gt::from(1)));
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+
+        let factor = abe
+            .rerandomize(&mut rng, &mpk, &mut ct, RerandomizeMode::BlindKey)
+            .unwrap();
+        assert!(!curve::Gt::eq(&factor, &curve::Gt::from(1)));
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&(k_enc * factor), &k)));
+    }
+
+    // the ciphertext has to be rerandomized under the epochs it was encrypted with
+    let policy = Policy::parse("A.a:0").unwrap();
+    let (_, mut ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    assert!(abe
+        .rerandomize(&mut rng, &mpk, &mut ct, RerandomizeMode::KeepKey)
+        .is_err());
+}
+
+#[test]
+fn rerandomize() {
+    for_each_scheme!(test_rerandomize);
+}
+
+#[test]
+fn rerandomize_changes_components() {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(Opt6::new());
+    let (msk, mpk) = abe.setup(&mut rng, &vec!["A"]);
+    let mut usk = abe
+        .keygen(&mut rng, USER_ID, &msk, &parse_attrs(&["A.a:0"]))
+        .unwrap();
+    let k_1_1_vec = usk.usk.get_partial_key("A").unwrap().k_1_1_vec.clone();
+    let k_6 = usk.usk.get_partial_key("A").unwrap().k_6;
+    abe.rerandomize_key(&mut rng, &mpk, &mut usk).unwrap();
+    let partial_usk = usk.usk.get_partial_key("A").unwrap();
+    assert!(k_1_1_vec
+        .iter()
+        .zip(partial_usk.k_1_1_vec.iter())
+        .all(|(old, new)| old != new));
+    assert!(k_6 != partial_usk.k_6);
+
+    let policy = Policy::parse("A.a:0 & !A.a:1").unwrap();
+    let (_, mut ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let c_1_vec = ct.ct.c_1_vec.clone();
+    let c_5_vec = ct.ct.c_5_vec.clone();
+    abe.rerandomize(&mut rng, &mpk, &mut ct, RerandomizeMode::KeepKey)
+        .unwrap();
+    assert!(c_1_vec
+        .iter()
+        .zip(ct.ct.c_1_vec.iter())
+        .all(|(old, new)| old != new));
+    assert!(c_5_vec[1] != ct.ct.c_5_vec[1]);
+}
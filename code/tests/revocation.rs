@@ -0,0 +1,78 @@
+mod common;
+
+use abe_cubed::{
+    curve,
+    policy::Policy,
+    scheme::{Abe, Scheme},
+};
+use common::{for_each_scheme, parse_attrs, USER_ID};
+
+const REVOKED_ID: &str = "REVOKED_USER_ID";
+const USER_ATTRS: [&str; 3] = ["A.a:0", "A.b:1", "B.c:0"];
+
+const POLICIES: [&str; 3] = ["A.a:0 & B.c:0", "!A.a:1 & B.c:0", "A.b:1 | !A.b:0"];
+
+fn test_revocation(scheme: impl Scheme) {
+    let mut rng = ark_std::test_rng();
+    let abe = Abe::new(scheme);
+    let (mut msk, mut mpk) = abe.setup(&mut rng, &vec!["A", "B"]);
+    let user_attrs = parse_attrs(&USER_ATTRS);
+    let mut revoked_usk = abe.keygen(&mut rng, REVOKED_ID, &msk, &user_attrs).unwrap();
+    let mut usk = abe.keygen(&mut rng, USER_ID, &msk, &user_attrs).unwrap();
+    let old_policy = Policy::parse(POLICIES[0]).unwrap();
+    let (old_k_enc, old_ct) = abe.encrypt(&mut rng, &mpk, &old_policy);
+    let k_dec = abe.decrypt(&usk, &old_ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&old_k_enc, &k)));
+
+    let update = abe.rotate_epoch(&mut rng, "A", &mut msk, &mut mpk).unwrap();
+    let uk = update.update_key(USER_ID);
+    abe.update_usk(&mut usk, &uk).unwrap();
+    assert!(abe.update_usk(&mut usk, &uk).is_err());
+    assert!(abe.rotate_epoch(&mut rng, "Z", &mut msk, &mut mpk).is_err());
+
+    // the updated key does not keep the old epoch, while a key which was not updated still
+    // decrypts the ciphertexts of that epoch
+    assert!(abe.decrypt(&usk, &old_ct).is_none());
+    let k_dec = abe.decrypt(&revoked_usk, &old_ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&old_k_enc, &k)));
+
+    for policy in POLICIES {
+        let policy = Policy::parse(policy).unwrap();
+        let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+        let k_dec = abe.decrypt(&usk, &ct);
+        assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+        assert!(abe.decrypt(&revoked_usk, &ct).is_none());
+    }
+
+    // authorities which did not move to a new epoch are not affected
+    let policy = Policy::parse("B.c:0 & !B.c:1").unwrap();
+    let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let k_dec = abe.decrypt(&revoked_usk, &ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+
+    let stale = abe.keygen_extend(&mut rng, &msk, &mut revoked_usk, &parse_attrs(&["A.a:3"]));
+    assert!(stale.is_err());
+
+    // update keys are bound to the GID they were issued for
+    abe.update_usk(&mut revoked_usk, &uk).unwrap();
+    let policy = Policy::parse(POLICIES[0]).unwrap();
+    let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+    let k_dec = abe.decrypt(&revoked_usk, &ct);
+    assert!(!k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+
+    // new keys are issued for the current epoch
+    let mut new_usk = abe
+        .keygen(&mut rng, REVOKED_ID, &msk, &parse_attrs(&["A.a:0"]))
+        .unwrap();
+    let k_dec = abe.decrypt(&new_usk, &ct);
+    assert!(k_dec.is_none());
+    abe.keygen_extend(&mut rng, &msk, &mut new_usk, &parse_attrs(&["B.c:0"]))
+        .unwrap();
+    let k_dec = abe.decrypt(&new_usk, &ct);
+    assert!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)));
+}
+
+#[test]
+fn epoch_revocation() {
+    for_each_scheme!(test_revocation);
+}
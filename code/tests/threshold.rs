@@ -0,0 +1,132 @@
+mod common;
+
+use abe_cubed::{
+    curve::{self, ScalarField},
+    policy::Policy,
+    scheme::{Authority, MpkDirectory, Scheme, ThresholdAuthority},
+};
+use common::{for_each_scheme, parse_attrs, AUTHORITY_POLICIES, USER_ID};
+
+fn test_threshold<S: Scheme>(scheme: S) {
+    let mut rng = ark_std::test_rng();
+    let auth_a = ThresholdAuthority::<S>::setup(&mut rng, "A", 2, 3).unwrap();
+    let auth_b = Authority::setup(&mut rng, scheme, "B");
+    let mut directory = MpkDirectory::<S>::new();
+    directory
+        .publish(auth_a.public_key(), &auth_a.prove_public_key(&mut rng))
+        .unwrap();
+    directory
+        .publish(auth_b.public_key(), &auth_b.prove_public_key(&mut rng))
+        .unwrap();
+    let mpk = directory.mpk();
+    let abe = &auth_b.abe;
+
+    // any two of the three servers issue the same partial key
+    let attrs_a = parse_attrs(&["A.a:0", "A.b:0"]);
+    for servers in [vec![1, 2], vec![2, 3], vec![3, 1], vec![1, 2, 3]] {
+        let shares = servers
+            .iter()
+            .map(|&i| {
+                let server = auth_a.server(i).unwrap();
+                server.keygen(&mut rng, USER_ID, &attrs_a).unwrap()
+            })
+            .collect();
+        let issued_a = abe.combine_key_shares(auth_a.dealings(), shares).unwrap();
+        let issued_b = auth_b
+            .keygen(&mut rng, USER_ID, &parse_attrs(&["B.c:0"]))
+            .unwrap();
+        let usk = abe.assemble_key(vec![issued_a, issued_b]).unwrap();
+        for (policy, satisfied) in AUTHORITY_POLICIES {
+            let policy = Policy::parse(policy).unwrap();
+            let (k_enc, ct) = abe.encrypt(&mut rng, &mpk, &policy);
+            let k_dec = abe.decrypt(&usk, &ct);
+            assert_eq!(k_dec.is_some_and(|k| curve::Gt::eq(&k_enc, &k)), satisfied);
+        }
+    }
+
+    // a single server cannot issue keys, and shares of different keys cannot be combined
+    let share = |i: u64, gid: &str, attrs: &[&str]| {
+        let server = auth_a.server(i).unwrap();
+        server
+            .keygen(ark_std::test_rng(), gid, &parse_attrs(attrs))
+            .unwrap()
+    };
+    assert!(abe
+        .combine_key_shares(auth_a.dealings(), vec![share(1, USER_ID, &["A.a:0"])])
+        .is_err());
+    assert!(abe
+        .combine_key_shares(
+            auth_a.dealings(),
+            vec![share(1, USER_ID, &["A.a:0"]), share(1, USER_ID, &["A.a:0"]),]
+        )
+        .is_err());
+    assert!(abe
+        .combine_key_shares(
+            auth_a.dealings(),
+            vec![
+                share(1, USER_ID, &["A.a:0"]),
+                share(2, "OTHER_USER_ID", &["A.a:0"]),
+            ]
+        )
+        .is_err());
+    assert!(abe
+        .combine_key_shares(
+            auth_a.dealings(),
+            vec![share(1, USER_ID, &["A.a:0"]), share(2, USER_ID, &["A.b:0"]),]
+        )
+        .is_err());
+
+    // every share is checked against the dealings, and a bad one is blamed on its server
+    let mut bad = share(2, USER_ID, &["A.a:0"]);
+    bad.usk = abe
+        .scheme
+        .combine_usk_shares(vec![(ScalarField::from(2), bad.usk)]);
+    let err = abe
+        .combine_key_shares(auth_a.dealings(), vec![share(1, USER_ID, &["A.a:0"]), bad])
+        .err()
+        .unwrap();
+    assert!(err.contains("server 2"));
+    let mut relabeled = share(2, USER_ID, &["A.a:0"]);
+    relabeled.server = 3;
+    let err = abe
+        .combine_key_shares(
+            auth_a.dealings(),
+            vec![share(1, USER_ID, &["A.a:0"]), relabeled],
+        )
+        .err()
+        .unwrap();
+    assert!(err.contains("server 3"));
+    let mut other_threshold = share(2, USER_ID, &["A.a:0"]);
+    other_threshold.threshold = 3;
+    assert!(abe
+        .combine_key_shares(
+            auth_a.dealings(),
+            vec![share(1, USER_ID, &["A.a:0"]), other_threshold],
+        )
+        .is_err());
+    // the dealings of another authority with the same name
+    let auth_c = ThresholdAuthority::<S>::setup(&mut rng, "A", 2, 3).unwrap();
+    assert!(abe
+        .combine_key_shares(
+            auth_c.dealings(),
+            vec![share(1, USER_ID, &["A.a:0"]), share(2, USER_ID, &["A.a:0"])],
+        )
+        .is_err());
+    assert!(abe
+        .combine_key_shares(&[], vec![share(1, USER_ID, &["A.a:0"])])
+        .is_err());
+
+    // servers only issue attributes of their authority
+    let server = auth_a.server(1).unwrap();
+    assert!(server
+        .keygen(&mut rng, USER_ID, &parse_attrs(&["B.c:0"]))
+        .is_err());
+    assert!(auth_a.server(4).is_none());
+    assert!(ThresholdAuthority::<S>::setup(&mut rng, "A", 4, 3).is_err());
+    assert!(ThresholdAuthority::<S>::setup(&mut rng, "A", 0, 3).is_err());
+}
+
+#[test]
+fn threshold() {
+    for_each_scheme!(test_threshold);
+}
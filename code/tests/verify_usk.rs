@@ -0,0 +1,128 @@
+mod common;
+
+use abe_cubed::{
+    curve::{G, H},
+    policy::UserAttribute,
+    scheme::{Authority, Opt0, Opt1, Opt2, Opt3, Opt4, Opt5, Opt6, Scheme, ThresholdAuthority},
+};
+use ark_ec::Group;
+use common::{parse_attrs, USER_ID};
+
+fn test_verify_usk<S: Scheme>(corruptions: &[fn(&mut S::PartialUSK)]) {
+    let mut rng = ark_std::test_rng();
+    let auth_a = Authority::setup(&mut rng, S::new(), "A");
+    let auth_b = Authority::setup(&mut rng, S::new(), "B");
+    let abe = &auth_a.abe;
+    let mpk_a = auth_a.public_key();
+    let user_attrs = parse_attrs(&["A.a:0", "A.a:1", "A.b:1", "A.c:0"]);
+    let issue = || {
+        auth_a
+            .keygen(ark_std::test_rng(), USER_ID, &user_attrs)
+            .unwrap()
+    };
+
+    let issued = issue();
+    abe.verify_issued_key(&mpk_a, &issued).unwrap();
+    assert!(abe
+        .verify_issued_key(&auth_b.public_key(), &issued)
+        .is_err());
+
+    // the key is bound to its GID and attributes
+    let mut other = issue();
+    other.gid = String::from("OTHER_USER_ID");
+    assert!(abe.verify_issued_key(&mpk_a, &other).is_err());
+    let mut other = issue();
+    other.user_attrs.pop();
+    assert!(abe.verify_issued_key(&mpk_a, &other).is_err());
+    let mut other = issue();
+    other.user_attrs[0] = UserAttribute::parse("A.a:2").unwrap();
+    assert!(abe.verify_issued_key(&mpk_a, &other).is_err());
+
+    for corrupt in corruptions {
+        let mut issued = issue();
+        corrupt(&mut issued.usk);
+        assert!(abe.verify_issued_key(&mpk_a, &issued).is_err());
+    }
+
+    // keys combined from the shares of a threshold authority verify as well
+    let auth_c = ThresholdAuthority::<S>::setup(&mut rng, "C", 2, 3).unwrap();
+    let user_attrs = parse_attrs(&["C.a:0", "C.b:1"]);
+    let shares = [1, 3]
+        .iter()
+        .map(|&i| {
+            let server = auth_c.server(i).unwrap();
+            server.keygen(&mut rng, USER_ID, &user_attrs).unwrap()
+        })
+        .collect();
+    let issued = abe.combine_key_shares(auth_c.dealings(), shares).unwrap();
+    abe.verify_issued_key(&auth_c.public_key(), &issued)
+        .unwrap();
+}
+
+fn first<T>(mut values: impl Iterator<Item = T>) -> T {
+    values.next().unwrap()
+}
+
+#[test]
+fn verify_usk() {
+    test_verify_usk::<Opt0>(&[
+        |usk| *first(usk.k_1_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_4_map.values_mut()) += H::generator(),
+        |usk| *first(usk.k_5_map.values_mut()) += H::generator(),
+        |usk| {
+            let attr = first(usk.k_4_map.keys()).clone();
+            usk.k_4_map.remove(&attr);
+        },
+    ]);
+    test_verify_usk::<Opt1>(&[
+        |usk| *first(usk.k_1_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_4_map.values_mut()) += H::generator(),
+        |usk| *first(usk.k_5_map.values_mut()) += H::generator(),
+    ]);
+    test_verify_usk::<Opt2>(&[
+        |usk| *first(usk.k_1_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| usk.k_4_vec[0] += H::generator(),
+        |usk| usk.k_5_vec[0] += H::generator(),
+        |usk| usk.k_4_vec.clear(),
+    ]);
+    test_verify_usk::<Opt3>(&[
+        |usk| *first(usk.k_1_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| usk.k_4_vec[0] += H::generator(),
+        |usk| usk.k_5_vec[0] += H::generator(),
+    ]);
+    test_verify_usk::<Opt4>(&[
+        |usk| usk.k_1_1_vec[0] += G::generator(),
+        |usk| *first(usk.k_1_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| usk.k_4_vec[0] += H::generator(),
+        |usk| usk.k_5_vec[0] += H::generator(),
+    ]);
+    test_verify_usk::<Opt5>(&[
+        |usk| *first(usk.k_1_map.values_mut()) += G::generator(),
+        |usk| usk.k_2_1 += G::generator(),
+        |usk| *first(usk.k_2_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| usk.k_4_vec[0] += H::generator(),
+        |usk| usk.k_5_vec[0] += H::generator(),
+        |usk| usk.k_6 += H::generator(),
+    ]);
+    test_verify_usk::<Opt6>(&[
+        |usk| usk.k_1_1_vec[0] += G::generator(),
+        |usk| *first(usk.k_1_2_map.values_mut()) += G::generator(),
+        |usk| usk.k_2_1 += G::generator(),
+        |usk| *first(usk.k_2_2_map.values_mut()) += G::generator(),
+        |usk| *first(usk.k_3_map.values_mut()) += G::generator(),
+        |usk| usk.k_4_vec[0] += H::generator(),
+        |usk| usk.k_5_vec[0] += H::generator(),
+        |usk| usk.k_6 += H::generator(),
+    ]);
+}
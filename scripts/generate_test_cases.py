@@ -1,5 +1,6 @@
 #!/usr/bin/env python3
 
+import argparse
 import random
 
 class And:
@@ -53,6 +54,11 @@ class PolicyGenerator:
     labels = "abcde"
     attributes = "0123456"
 
+    def __init__(self, reuse=0.0):
+        # probability of reusing a literal which already occurs in the policy
+        self.reuse = reuse
+        self.pool = []
+
     def rand_auth(self):
         return random.choice(self.authorities)
 
@@ -67,7 +73,11 @@ class PolicyGenerator:
         return random.choices([True, False], weights=weights)[0]
 
     def rand_term(self):
-        t = Term(self.rand_auth(), self.rand_lbl(), self.rand_attr(), neg=self.rand_neg())
+        if self.pool and random.random() < self.reuse:
+            t = random.choice(self.pool)
+        else:
+            t = Term(self.rand_auth(), self.rand_lbl(), self.rand_attr(), neg=self.rand_neg())
+            self.pool.append(t)
         if t.neg:
             degree = random.randint(1, 4)
             return t, [Term(t.auth, t.lbl, t.attr, idx=d) for d in range(degree)]
@@ -82,6 +92,7 @@ class PolicyGenerator:
         # There are some corner-cases when this fails, e.g., 
         # if a negated attributes is used without negation elsewhere
         for _ in range(max_attempts):
+            self.pool = []
             policy, ok_set = self._generate_ok(fuel)
             if policy.eval(ok_set):
                 break
@@ -140,11 +151,11 @@ class PolicyGenerator:
         raise RuntimeError("Could not find a failing set for policy")
 
 
-def print_test_case(policy, user_attrs, idx, is_ok):
+def print_test_case(policy, user_attrs, idx, is_ok, prefix):
     user_attrs = ", ".join(f"\"{ua}\"" for ua in user_attrs)
     s = f"""
 #[test]
-fn opt0_generated_test_case_{idx:03}_{"ok" if is_ok else "fail"}() {{
+fn {prefix}_test_case_{idx:03}_{"ok" if is_ok else "fail"}() {{
    let user_attrs = vec![{user_attrs}];
    let policy = "{policy}";
    assert_decryption_{"ok" if is_ok else "fail"}(user_attrs, policy);
@@ -154,12 +165,20 @@ fn opt0_generated_test_case_{idx:03}_{"ok" if is_ok else "fail"}() {{
 
 
 def main():
-    generator = PolicyGenerator()
-    for i in range(100):
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--prefix", default="opt0_generated")
+    parser.add_argument("--count", type=int, default=100)
+    parser.add_argument("--reuse", type=float, default=0.0)
+    parser.add_argument("--seed", type=int, default=None)
+    args = parser.parse_args()
+    random.seed(args.seed)
+
+    generator = PolicyGenerator(reuse=args.reuse)
+    for i in range(args.count):
         policy, ok_set, fail_set = generator.generate(5)
-        print_test_case(policy, ok_set, i, is_ok=True)
+        print_test_case(policy, ok_set, i, is_ok=True, prefix=args.prefix)
         assert policy.eval(ok_set), "Ok set is wrong"
-        print_test_case(policy, fail_set, i, is_ok=False)
+        print_test_case(policy, fail_set, i, is_ok=False, prefix=args.prefix)
         assert not policy.eval(fail_set), "Fail set is wrong"
 
 